@@ -3,7 +3,7 @@
  * Task 4.1: 整合 Claude Vision API
  */
 
-import Anthropic from '@anthropic-ai/sdk';
+import { LlmClient, UsageAttribution } from './llm_client';
 import { EventEmitter } from 'events';
 import { AIServiceError } from '../error/error_types';
 
@@ -68,7 +68,7 @@ export interface RetryConfig {
 }
 
 export class ClaudeVisionClient extends EventEmitter {
-  private client: Anthropic;
+  private client: LlmClient;
   private config: {
     model: string;
     maxTokens: number;
@@ -81,9 +81,7 @@ export class ClaudeVisionClient extends EventEmitter {
   constructor(apiKey?: string) {
     super();
 
-    this.client = new Anthropic({
-      apiKey: apiKey || process.env.ANTHROPIC_API_KEY,
-    });
+    this.client = new LlmClient(apiKey);
 
     this.config = {
       model: 'claude-sonnet-4-20250514',
@@ -103,6 +101,13 @@ export class ClaudeVisionClient extends EventEmitter {
     this.initializePromptTemplates();
   }
 
+  /**
+   * 設定後續 AI 請求的用量歸屬（專案與工作階段）
+   */
+  setAttribution(attribution: UsageAttribution): void {
+    this.client.setAttribution(attribution);
+  }
+
   /**
    * 初始化 Prompt 模板
    */
@@ -202,7 +207,7 @@ export class ClaudeVisionClient extends EventEmitter {
 
       // Call Claude API with retry
       const response = await this.callWithRetry(async () => {
-        return await this.client.messages.create({
+        return await this.client.createMessage({
           model: this.config.model,
           max_tokens: this.config.maxTokens,
           temperature: this.config.temperature,
@@ -245,7 +250,7 @@ export class ClaudeVisionClient extends EventEmitter {
           rawResponse,
         },
         metadata: {
-          model: this.config.model,
+          model: response.model,
           tokensUsed: response.usage.input_tokens + response.usage.output_tokens,
           duration,
           timestamp: new Date(),
@@ -488,7 +493,7 @@ ${basePrompt}
         'base64'
       );
 
-      const response = await this.client.messages.create({
+      const response = await this.client.createMessage({
         model: this.config.model,
         max_tokens: 100,
         messages: [
@@ -3,7 +3,7 @@
  * Task 4.3: 實作內容去重與合併
  */
 
-import Anthropic from '@anthropic-ai/sdk';
+import { LlmClient, UsageAttribution } from './llm_client';
 import { Functionality, StepByStepGuide } from './content_structurer';
 import { EventEmitter } from 'events';
 import * as crypto from 'crypto';
@@ -65,16 +65,21 @@ export interface OptimizedManual {
 }
 
 export class ContentDeduplication extends EventEmitter {
-  private anthropic: Anthropic;
+  private anthropic: LlmClient;
   private similarityThreshold: number = 0.9;
   private embeddingsCache: Map<string, number[]> = new Map();
 
   constructor(apiKey?: string) {
     super();
 
-    this.anthropic = new Anthropic({
-      apiKey: apiKey || process.env.ANTHROPIC_API_KEY,
-    });
+    this.anthropic = new LlmClient(apiKey);
+  }
+
+  /**
+   * 設定後續 AI 請求的用量歸屬（專案與工作階段）
+   */
+  setAttribution(attribution: UsageAttribution): void {
+    this.anthropic.setAttribution(attribution);
   }
 
   /**
@@ -324,7 +329,7 @@ export class ContentDeduplication extends EventEmitter {
   private async calculateSemanticSimilarity(text1: string, text2: string): Promise<number> {
     try {
       // Use Claude to compare semantic similarity
-      const response = await this.anthropic.messages.create({
+      const response = await this.anthropic.createMessage({
         model: 'claude-sonnet-4-20250514',
         max_tokens: 200,
         temperature: 0,
@@ -0,0 +1,193 @@
+/**
+ * LLM Client
+ * 經由桌面版 API 閘道呼叫使用者選擇的模型服務（Claude、OpenAI 相容 API 或 Ollama），
+ * 並以標頭標示用量所屬的專案與工作階段
+ */
+
+import Anthropic from '@anthropic-ai/sdk';
+
+/** Read by the desktop API gateway (api_gateway.rs) to attribute usage; never sent upstream */
+export const PROJECT_HEADER = 'x-autodoc-project';
+export const SESSION_HEADER = 'x-autodoc-session';
+
+/** Values of `AUTODOC_LLM_PROVIDER` (llm_provider.rs) */
+export type LlmProvider = 'anthropic' | 'openai_compatible' | 'ollama';
+
+const PROVIDERS: LlmProvider[] = ['anthropic', 'openai_compatible', 'ollama'];
+
+export interface LlmSettings {
+  provider: LlmProvider;
+  /** Gateway URL; requests go to the same paths as at the provider */
+  baseUrl?: string;
+  apiKey?: string;
+  /** Overrides the model named in each request */
+  model?: string;
+}
+
+/**
+ * Settings passed by the desktop app. Without them (standalone runs) the
+ * Anthropic SDK's own ANTHROPIC_* variables apply.
+ */
+export function llmSettingsFromEnv(env: NodeJS.ProcessEnv = process.env): LlmSettings {
+  const provider = env.AUTODOC_LLM_PROVIDER || 'anthropic';
+  if (!PROVIDERS.includes(provider as LlmProvider)) {
+    throw new Error(`Unsupported LLM provider: ${provider}`);
+  }
+  return {
+    provider: provider as LlmProvider,
+    baseUrl: env.AUTODOC_LLM_BASE_URL || undefined,
+    apiKey: env.AUTODOC_LLM_API_KEY || undefined,
+    model: env.AUTODOC_LLM_MODEL || undefined,
+  };
+}
+
+export interface UsageAttribution {
+  projectId?: string;
+  sessionId?: string;
+}
+
+/** Project and session currently being explored; used by clients without their own attribution */
+let activeAttribution: UsageAttribution = {};
+
+/**
+ * 設定目前進行中的專案與探索工作階段，之後所有 LLM 請求的用量都會歸屬於它
+ */
+export function setActiveAttribution(attribution: UsageAttribution): void {
+  activeAttribution = { ...attribution };
+}
+
+export function attributionHeaders(attribution: UsageAttribution): Record<string, string> {
+  const headers: Record<string, string> = {};
+  if (attribution.projectId) {
+    headers[PROJECT_HEADER] = attribution.projectId;
+  }
+  if (attribution.sessionId) {
+    headers[SESSION_HEADER] = attribution.sessionId;
+  }
+  return headers;
+}
+
+type ChatContent = string | Array<Record<string, unknown>>;
+
+/**
+ * Anthropic message content to chat completions content; images become data URLs
+ */
+function toChatContent(content: Anthropic.MessageParam['content']): ChatContent {
+  if (typeof content === 'string') {
+    return content;
+  }
+  return content.map((block) => {
+    if (block.type === 'image' && block.source.type === 'base64') {
+      return {
+        type: 'image_url',
+        image_url: { url: `data:${block.source.media_type};base64,${block.source.data}` },
+      };
+    }
+    if (block.type === 'text') {
+      return { type: 'text', text: block.text };
+    }
+    throw new Error(`Content block "${block.type}" is not supported by this LLM provider`);
+  });
+}
+
+export class LlmClient {
+  private settings: LlmSettings;
+  private anthropic: Anthropic;
+  private apiKey?: string;
+  private attribution: UsageAttribution = {};
+
+  constructor(apiKey?: string, settings: LlmSettings = llmSettingsFromEnv()) {
+    this.settings = settings;
+    this.apiKey = apiKey || settings.apiKey;
+    this.anthropic = new Anthropic({
+      apiKey: this.apiKey || process.env.ANTHROPIC_API_KEY,
+      baseURL: settings.baseUrl,
+    });
+  }
+
+  /**
+   * 設定後續請求的用量歸屬
+   */
+  setAttribution(attribution: UsageAttribution): void {
+    this.attribution = { ...attribution };
+  }
+
+  /**
+   * 自行設定的歸屬優先，未設定的欄位沿用目前的探索工作階段
+   */
+  private currentAttribution(): UsageAttribution {
+    return {
+      projectId: this.attribution.projectId ?? activeAttribution.projectId,
+      sessionId: this.attribution.sessionId ?? activeAttribution.sessionId,
+    };
+  }
+
+  /**
+   * 以 Messages API 的格式呼叫模型；非 Anthropic 服務會轉成 chat completions 請求
+   */
+  async createMessage(body: Anthropic.MessageCreateParamsNonStreaming): Promise<Anthropic.Message> {
+    const request = { ...body, model: this.settings.model || body.model };
+    if (this.settings.provider === 'anthropic') {
+      return this.anthropic.messages.create(request, {
+        headers: attributionHeaders(this.currentAttribution()),
+      });
+    }
+    return this.createChatCompletion(request);
+  }
+
+  private async createChatCompletion(
+    body: Anthropic.MessageCreateParamsNonStreaming
+  ): Promise<Anthropic.Message> {
+    if (!this.settings.baseUrl) {
+      throw new Error(`AUTODOC_LLM_BASE_URL is required for the ${this.settings.provider} provider`);
+    }
+    // OpenAI-compatible base URLs include /v1; Ollama serves the same API under /v1
+    const path = this.settings.provider === 'ollama' ? '/v1/chat/completions' : '/chat/completions';
+
+    const messages: Array<{ role: string; content: ChatContent }> = [];
+    if (typeof body.system === 'string' && body.system) {
+      messages.push({ role: 'system', content: body.system });
+    }
+    for (const message of body.messages) {
+      messages.push({ role: message.role, content: toChatContent(message.content) });
+    }
+
+    const headers: Record<string, string> = {
+      'content-type': 'application/json',
+      ...attributionHeaders(this.currentAttribution()),
+    };
+    if (this.apiKey) {
+      headers.authorization = `Bearer ${this.apiKey}`;
+    }
+
+    const response = await fetch(`${this.settings.baseUrl.replace(/\/+$/, '')}${path}`, {
+      method: 'POST',
+      headers,
+      body: JSON.stringify({
+        model: body.model,
+        max_tokens: body.max_tokens,
+        temperature: body.temperature,
+        messages,
+      }),
+    });
+    if (!response.ok) {
+      throw new Error(`LLM request failed with status ${response.status}: ${await response.text()}`);
+    }
+
+    const completion: any = await response.json();
+    const choice = completion.choices?.[0];
+    return {
+      id: completion.id ?? '',
+      type: 'message',
+      role: 'assistant',
+      model: completion.model ?? body.model,
+      content: [{ type: 'text', text: choice?.message?.content ?? '' }],
+      stop_reason: choice?.finish_reason === 'length' ? 'max_tokens' : 'end_turn',
+      stop_sequence: null,
+      usage: {
+        input_tokens: completion.usage?.prompt_tokens ?? 0,
+        output_tokens: completion.usage?.completion_tokens ?? 0,
+      },
+    } as Anthropic.Message;
+  }
+}
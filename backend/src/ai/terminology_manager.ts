@@ -3,7 +3,7 @@
  * Task 4.4: 建立專業術語管理
  */
 
-import Anthropic from '@anthropic-ai/sdk';
+import { LlmClient, UsageAttribution } from './llm_client';
 import { EventEmitter } from 'events';
 
 export interface Term {
@@ -64,16 +64,14 @@ export interface Glossary {
 }
 
 export class TerminologyManager extends EventEmitter {
-  private anthropic: Anthropic;
+  private anthropic: LlmClient;
   private database: TerminologyDatabase;
   private stopWords: Set<string>;
 
   constructor(apiKey?: string) {
     super();
 
-    this.anthropic = new Anthropic({
-      apiKey: apiKey || process.env.ANTHROPIC_API_KEY,
-    });
+    this.anthropic = new LlmClient(apiKey);
 
     this.database = {
       terms: new Map(),
@@ -122,6 +120,13 @@ export class TerminologyManager extends EventEmitter {
     ]);
   }
 
+  /**
+   * 設定後續 AI 請求的用量歸屬（專案與工作階段）
+   */
+  setAttribution(attribution: UsageAttribution): void {
+    this.anthropic.setAttribution(attribution);
+  }
+
   /**
    * 提取專業術語
    */
@@ -165,7 +170,7 @@ export class TerminologyManager extends EventEmitter {
    */
   private async extractTerminologyWithAI(content: string): Promise<Term[]> {
     try {
-      const response = await this.anthropic.messages.create({
+      const response = await this.anthropic.createMessage({
         model: 'claude-sonnet-4-20250514',
         max_tokens: 4096,
         temperature: 0,
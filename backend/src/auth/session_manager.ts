@@ -5,6 +5,7 @@
 
 import { EventEmitter } from 'events';
 import { Credential, CredentialManager } from './credential_manager';
+import { SessionVaultClient, VaultCookie } from './session_vault_client';
 
 /**
  * Session 狀態
@@ -19,6 +20,8 @@ export interface Session {
   credentialId: string;
   url: string;
   status: SessionStatus;
+  /** 桌面配置 auth.targets 的 ID；有值時登入狀態保存在桌面版的 Session 保存庫 */
+  targetId?: string;
 
   // Cookie 資訊
   cookies: Array<{
@@ -71,16 +74,19 @@ export class SessionManager extends EventEmitter {
   private credentialManager: CredentialManager;
   private config: SessionConfig;
   private cdpWrapper: any; // CDP Wrapper for browser control
+  private vault: SessionVaultClient | null;
 
   constructor(
     credentialManager: CredentialManager,
     cdpWrapper: any,
-    config?: Partial<SessionConfig>
+    config?: Partial<SessionConfig>,
+    vault: SessionVaultClient | null = SessionVaultClient.fromEnv()
   ) {
     super();
 
     this.credentialManager = credentialManager;
     this.cdpWrapper = cdpWrapper;
+    this.vault = vault;
 
     this.config = {
       maxSessions: 10,
@@ -94,9 +100,9 @@ export class SessionManager extends EventEmitter {
   }
 
   /**
-   * 使用認證登入
+   * 使用認證登入；指定 targetId 時先嘗試還原已保存的 Session，登入後再保存
    */
-  async login(credentialId: string, url: string): Promise<LoginResult> {
+  async login(credentialId: string, url: string, targetId?: string): Promise<LoginResult> {
     console.log(`🔐 Attempting login with credential: ${credentialId}`);
 
     try {
@@ -108,6 +114,19 @@ export class SessionManager extends EventEmitter {
         };
       }
 
+      if (targetId) {
+        const restored = await this.restoreFromVault(targetId, credentialId, url);
+        if (restored) {
+          this.sessions.set(restored.id, restored);
+          console.log(`✅ Restored saved session: ${restored.id}`);
+          this.emit('login_success', { session: restored });
+          return {
+            success: true,
+            session: restored,
+          };
+        }
+      }
+
       // 根據認證類型執行登入
       let session: Session | null = null;
 
@@ -136,6 +155,10 @@ export class SessionManager extends EventEmitter {
       }
 
       if (session) {
+        if (targetId) {
+          session.targetId = targetId;
+          await this.saveToVault(targetId);
+        }
         this.sessions.set(session.id, session);
 
         console.log(`✅ Login successful: ${session.id}`);
@@ -382,6 +405,88 @@ export class SessionManager extends EventEmitter {
     return session;
   }
 
+  /**
+   * 還原桌面版保存的 cookie 與 localStorage；已失效的 Session 會從保存庫移除
+   */
+  private async restoreFromVault(
+    targetId: string,
+    credentialId: string,
+    url: string
+  ): Promise<Session | null> {
+    if (!this.vault) {
+      return null;
+    }
+
+    try {
+      const saved = await this.vault.load(targetId);
+      if (!saved) {
+        return null;
+      }
+      console.log(`  💾 Restoring saved session for target: ${targetId}`);
+
+      // localStorage belongs to an origin, so it is written from one of its pages
+      await this.cdpWrapper.navigate(saved.origin);
+      for (const cookie of saved.cookies) {
+        await this.cdpWrapper.setCookie({
+          name: cookie.name,
+          value: cookie.value,
+          domain: cookie.domain,
+          path: cookie.path,
+          expires: cookie.expires > 0 ? cookie.expires : undefined,
+          httpOnly: cookie.httpOnly,
+          secure: cookie.secure,
+          sameSite: cookie.sameSite,
+        });
+      }
+      await this.cdpWrapper.evaluate(
+        `(entries => { for (const [k, v] of Object.entries(entries)) localStorage.setItem(k, v); })(${JSON.stringify(saved.localStorage)})`
+      );
+
+      await this.cdpWrapper.navigate(url);
+      await this.cdpWrapper.waitForPageLoad();
+
+      if (!(await this.verifyLoginSuccess())) {
+        console.log(`  🗑️  Saved session for target ${targetId} is no longer logged in`);
+        await this.vault.invalidate(targetId);
+        return null;
+      }
+
+      const session = this.createSession(credentialId, url, await this.cdpWrapper.getCookies());
+      session.targetId = targetId;
+      return session;
+    } catch (error) {
+      console.warn(`⚠️  Could not restore saved session for target ${targetId}:`, error);
+      return null;
+    }
+  }
+
+  /**
+   * 將目前瀏覽器的 cookie 與 localStorage 保存到桌面版
+   */
+  private async saveToVault(targetId: string): Promise<void> {
+    if (!this.vault) {
+      return;
+    }
+
+    try {
+      const cookies: any[] = await this.cdpWrapper.getCookies();
+      const origin: string = await this.cdpWrapper.evaluate('location.origin');
+      const localStorage: Record<string, string> = await this.cdpWrapper.evaluate(
+        'Object.fromEntries(Array.from({ length: localStorage.length }, (_, i) => localStorage.key(i)).map(k => [k, localStorage.getItem(k)]))'
+      );
+
+      await this.vault.save(targetId, {
+        origin,
+        cookies: cookies.map(toVaultCookie),
+        localStorage: localStorage || {},
+      });
+      console.log(`  💾 Saved session for target: ${targetId}`);
+    } catch (error) {
+      // Not saving only costs a login next time
+      console.warn(`⚠️  Could not save session for target ${targetId}:`, error);
+    }
+  }
+
   /**
    * 驗證登入成功
    */
@@ -503,6 +608,11 @@ export class SessionManager extends EventEmitter {
       // Clear cookies
       await this.cdpWrapper.clearCookies();
 
+      // The saved copy is logged out too
+      if (session.targetId && this.vault) {
+        await this.vault.invalidate(session.targetId);
+      }
+
       // Remove session
       this.sessions.delete(sessionId);
 
@@ -548,6 +658,10 @@ export class SessionManager extends EventEmitter {
       session.metadata.lastAccessedAt = new Date();
       session.metadata.expiresAt = new Date(Date.now() + this.config.sessionTimeout);
 
+      if (session.targetId) {
+        await this.saveToVault(session.targetId);
+      }
+
       console.log(`✅ Session refreshed: ${sessionId}`);
       this.emit('session_refreshed', { session });
 
@@ -621,3 +735,19 @@ export class SessionManager extends EventEmitter {
     return count;
   }
 }
+
+/**
+ * CDP cookie 轉為保存庫格式
+ */
+function toVaultCookie(cookie: any): VaultCookie {
+  return {
+    name: cookie.name,
+    value: cookie.value,
+    domain: cookie.domain,
+    path: cookie.path || '/',
+    expires: typeof cookie.expires === 'number' ? cookie.expires : -1,
+    httpOnly: Boolean(cookie.httpOnly),
+    secure: Boolean(cookie.secure),
+    sameSite: cookie.sameSite,
+  };
+}
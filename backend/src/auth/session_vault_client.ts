@@ -0,0 +1,82 @@
+/**
+ * Session Vault Client
+ * 透過桌面版 API 閘道保存與還原目標應用程式的登入狀態（cookie 與 localStorage），
+ * 加密與到期判斷由桌面端 session_vault.rs 負責
+ */
+
+/** A cookie as reported by the DevTools protocol (`Network.getAllCookies`) */
+export interface VaultCookie {
+  name: string;
+  value: string;
+  domain: string;
+  path: string;
+  /** Seconds since the epoch; -1 for a session cookie */
+  expires: number;
+  httpOnly: boolean;
+  secure: boolean;
+  sameSite?: string;
+}
+
+export interface BrowserSession {
+  origin: string;
+  cookies: VaultCookie[];
+  localStorage: Record<string, string>;
+}
+
+/** Same path as `SESSIONS_ROUTE` in api_gateway.rs */
+const SESSIONS_PATH = '/autodoc/sessions';
+
+export class SessionVaultClient {
+  private baseUrl: string;
+  private token: string;
+
+  constructor(baseUrl: string, token: string) {
+    this.baseUrl = baseUrl.replace(/\/+$/, '');
+    this.token = token;
+  }
+
+  /**
+   * 由桌面版傳入的閘道位址建立；獨立執行時沒有保存庫
+   */
+  static fromEnv(env: NodeJS.ProcessEnv = process.env): SessionVaultClient | null {
+    if (!env.AUTODOC_GATEWAY_URL || !env.AUTODOC_GATEWAY_TOKEN) {
+      return null;
+    }
+    return new SessionVaultClient(env.AUTODOC_GATEWAY_URL, env.AUTODOC_GATEWAY_TOKEN);
+  }
+
+  /**
+   * 仍有效的已保存 Session；沒有或已過期時回傳 null
+   */
+  async load(targetId: string): Promise<BrowserSession | null> {
+    const response = await this.request('GET', targetId);
+    if (response.status === 404) {
+      return null;
+    }
+    return (await response.json()) as BrowserSession;
+  }
+
+  async save(targetId: string, session: BrowserSession): Promise<void> {
+    await this.request('PUT', targetId, JSON.stringify(session));
+  }
+
+  async invalidate(targetId: string): Promise<void> {
+    await this.request('DELETE', targetId);
+  }
+
+  private async request(method: string, targetId: string, body?: string): Promise<Response> {
+    const response = await fetch(`${this.baseUrl}${SESSIONS_PATH}/${encodeURIComponent(targetId)}`, {
+      method,
+      headers: {
+        'x-api-key': this.token,
+        ...(body ? { 'content-type': 'application/json' } : {}),
+      },
+      body,
+    });
+    // A GET 404 only means nothing is saved
+    if (!response.ok && !(method === 'GET' && response.status === 404)) {
+      throw new Error(`Session vault request failed with status ${response.status}: ${await response.text()}`);
+    }
+    return response;
+  }
+}
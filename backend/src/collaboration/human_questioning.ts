@@ -4,7 +4,7 @@
  */
 
 import { EventEmitter } from 'events';
-import Anthropic from '@anthropic-ai/sdk';
+import { LlmClient, UsageAttribution } from '../ai/llm_client';
 
 export interface HumanQuestion {
   id: string;
@@ -41,7 +41,7 @@ export interface PausedState {
 }
 
 export class HumanQuestioningSystem extends EventEmitter {
-  private anthropic: Anthropic;
+  private anthropic: LlmClient;
   private isPaused: boolean = false;
   private pausedState: PausedState | null = null;
   private questionHistory: Array<{ question: HumanQuestion; answer: AIAnswer }> = [];
@@ -50,15 +50,19 @@ export class HumanQuestioningSystem extends EventEmitter {
   constructor() {
     super();
 
-    // Initialize Claude API client
-    const apiKey = process.env.ANTHROPIC_API_KEY;
-    if (!apiKey) {
-      console.warn('ANTHROPIC_API_KEY not set - AI answering will not work');
+    // Initialize LLM client; the key comes from AUTODOC_LLM_API_KEY or ANTHROPIC_API_KEY
+    if (!process.env.AUTODOC_LLM_API_KEY && !process.env.ANTHROPIC_API_KEY) {
+      console.warn('No LLM API key set - AI answering will not work');
     }
 
-    this.anthropic = new Anthropic({
-      apiKey: apiKey || 'dummy-key',
-    });
+    this.anthropic = new LlmClient();
+  }
+
+  /**
+   * 設定後續 AI 請求的用量歸屬（專案與工作階段）
+   */
+  setAttribution(attribution: UsageAttribution): void {
+    this.anthropic.setAttribution(attribution);
   }
 
   /**
@@ -116,7 +120,7 @@ export class HumanQuestioningSystem extends EventEmitter {
 
     try {
       // Call Claude API
-      const response = await this.anthropic.messages.create({
+      const response = await this.anthropic.createMessage({
         model: process.env.CLAUDE_MODEL || 'claude-sonnet-4-20250514',
         max_tokens: 2048,
         messages: [{
@@ -8,6 +8,7 @@
 import { EventEmitter } from 'events';
 import * as fs from 'fs/promises';
 import * as path from 'path';
+import { reportTelemetry } from '../utils/desktop_telemetry';
 
 /**
  * Error severity levels
@@ -430,6 +431,9 @@ export class GlobalErrorHandler extends EventEmitter {
     if (this.config.enableRemoteLogging && this.config.remoteEndpoint) {
       await this.logToRemote(error);
     }
+
+    // Desktop usage statistics; the desktop app scrubs the detail
+    await reportTelemetry({ event: 'error', code: error.category, detail: error.message });
   }
 
   /**
@@ -10,11 +10,15 @@ import { ExplorationStrategy, ExplorationStrategyType, ExplorationConfig } from
 import { ExplorationExecutor } from './exploration_executor';
 import { ExplorationVisualization, ProgressStats } from './visualization';
 import { PageSnapshot } from '../types';
-import { ExplorationError } from '../error/error_types';
+import { AutoDocError, ExplorationError } from '../error/error_types';
+import { reportTelemetry } from '../utils/desktop_telemetry';
+import { setActiveAttribution } from '../ai/llm_client';
 import { WebSocketServer } from 'ws';
 
 export interface ExplorationOptions {
   entryUrl: string;
+  /** Project the exploration belongs to; LLM usage is attributed to it */
+  projectId?: string;
   strategy?: ExplorationStrategyType;
   maxDepth?: number;
   maxPages?: number;
@@ -65,6 +69,7 @@ export class ExplorationEngine extends EventEmitter {
         snapshots: [],
         errors: [],
       };
+      setActiveAttribution({ projectId: options.projectId, sessionId: this.currentSession.id });
 
       // Ensure browser is launched
       if (!this.browserManager.isReady()) {
@@ -106,6 +111,7 @@ export class ExplorationEngine extends EventEmitter {
 
       console.log('✅ Exploration completed');
       this.emit('exploration_complete', this.currentSession);
+      await this.reportOutcome(this.isStopped ? 'cancelled' : 'completed');
 
       // Generate final stats
       if (this.strategy) {
@@ -121,11 +127,35 @@ export class ExplorationEngine extends EventEmitter {
         this.currentSession.status = 'failed';
         this.currentSession.endTime = new Date();
       }
+      await this.reportOutcome(
+        'failed',
+        error instanceof AutoDocError ? error.code : 'EXPLORATION_ERROR'
+      );
 
       throw new ExplorationError('Exploration failed', { error });
     }
   }
 
+  /**
+   * 將探索時間與頁數記入桌面版使用統計
+   */
+  private async reportOutcome(
+    outcome: 'completed' | 'failed' | 'cancelled',
+    errorCode?: string
+  ): Promise<void> {
+    if (!this.currentSession) {
+      return;
+    }
+    const { startTime, endTime, snapshots } = this.currentSession;
+    await reportTelemetry({
+      event: 'exploration',
+      duration_ms: (endTime ?? new Date()).getTime() - startTime.getTime(),
+      pages: snapshots.length,
+      outcome,
+      error_code: errorCode,
+    });
+  }
+
   /**
    * 主探索循環
    */
@@ -57,6 +57,7 @@ export interface SnapshotMetadata {
   entryUrl: string;
   baseDomain: string;
   siteName?: string;
+  targetId?: string; // 目標應用程式 ID（對應桌面配置 auth.targets）
 
   // 探索配置
   explorationConfig: {
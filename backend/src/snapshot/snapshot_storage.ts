@@ -8,6 +8,7 @@ import * as fs from 'fs/promises';
 import * as path from 'path';
 import * as zlib from 'zlib';
 import { promisify } from 'util';
+import { decompress as decompressZstd } from 'fzstd';
 import {
   ProjectSnapshot,
   SnapshotFiles,
@@ -18,6 +19,32 @@ import {
 const gzip = promisify(zlib.gzip);
 const gunzip = promisify(zlib.gunzip);
 
+/**
+ * zstd container written by the desktop app (snapshot_store.rs):
+ * 4-byte magic "ADZS", 1-byte format version, u64 LE original length, zstd frame
+ */
+const ZSTD_MAGIC = Buffer.from('ADZS');
+const ZSTD_HEADER_LENGTH = 13;
+
+export function decodeZstdContainer(data: Buffer): Buffer {
+  if (
+    data.length < ZSTD_HEADER_LENGTH ||
+    !data.subarray(0, ZSTD_MAGIC.length).equals(ZSTD_MAGIC) ||
+    data[4] !== 1
+  ) {
+    throw new Error('Unsupported compressed snapshot format');
+  }
+  const frame = data.subarray(ZSTD_HEADER_LENGTH);
+  // zlib.zstdDecompressSync only ships with Node 22.15+; the packaged backend runs
+  // on Node 18/20, so fall back to the pure JS decoder there
+  const native = (zlib as any).zstdDecompressSync as ((buf: Buffer) => Buffer) | undefined;
+  const decoded = native ? native(frame) : Buffer.from(decompressZstd(frame));
+  if (BigInt(decoded.length) !== data.readBigUInt64LE(ZSTD_MAGIC.length + 1)) {
+    throw new Error('Compressed snapshot is truncated');
+  }
+  return decoded;
+}
+
 export interface StorageConfig {
   baseDir: string;
   compression: boolean;
@@ -444,6 +471,8 @@ export class SnapshotStorage extends EventEmitter {
 
   private async writeFile(filePath: string, content: string, compress: boolean): Promise<void> {
     const data = Buffer.from(content, 'utf-8');
+    // A zstd copy from the desktop app would otherwise shadow the new content
+    await fs.rm(filePath + '.zst', { force: true });
     if (compress) {
       const compressed = await gzip(data, { level: this.config.compressionLevel });
       await fs.writeFile(filePath + '.gz', compressed);
@@ -453,6 +482,13 @@ export class SnapshotStorage extends EventEmitter {
   }
 
   private async readFile(filePath: string): Promise<string> {
+    // Files recompressed by the desktop app take precedence
+    const zstdPath = filePath + '.zst';
+    if (await this.exists(zstdPath)) {
+      const compressed = await fs.readFile(zstdPath);
+      return decodeZstdContainer(compressed).toString('utf-8');
+    }
+
     // Try compressed first
     const compressedPath = filePath + '.gz';
     const compressedExists = await this.exists(compressedPath);
@@ -0,0 +1,50 @@
+/**
+ * Desktop Telemetry
+ * 透過桌面版 API 閘道把探索與錯誤事件寫入本機使用統計，
+ * 是否記錄由桌面端 enable_telemetry 設定決定，資料不會送出本機
+ */
+
+/** Same path as `TELEMETRY_ROUTE` in api_gateway.rs */
+const TELEMETRY_PATH = '/autodoc/telemetry';
+/** Reporting must never hold up an exploration */
+const REPORT_TIMEOUT_MS = 2000;
+
+/** Same shape as `TelemetryEvent` in telemetry.rs */
+export type TelemetryEvent =
+  | {
+      event: 'exploration';
+      duration_ms: number;
+      pages: number;
+      outcome: 'completed' | 'failed' | 'cancelled';
+      error_code?: string;
+    }
+  | {
+      event: 'error';
+      code: string;
+      detail?: string;
+    };
+
+/**
+ * 回報事件；獨立執行（沒有閘道）時不做任何事，失敗也只會被忽略
+ */
+export async function reportTelemetry(
+  event: TelemetryEvent,
+  env: NodeJS.ProcessEnv = process.env
+): Promise<void> {
+  if (!env.AUTODOC_GATEWAY_URL || !env.AUTODOC_GATEWAY_TOKEN) {
+    return;
+  }
+  try {
+    await fetch(`${env.AUTODOC_GATEWAY_URL.replace(/\/+$/, '')}${TELEMETRY_PATH}`, {
+      method: 'POST',
+      headers: {
+        'x-api-key': env.AUTODOC_GATEWAY_TOKEN,
+        'content-type': 'application/json',
+      },
+      body: JSON.stringify(event),
+      signal: AbortSignal.timeout(REPORT_TIMEOUT_MS),
+    });
+  } catch {
+    // Usage statistics are best effort
+  }
+}
@@ -0,0 +1,79 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as http from 'http';
+import { AddressInfo } from 'net';
+import { GlobalErrorHandler, ErrorCategory } from '../../src/error/error_handler';
+import { reportTelemetry } from '../../src/utils/desktop_telemetry';
+
+const TOKEN = 'gateway-session-token';
+
+describe('Desktop telemetry - error and exploration events', () => {
+  let server: http.Server;
+  let received: Array<{ url?: string; apiKey?: string | string[]; body: any }>;
+  let previousEnv: { url?: string; token?: string };
+
+  beforeEach(async () => {
+    received = [];
+    // Stands in for the telemetry route of the desktop API gateway
+    server = http.createServer((req, res) => {
+      let data = '';
+      req.on('data', chunk => (data += chunk));
+      req.on('end', () => {
+        received.push({ url: req.url, apiKey: req.headers['x-api-key'], body: JSON.parse(data) });
+        res.writeHead(204);
+        res.end();
+      });
+    });
+    await new Promise<void>(resolve => server.listen(0, '127.0.0.1', resolve));
+
+    previousEnv = { url: process.env.AUTODOC_GATEWAY_URL, token: process.env.AUTODOC_GATEWAY_TOKEN };
+    process.env.AUTODOC_GATEWAY_URL = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+    process.env.AUTODOC_GATEWAY_TOKEN = TOKEN;
+  });
+
+  afterEach(async () => {
+    for (const [name, value] of [
+      ['AUTODOC_GATEWAY_URL', previousEnv.url],
+      ['AUTODOC_GATEWAY_TOKEN', previousEnv.token],
+    ] as const) {
+      if (value === undefined) {
+        delete process.env[name];
+      } else {
+        process.env[name] = value;
+      }
+    }
+    await new Promise(resolve => server.close(resolve));
+  });
+
+  it('should report handled errors to the desktop app', async () => {
+    const handler = new GlobalErrorHandler({
+      enableFileLogging: false,
+      enableConsoleOutput: false,
+      autoRecovery: false,
+    });
+
+    await handler.handleError(new Error('Network request failed: ECONNREFUSED'));
+
+    expect(received).toHaveLength(1);
+    expect(received[0].url).toBe('/autodoc/telemetry');
+    expect(received[0].apiKey).toBe(TOKEN);
+    expect(received[0].body).toEqual({
+      event: 'error',
+      code: ErrorCategory.NETWORK,
+      detail: 'Network request failed: ECONNREFUSED',
+    });
+  });
+
+  it('should do nothing when not started by the desktop app', async () => {
+    await reportTelemetry({ event: 'error', code: 'unknown' }, {});
+    expect(received).toHaveLength(0);
+  });
+
+  it('should ignore an unreachable gateway', async () => {
+    await expect(
+      reportTelemetry(
+        { event: 'exploration', duration_ms: 10, pages: 0, outcome: 'cancelled' },
+        { AUTODOC_GATEWAY_URL: 'http://127.0.0.1:1', AUTODOC_GATEWAY_TOKEN: TOKEN }
+      )
+    ).resolves.toBeUndefined();
+  });
+});
@@ -0,0 +1,84 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as http from 'http';
+import { AddressInfo } from 'net';
+import { ClaudeVisionClient } from '../../src/ai/claude_vision_client';
+import { PROJECT_HEADER, SESSION_HEADER, setActiveAttribution } from '../../src/ai/llm_client';
+
+describe('LlmClient - usage attribution', () => {
+  let server: http.Server;
+  let received: http.IncomingHttpHeaders[];
+  let previousBaseUrl: string | undefined;
+
+  beforeEach(async () => {
+    received = [];
+    // Stands in for the desktop API gateway
+    server = http.createServer((req, res) => {
+      received.push(req.headers);
+      req.resume();
+      req.on('end', () => {
+        res.writeHead(200, { 'content-type': 'application/json' });
+        res.end(
+          JSON.stringify({
+            id: 'msg_1',
+            type: 'message',
+            role: 'assistant',
+            model: 'claude-sonnet-4-20250514',
+            content: [{ type: 'text', text: 'OK' }],
+            stop_reason: 'end_turn',
+            usage: { input_tokens: 10, output_tokens: 1 },
+          })
+        );
+      });
+    });
+    await new Promise<void>(resolve => server.listen(0, '127.0.0.1', resolve));
+
+    previousBaseUrl = process.env.ANTHROPIC_BASE_URL;
+    process.env.ANTHROPIC_BASE_URL = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+  });
+
+  afterEach(async () => {
+    if (previousBaseUrl === undefined) {
+      delete process.env.ANTHROPIC_BASE_URL;
+    } else {
+      process.env.ANTHROPIC_BASE_URL = previousBaseUrl;
+    }
+    await new Promise(resolve => server.close(resolve));
+  });
+
+  it('should send the project and session with every request', async () => {
+    const client = new ClaudeVisionClient('gateway-session-token');
+    client.setAttribution({ projectId: 'proj-a', sessionId: 'sess-1' });
+
+    expect(await client.testConnection()).toBe(true);
+    expect(received).toHaveLength(1);
+    expect(received[0][PROJECT_HEADER]).toBe('proj-a');
+    expect(received[0][SESSION_HEADER]).toBe('sess-1');
+    expect(received[0]['x-api-key']).toBe('gateway-session-token');
+  });
+
+  it('should attribute requests to the running exploration', async () => {
+    setActiveAttribution({ projectId: 'proj-b', sessionId: 'session-1' });
+    try {
+      const client = new ClaudeVisionClient('gateway-session-token');
+      expect(await client.testConnection()).toBe(true);
+      expect(received[0][PROJECT_HEADER]).toBe('proj-b');
+      expect(received[0][SESSION_HEADER]).toBe('session-1');
+
+      // A client's own attribution wins
+      client.setAttribution({ projectId: 'proj-c' });
+      expect(await client.testConnection()).toBe(true);
+      expect(received[1][PROJECT_HEADER]).toBe('proj-c');
+      expect(received[1][SESSION_HEADER]).toBe('session-1');
+    } finally {
+      setActiveAttribution({});
+    }
+  });
+
+  it('should omit the headers until attribution is set', async () => {
+    const client = new ClaudeVisionClient('gateway-session-token');
+
+    expect(await client.testConnection()).toBe(true);
+    expect(received[0][PROJECT_HEADER]).toBeUndefined();
+    expect(received[0][SESSION_HEADER]).toBeUndefined();
+  });
+});
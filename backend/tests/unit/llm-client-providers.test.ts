@@ -0,0 +1,144 @@
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as http from 'http';
+import { AddressInfo } from 'net';
+import { LlmClient, llmSettingsFromEnv, PROJECT_HEADER } from '../../src/ai/llm_client';
+
+interface ReceivedRequest {
+  url: string;
+  headers: http.IncomingHttpHeaders;
+  body: any;
+}
+
+const IMAGE_MESSAGE = {
+  model: 'claude-sonnet-4-20250514',
+  max_tokens: 100,
+  messages: [
+    {
+      role: 'user' as const,
+      content: [
+        {
+          type: 'image' as const,
+          source: { type: 'base64' as const, media_type: 'image/png' as const, data: 'iVBORw0K' },
+        },
+        { type: 'text' as const, text: 'Describe this page' },
+      ],
+    },
+  ],
+};
+
+describe('LlmClient - providers selected in the desktop app', () => {
+  let server: http.Server;
+  let baseUrl: string;
+  let received: ReceivedRequest[];
+
+  beforeEach(async () => {
+    received = [];
+    // Stands in for the desktop API gateway; answers in whichever API was called
+    server = http.createServer((req, res) => {
+      let data = '';
+      req.on('data', chunk => (data += chunk));
+      req.on('end', () => {
+        received.push({ url: req.url || '', headers: req.headers, body: JSON.parse(data) });
+        res.writeHead(200, { 'content-type': 'application/json' });
+        if (req.url?.endsWith('/chat/completions')) {
+          res.end(
+            JSON.stringify({
+              id: 'chatcmpl-1',
+              model: 'served-model',
+              choices: [{ message: { role: 'assistant', content: 'A login page' }, finish_reason: 'stop' }],
+              usage: { prompt_tokens: 12, completion_tokens: 3 },
+            })
+          );
+        } else {
+          res.end(
+            JSON.stringify({
+              id: 'msg_1',
+              type: 'message',
+              role: 'assistant',
+              model: 'served-model',
+              content: [{ type: 'text', text: 'A login page' }],
+              stop_reason: 'end_turn',
+              usage: { input_tokens: 12, output_tokens: 3 },
+            })
+          );
+        }
+      });
+    });
+    await new Promise<void>(resolve => server.listen(0, '127.0.0.1', resolve));
+    baseUrl = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+  });
+
+  afterEach(async () => {
+    await new Promise(resolve => server.close(resolve));
+  });
+
+  it('should read provider, gateway and model from the environment', () => {
+    expect(
+      llmSettingsFromEnv({
+        AUTODOC_LLM_PROVIDER: 'ollama',
+        AUTODOC_LLM_BASE_URL: 'http://127.0.0.1:4000',
+        AUTODOC_LLM_API_KEY: 'session',
+        AUTODOC_LLM_MODEL: 'llama3.2-vision',
+      })
+    ).toEqual({
+      provider: 'ollama',
+      baseUrl: 'http://127.0.0.1:4000',
+      apiKey: 'session',
+      model: 'llama3.2-vision',
+    });
+    expect(llmSettingsFromEnv({}).provider).toBe('anthropic');
+    expect(() => llmSettingsFromEnv({ AUTODOC_LLM_PROVIDER: 'other' })).toThrow(/Unsupported/);
+  });
+
+  it('should use the selected model with Claude', async () => {
+    const client = new LlmClient(undefined, {
+      provider: 'anthropic',
+      baseUrl,
+      apiKey: 'session',
+      model: 'claude-opus-4-20250514',
+    });
+
+    const message = await client.createMessage(IMAGE_MESSAGE);
+    expect(received[0].url).toBe('/v1/messages');
+    expect(received[0].headers['x-api-key']).toBe('session');
+    expect(received[0].body.model).toBe('claude-opus-4-20250514');
+    expect(message.model).toBe('served-model');
+  });
+
+  it('should call chat completions for OpenAI-compatible services', async () => {
+    const client = new LlmClient(undefined, {
+      provider: 'openai_compatible',
+      baseUrl: `${baseUrl}/v1`,
+      apiKey: 'session',
+      model: 'gpt-4o',
+    });
+    client.setAttribution({ projectId: 'proj-a' });
+
+    const message = await client.createMessage(IMAGE_MESSAGE);
+    const request = received[0];
+    expect(request.url).toBe('/v1/chat/completions');
+    expect(request.headers.authorization).toBe('Bearer session');
+    expect(request.headers[PROJECT_HEADER]).toBe('proj-a');
+    expect(request.body.model).toBe('gpt-4o');
+    expect(request.body.messages[0].content).toEqual([
+      { type: 'image_url', image_url: { url: 'data:image/png;base64,iVBORw0K' } },
+      { type: 'text', text: 'Describe this page' },
+    ]);
+
+    expect(message.content).toEqual([{ type: 'text', text: 'A login page' }]);
+    expect(message.usage).toEqual({ input_tokens: 12, output_tokens: 3 });
+  });
+
+  it('should reach Ollama under /v1 without a key', async () => {
+    const client = new LlmClient(undefined, {
+      provider: 'ollama',
+      baseUrl,
+      model: 'llama3.2-vision',
+    });
+
+    await client.createMessage(IMAGE_MESSAGE);
+    expect(received[0].url).toBe('/v1/chat/completions');
+    expect(received[0].headers.authorization).toBeUndefined();
+    expect(received[0].body.model).toBe('llama3.2-vision');
+  });
+});
@@ -0,0 +1,154 @@
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
+import * as http from 'http';
+import { AddressInfo } from 'net';
+import { SessionManager } from '../../src/auth/session_manager';
+import { SessionVaultClient } from '../../src/auth/session_vault_client';
+
+const TOKEN = 'gateway-session-token';
+const ORIGIN = 'https://crm.example.com';
+
+const CDP_COOKIE = {
+  name: 'sid',
+  value: 'abc',
+  domain: 'crm.example.com',
+  path: '/',
+  expires: -1,
+  size: 6,
+  httpOnly: true,
+  secure: true,
+  session: true,
+  sameSite: 'Lax',
+};
+
+function fakeBrowser(loggedIn: boolean) {
+  return {
+    navigate: vi.fn(async () => {}),
+    reload: vi.fn(async () => {}),
+    waitForPageLoad: vi.fn(async () => {}),
+    setExtraHTTPHeaders: vi.fn(async () => {}),
+    setCookie: vi.fn(async () => {}),
+    clearCookies: vi.fn(async () => {}),
+    getCookies: vi.fn(async () => [CDP_COOKIE]),
+    evaluate: vi.fn(async (expression: string) => {
+      if (expression === 'location.origin') {
+        return ORIGIN;
+      }
+      return expression.startsWith('Object.fromEntries') ? { token: 'jwt' } : undefined;
+    }),
+    elementExists: vi.fn(async () => loggedIn),
+    getCurrentUrl: vi.fn(async () => `${ORIGIN}/login`),
+  };
+}
+
+const credentials: any = {
+  getCredential: async () => ({ id: 'cred-1', name: 'CRM', type: 'bearer', token: 'crm-token' }),
+};
+
+describe('SessionManager - desktop session vault', () => {
+  let server: http.Server;
+  let vault: SessionVaultClient;
+  let saved: Map<string, string>;
+  let methods: string[];
+
+  beforeEach(async () => {
+    saved = new Map();
+    methods = [];
+    // Stands in for the session routes of the desktop API gateway
+    server = http.createServer((req, res) => {
+      let data = '';
+      req.on('data', chunk => (data += chunk));
+      req.on('end', () => {
+        methods.push(req.method || '');
+        if (req.headers['x-api-key'] !== TOKEN) {
+          res.writeHead(401);
+          res.end();
+          return;
+        }
+        const targetId = decodeURIComponent((req.url || '').split('/').pop() || '');
+        if (req.method === 'GET') {
+          const session = saved.get(targetId);
+          res.writeHead(session ? 200 : 404, { 'content-type': 'application/json' });
+          res.end(session);
+          return;
+        }
+        if (req.method === 'PUT') {
+          saved.set(targetId, data);
+        } else {
+          saved.delete(targetId);
+        }
+        res.writeHead(204);
+        res.end();
+      });
+    });
+    await new Promise<void>(resolve => server.listen(0, '127.0.0.1', resolve));
+    vault = new SessionVaultClient(`http://127.0.0.1:${(server.address() as AddressInfo).port}`, TOKEN);
+  });
+
+  afterEach(async () => {
+    await new Promise(resolve => server.close(resolve));
+  });
+
+  it('should save cookies and localStorage after logging in', async () => {
+    const browser = fakeBrowser(true);
+    const manager = new SessionManager(credentials, browser, { autoRefresh: false }, vault);
+
+    const result = await manager.login('cred-1', `${ORIGIN}/home`, 'crm');
+    expect(result.success).toBe(true);
+    expect(result.session?.targetId).toBe('crm');
+    expect(browser.setExtraHTTPHeaders).toHaveBeenCalled();
+
+    expect(JSON.parse(saved.get('crm')!)).toEqual({
+      origin: ORIGIN,
+      cookies: [
+        {
+          name: 'sid',
+          value: 'abc',
+          domain: 'crm.example.com',
+          path: '/',
+          expires: -1,
+          httpOnly: true,
+          secure: true,
+          sameSite: 'Lax',
+        },
+      ],
+      localStorage: { token: 'jwt' },
+    });
+  });
+
+  it('should restore a saved session instead of logging in again', async () => {
+    await new SessionManager(credentials, fakeBrowser(true), {}, vault).login('cred-1', `${ORIGIN}/home`, 'crm');
+
+    const browser = fakeBrowser(true);
+    const manager = new SessionManager(credentials, browser, {}, vault);
+    const result = await manager.login('cred-1', `${ORIGIN}/home`, 'crm');
+
+    expect(result.success).toBe(true);
+    expect(browser.setExtraHTTPHeaders).not.toHaveBeenCalled();
+    expect(browser.navigate).toHaveBeenNthCalledWith(1, ORIGIN);
+    expect(browser.setCookie).toHaveBeenCalledWith(
+      expect.objectContaining({ name: 'sid', value: 'abc', expires: undefined })
+    );
+    expect(browser.evaluate).toHaveBeenCalledWith(expect.stringContaining('{"token":"jwt"}'));
+
+    // Logging out drops the saved copy
+    await manager.logout(result.session!.id);
+    expect(saved.has('crm')).toBe(false);
+  });
+
+  it('should drop a saved session that is no longer logged in', async () => {
+    saved.set('crm', JSON.stringify({ origin: ORIGIN, cookies: [], localStorage: {} }));
+    const browser = fakeBrowser(false);
+    const manager = new SessionManager(credentials, browser, {}, vault);
+
+    await manager.login('cred-1', `${ORIGIN}/home`, 'crm');
+    expect(methods.slice(0, 2)).toEqual(['GET', 'DELETE']);
+    expect(browser.setExtraHTTPHeaders).toHaveBeenCalled();
+  });
+
+  it('should only use the vault when started by the desktop app', () => {
+    expect(SessionVaultClient.fromEnv({})).toBeNull();
+    expect(
+      SessionVaultClient.fromEnv({ AUTODOC_GATEWAY_URL: 'http://127.0.0.1:1', AUTODOC_GATEWAY_TOKEN: TOKEN })
+    ).not.toBeNull();
+  });
+});
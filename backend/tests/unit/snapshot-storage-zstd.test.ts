@@ -0,0 +1,33 @@
+import { describe, it, expect } from 'vitest';
+import { decodeZstdContainer } from '../../src/snapshot/snapshot_storage';
+
+// Written by the desktop app's snapshot_store::encode (magic, version, u64 LE length, zstd frame)
+const DESKTOP_CONTAINER = Buffer.from(
+  'QURaUwFJAAAAAAAAACi1L/0kSUkCAHsiaWQiOiJzbmFwLTEiLCJwYWdlcyI6W3sidXJsIjoiaHR0cHM6Ly9leGFt' +
+    'cGxlLmNvbSIsInRpdGxlIjoiRXhhbXBsZSJ9XX2eNhZI',
+  'base64'
+);
+const EXPECTED = '{"id":"snap-1","pages":[{"url":"https://example.com","title":"Example"}]}';
+
+describe('SnapshotStorage - zstd snapshots from the desktop app', () => {
+  it('should decode the container written by the desktop app', () => {
+    // Node 18/20, which the backend targets, have no zlib.zstdDecompressSync, so
+    // this goes through the pure JS decoder there
+    expect(decodeZstdContainer(DESKTOP_CONTAINER).toString('utf-8')).toBe(EXPECTED);
+  });
+
+  it('should reject unknown formats', () => {
+    const data = Buffer.from(DESKTOP_CONTAINER);
+    data[4] = 2;
+    expect(() => decodeZstdContainer(data)).toThrow(/Unsupported compressed snapshot format/);
+    expect(() => decodeZstdContainer(Buffer.from('ADZS'))).toThrow(
+      /Unsupported compressed snapshot format/
+    );
+  });
+
+  it('should reject a length that does not match the decoded data', () => {
+    const data = Buffer.from(DESKTOP_CONTAINER);
+    data.writeBigUInt64LE(BigInt(EXPECTED.length + 1), 5);
+    expect(() => decodeZstdContainer(data)).toThrow(/truncated/);
+  });
+});
@@ -0,0 +1,24 @@
+-- Migration 001: API usage ledger and budgets
+-- Applied by the desktop app (usage_ledger.rs) to the SQLite application database
+-- and recorded in schema_migrations. Safe to run more than once.
+
+CREATE TABLE IF NOT EXISTS api_usage (
+    id INTEGER PRIMARY KEY,
+    at TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    images INTEGER NOT NULL,
+    cost_usd REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS api_usage_at ON api_usage (at);
+
+CREATE TABLE IF NOT EXISTS usage_budgets (
+    id INTEGER PRIMARY KEY,
+    project_id TEXT,
+    period TEXT NOT NULL,
+    limit_usd REAL NOT NULL,
+    action TEXT NOT NULL
+);
@@ -0,0 +1,333 @@
+# AutoDoc Agent desktop messages (English)
+# Keep in sync with locales/zh-TW/main.ftl
+
+## Shared
+
+common-config-dir-unavailable = Cannot determine the configuration directory
+common-serialize-config-failed = Failed to serialize settings: { $error }
+
+## Tray and dialogs
+
+tray-show = Show Window
+tray-hide = Hide Window
+tray-settings = Settings
+tray-about = About
+tray-quit = Quit
+tray-about-title = About AutoDoc Agent
+tray-about-message =
+    AutoDoc Agent v{ $version }
+    Exploratory user manual generator
+
+    © 2025 AutoDoc Team
+tray-still-running =
+    AutoDoc Agent is still running in the system tray.
+    To quit completely, choose "Quit" from the tray menu.
+
+    You can turn off "Minimize to tray" in Settings.
+
+## Settings
+
+config-load-failed = Failed to load settings: { $error }
+config-path-unavailable = Cannot determine the settings path: { $error }
+config-path-invalid = Invalid settings path
+config-save-failed = Failed to save settings: { $error }
+config-max-depth-range = Max depth must be between 1 and 10
+config-max-pages-range = Max pages must be between 10 and 1000
+config-storage-paths-invalid = Storage path validation failed: { $error }
+config-storage-check-failed = Storage path check failed: { $problems }
+config-auth-paths-invalid = Credential path validation failed: { $error }
+config-api-key-empty = Claude API key must not be empty
+config-api-key-malformed = Claude API key is malformed
+config-snapshot-dir-failed = Cannot create the snapshot directory: { $error }
+config-valid = Settings are valid
+
+## Settings history
+
+history-invalid-id = Invalid version ID: { $id }
+history-create-dir-failed = Cannot create the history directory: { $error }
+history-write-failed = Failed to write settings history: { $error }
+history-read-failed = Failed to read settings history: { $error }
+history-version-not-found = Settings version not found: { $id }
+history-parse-failed = Failed to parse settings version: { $error }
+
+## Settings import and export
+
+bundle-unsupported-format = Unsupported format: { $format }
+bundle-serialize-secrets-failed = Failed to serialize secrets: { $error }
+bundle-parse-failed = Failed to parse settings bundle: { $error }
+bundle-version-too-new = Settings bundle version { $version } is newer than this app; please update first
+bundle-password-required = This bundle contains encrypted secrets; a password is required
+bundle-parse-secrets-failed = Failed to parse secrets: { $error }
+bundle-export-password-required = A password is required to export secrets
+bundle-write-failed = Failed to write settings bundle: { $error }
+bundle-read-failed = Failed to read settings bundle: { $error }
+
+## Path checks
+
+path-not-absolute = Path must be absolute: { $path }
+path-parent-component = Path must not contain '..': { $path }
+path-outside-roots = Path must be inside an allowed directory: { $path }
+path-symlink = Path contains a symbolic link: { $path }
+path-validation-failed = Path validation failed: { $error }
+path-create-dir-failed = Cannot create directory { $path }: { $error }
+path-symlink-or-not-dir = Path contains a symbolic link or a non-directory: { $path }
+path-not-found = Path does not exist: { $path }
+path-not-writable = Directory is not writable { $path }: { $error }
+path-free-space-unavailable = Cannot determine free space: { $error }
+path-low-free-space = Not enough free space: { $available } MB left, at least { $required } MB required
+
+## Target applications
+
+target-url-invalid = Invalid URL { $url }: { $error }
+target-url-unsupported-scheme = Unsupported URL scheme: { $scheme }
+target-id-invalid = Malformed target ID: { $id }
+target-id-duplicate = Duplicate target ID: { $id }
+target-name-empty = Target name must not be empty
+target-username-required = This authentication type requires a username
+
+## Secure storage
+
+secret-store-failed = Failed to store credential: { $error }
+secret-entry-failed = Failed to create keychain entry: { $error }
+secret-not-found = Credential not found or inaccessible: { $key }
+credential-unknown = Unknown credential: { $key }
+credential-internal = Credential { $key } can only be managed by the app
+credential-rotation-due = { $name } has not been changed for { $days } days; consider rotating it
+credential-expiring-soon = { $name } expires on { $date }
+credential-expired = { $name } expired on { $date }
+credential-label-target-password = Target app password
+credential-label-proxy-password = Proxy password
+credential-label-google-token = Google authorization
+credential-label-session-vault-key = Browser session vault key
+secret-keychain-failed = Failed to access keychain: { $error }
+secret-vault-locked = The secret vault is locked; unlock it with its passphrase
+secret-vault-passphrase-required = A vault passphrase is required
+secret-vault-wrong-passphrase = Wrong vault passphrase or damaged vault file
+secret-vault-version = Unsupported secret vault version { $version }
+secret-vault-read-failed = Failed to read the secret vault: { $error }
+secret-vault-write-failed = Failed to write the secret vault: { $error }
+secret-migration-verify-failed = Credential { $key } did not read back correctly after migration
+legacy-secrets-read-failed = Failed to read the config file for plaintext secrets: { $error }
+legacy-secrets-backup-failed = Failed to handle the config backup: { $error }
+legacy-secrets-write-failed = Failed to remove plaintext secrets from the config file: { $error }
+secret-diag-no-dbus = No D-Bus session bus (DBUS_SESSION_BUS_ADDRESS is not set), so the Secret Service cannot be reached
+secret-diag-no-secret-service = No Secret Service provider is running; install and start gnome-keyring or KeePassXC
+secret-diag-keyring-locked = The keychain is locked; unlock it and try again
+secret-diag-keyring-error = The keychain reported: { $error }
+
+## Encryption
+
+crypto-kdf-limits = Key derivation parameters exceed the supported limits
+crypto-kdf-invalid = Invalid key derivation parameters: { $error }
+crypto-kdf-failed = Key derivation failed: { $error }
+crypto-passphrase-empty = Passphrase must not be empty
+crypto-encrypt-failed = Encryption failed
+crypto-unsupported-scheme = Unsupported encryption scheme: { $scheme }
+crypto-corrupted = Corrupted encrypted data ({ $field })
+crypto-wrong-passphrase = Decryption failed: wrong passphrase or corrupted data
+crypto-wrong-key = Decryption failed: wrong key or corrupted data
+
+## HTTP and proxy
+
+proxy-url-invalid = Malformed proxy URL
+proxy-url-invalid-detail = Malformed proxy URL: { $error }
+proxy-unsupported-scheme = Unsupported proxy scheme: { $scheme }
+proxy-invalid = Invalid proxy settings: { $error }
+proxy-auth-required = The proxy requires authentication; check the proxy username and password
+proxy-test-http-error = The target server returned an error: { $status }
+proxy-test-ok = Connected ({ $status })
+ca-read-failed = Cannot read CA bundle { $path }: { $error }
+ca-malformed = Malformed CA bundle: { $error }
+ca-empty = CA bundle contains no certificates: { $path }
+http-client-build-failed = Failed to create HTTP client: { $error }
+
+## Connection tests
+
+check-hint-proxy = Cannot connect through the proxy; check the proxy settings under Advanced
+check-hint-dns = Cannot resolve the host name; check the URL and DNS settings
+check-hint-connect = Cannot connect; make sure the service is running and the URL and port are correct
+check-hint-tls = TLS verification failed; behind a corporate proxy, configure a CA bundle
+check-hint-timeout = The connection timed out; check your network or proxy settings
+check-hint-config = Invalid connection settings; check the proxy and CA bundle settings
+check-hint-retry = Please try again later
+check-hint-http-client = Check the proxy and CA bundle settings under Advanced
+check-claude-no-key = No API key configured
+check-claude-no-key-hint = Enter your Claude API key in the authentication settings
+check-claude-key-valid = API key is valid
+check-claude-key-invalid = API key is invalid or disabled
+check-claude-key-invalid-hint = Check in the Anthropic Console that the API key is correct and still active
+check-claude-rate-limited = API key is valid but currently rate limited
+check-claude-rate-limited-hint = Lower the API rate limit setting or try again later
+check-claude-api-error = API returned an error: { $status }
+check-claude-api-error-hint = The Anthropic service may be temporarily unavailable; try again later
+check-llm-no-key-hint = Enter the { $provider } key in the authentication settings
+check-llm-ok = Service is reachable and the model is available
+check-llm-model-missing = Service is reachable but model { $model } is not available
+check-llm-model-missing-hint = Check the model name, or pull the model on the server first
+check-llm-key-invalid-hint = Check that the { $provider } key is correct and still active
+check-llm-api-error-hint = { $provider } may be temporarily unavailable; check the base URL or try again later
+check-mcp-url-invalid = Malformed MCP server URL
+check-mcp-url-invalid-detail = Malformed MCP server URL: { $error }
+check-mcp-url-hint = Enter a full URL, for example http://localhost
+check-mcp-responded = MCP server responded at { $url }
+check-mcp-not-running-hint = Make sure the Chrome MCP server is running and check the port
+check-google-no-credentials = No credentials file configured
+check-google-no-credentials-hint = To export to Google Docs, choose an OAuth credentials file
+check-google-credentials-hint = Download an OAuth client JSON of type "Desktop app" from the Google Cloud Console
+check-google-client-valid = OAuth client is valid
+check-google-client-invalid = OAuth client is invalid
+check-google-client-invalid-hint = The client_id or client_secret in the credentials file is wrong; download it again
+check-google-error = Google returned an error: { $status }
+check-google-error-hint = Google services may be temporarily unavailable; try again later
+check-google-unknown = Cannot determine the OAuth client status: { $status } { $error }
+check-target-no-url = No entry URL configured
+check-target-error = The target site returned an error: { $status }
+check-target-error-hint = The target site may be temporarily unavailable
+check-target-reachable = The target site is reachable ({ $status })
+
+## Google authorization
+
+google-credentials-read-failed = Cannot read the credentials file: { $error }
+google-credentials-malformed = Malformed credentials file: { $error }
+google-credentials-missing-section = The credentials file has no "installed" or "web" section
+google-credentials-not-configured = No Google OAuth credentials file configured
+google-token-serialize-failed = Failed to serialize token: { $error }
+google-connect-failed = Cannot connect to Google: { $error }
+google-response-read-failed = Failed to read the Google response: { $error }
+google-auth-failed = Google authorization failed ({ $detail })
+google-response-malformed = Malformed Google response: { $error }
+google-no-refresh-token = Google did not return a refresh token; please authorize again
+google-auth-url-invalid = Malformed authorization URL: { $error }
+google-open-browser-failed = Cannot open the browser: { $error }
+google-callback-success-title = AutoDoc Agent is authorized
+google-callback-success-body = You can close this window.
+google-callback-failure-title = AutoDoc Agent authorization failed
+google-callback-failure-body = Return to the app and try again.
+google-callback-wait-failed = Failed while waiting for the authorization callback: { $error }
+google-consent-denied = Authorization was denied or failed: { $error }
+google-callback-missing-code = The authorization callback carries no code
+google-callback-server-failed = Cannot start the local callback server: { $error }
+google-auth-timeout = Timed out waiting for authorization; please try again
+google-not-authorized = No Google account authorized
+google-token-file-malformed = Malformed token file: { $error }
+google-token-file-no-refresh = The token file has no refresh token
+google-token-file-read-failed = Cannot read the token file: { $error }
+google-token-file-delete-failed = Cannot delete the token file: { $error }
+google-token-verify-failed = The token could not be read back from the secret store; the token file was kept
+
+## Autostart
+
+autostart-exe-path-failed = Cannot determine the executable path: { $error }
+autostart-create-dir-failed = Cannot create the autostart directory: { $error }
+autostart-write-failed = Cannot write the autostart entry: { $error }
+autostart-remove-failed = Cannot remove the autostart entry: { $error }
+
+## Window state
+
+window-state-serialize-failed = Failed to serialize window state: { $error }
+window-state-write-failed = Failed to write window state: { $error }
+
+## Backend process
+
+sidecar-port-range = Port must be between 1024 and 65535
+sidecar-already-running = Backend is already running
+sidecar-backend-missing = Backend file not found: { $path }
+sidecar-start-failed = Failed to start the backend: { $error }
+sidecar-stop-failed = Failed to stop the backend: { $error }
+sidecar-not-running = Backend is not running
+sidecar-current-dir-failed = Failed to get current directory: { $error }
+sidecar-resource-dir-failed = Failed to get resource directory: { $error }
+sidecar-started = Backend started on port { $port }
+sidecar-stopped = Backend stopped
+sidecar-restarted = Backend restarted on port { $port }
+
+## Snapshots and retention
+
+snapshot-manifest-read-failed = Cannot read snapshot manifest { $path }: { $error }
+snapshot-manifest-malformed = Malformed snapshot manifest { $path }: { $error }
+retention-item-failed = Cannot clean up { $path }: { $error }
+retention-log-write-failed = Failed to write the cleanup log: { $error }
+retention-already-running = A cleanup is already running; try again later
+usage-cache-write-failed = Failed to write the usage cache: { $error }
+usage-low-space = Only { $available } MB free on the volume of { $path }, below the configured { $required } MB
+store-recompress-item-failed = Cannot recompress { $path }: { $error }
+store-recompress-running = Recompression is already running; try again later
+
+## Storage relocation
+
+move-journal-invalid = Cannot read the move journal: { $error }
+move-journal-write-failed = Cannot write the move journal: { $error }
+move-target-overlaps = The new location { $target } cannot contain or be inside { $source }
+move-target-not-empty = The new location { $path } already contains files; choose an empty folder
+move-insufficient-space = Not enough free space at { $path }: { $required } MB needed, { $available } MB available
+move-copy-failed = Failed to copy { $path }: { $error }
+move-cleanup-failed = Cannot remove { $path } from the old location: { $error }
+move-other-pending = A move from { $source } to { $target } is unfinished; resume or cancel it first
+move-interrupted = The move was paused and can be resumed later
+move-already-running = A move is already running
+
+## Backup and restore
+
+backup-write-failed = Failed to write the backup: { $error }
+backup-read-failed = Failed to read the backup: { $error }
+backup-manifest-missing = The backup has no manifest and may be incomplete
+backup-manifest-invalid = The backup manifest is malformed: { $error }
+backup-version-too-new = Backup format version { $version } is newer than this app supports; please update first
+backup-checksum-mismatch = Checksum mismatch for { $path } in the backup; the file may be corrupted
+backup-entry-missing = The backup is missing { $path }
+backup-entry-invalid = The backup contains an invalid path { $path }
+backup-entry-unexpected = The backup contains { $path }, which the manifest does not list
+backup-restore-file-failed = Failed to restore { $path }: { $error }
+backup-restore-exists = { $path } already exists; allow overwriting to restore over existing files
+
+## Usage statistics
+
+telemetry-write-failed = Failed to write usage statistics: { $error }
+telemetry-export-failed = Failed to export usage statistics: { $error }
+telemetry-purge-failed = Failed to clear usage statistics: { $error }
+
+## API gateway
+gateway-bind-failed = Failed to start the local API gateway: { $error }
+gateway-unauthorized = Invalid gateway session token
+gateway-body-too-large = Request body is too large
+gateway-no-api-key = Claude API key is not set
+gateway-invalid-api-key = Claude API key contains invalid characters
+gateway-upstream-failed = Failed to reach the Claude API: { $error }
+gateway-keys-rejected = Every API key was rejected; check the keys in Settings
+gateway-telemetry-invalid = Invalid telemetry event: { $error }
+gateway-telemetry-unsupported = Only exploration and error events can be reported
+
+## Usage ledger
+ledger-open-failed = Failed to open the usage ledger: { $error }
+ledger-query-failed = Usage ledger query failed: { $error }
+ledger-export-failed = Failed to export usage: { $error }
+ledger-budget-invalid-limit = Budget limit must be greater than zero
+ledger-budget-not-found = Budget { $id } does not exist
+ledger-budget-paused = Claude requests are paused: { $spent } of { $limit } USD budget used
+
+## LLM providers
+llm-key-empty = { $provider } key must not be empty
+llm-key-malformed = { $provider } key must not contain spaces
+llm-base-url-invalid = Invalid base URL: { $url }
+llm-model-empty = Model must not be empty
+
+## API key pool
+key-pool-id-invalid = Invalid key ID: { $id }
+key-pool-id-duplicate = Duplicate key ID: { $id }
+key-pool-name-empty = Key name must not be empty
+key-pool-rate-limit-invalid = Requests per minute must be greater than zero
+
+## Session vault
+
+session-key-invalid = The session vault key is unreadable
+session-target-invalid = Unknown target: { $target }
+session-not-found = No saved session for target { $target }
+session-read-failed = Failed to read saved session: { $error }
+session-write-failed = Failed to save session: { $error }
+session-delete-failed = Failed to delete saved session: { $error }
+session-version-unsupported = Saved session version { $version } is not supported
+
+## Updates
+
+updater-no-notes = No updates
+updater-install-unsupported = In-app updates are not available yet; please download the latest version manually
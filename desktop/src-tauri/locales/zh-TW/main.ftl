@@ -0,0 +1,333 @@
+# AutoDoc Agent 桌面端訊息（繁體中文）
+# 新增訊息時請同步更新 locales/en/main.ftl
+
+## 共用
+
+common-config-dir-unavailable = 無法取得設定目錄
+common-serialize-config-failed = 序列化配置失敗: { $error }
+
+## 托盤與對話框
+
+tray-show = 顯示主視窗
+tray-hide = 隱藏視窗
+tray-settings = 設定
+tray-about = 關於
+tray-quit = 退出
+tray-about-title = 關於 AutoDoc Agent
+tray-about-message =
+    AutoDoc Agent v{ $version }
+    智能探索式使用手冊生成器
+
+    © 2025 AutoDoc Team
+tray-still-running =
+    AutoDoc Agent 仍在系統托盤中執行。
+    如需完全結束，請從托盤選單選擇「退出」。
+
+    可在設定中關閉「最小化到系統托盤」。
+
+## 配置
+
+config-load-failed = 載入配置失敗: { $error }
+config-path-unavailable = 無法取得配置路徑: { $error }
+config-path-invalid = 無效的配置路徑
+config-save-failed = 保存配置失敗: { $error }
+config-max-depth-range = 最大深度必須在 1-10 之間
+config-max-pages-range = 最大頁面數必須在 10-1000 之間
+config-storage-paths-invalid = 儲存路徑驗證失敗: { $error }
+config-storage-check-failed = 儲存路徑檢查失敗: { $problems }
+config-auth-paths-invalid = 認證路徑驗證失敗: { $error }
+config-api-key-empty = Claude API Key 不能為空
+config-api-key-malformed = Claude API Key 格式不正確
+config-snapshot-dir-failed = 無法建立快照目錄: { $error }
+config-valid = 配置驗證通過
+
+## 配置歷史
+
+history-invalid-id = 無效的版本 ID: { $id }
+history-create-dir-failed = 無法建立歷史目錄: { $error }
+history-write-failed = 寫入配置歷史失敗: { $error }
+history-read-failed = 讀取配置歷史失敗: { $error }
+history-version-not-found = 找不到配置版本: { $id }
+history-parse-failed = 解析配置版本失敗: { $error }
+
+## 配置匯入匯出
+
+bundle-unsupported-format = 不支援的格式: { $format }
+bundle-serialize-secrets-failed = 序列化密鑰失敗: { $error }
+bundle-parse-failed = 解析配置包失敗: { $error }
+bundle-version-too-new = 配置包版本 { $version } 過新，請先更新應用程式
+bundle-password-required = 此配置包包含加密的密鑰，需要提供密碼
+bundle-parse-secrets-failed = 解析密鑰失敗: { $error }
+bundle-export-password-required = 匯出密鑰時必須提供密碼
+bundle-write-failed = 寫入配置包失敗: { $error }
+bundle-read-failed = 讀取配置包失敗: { $error }
+
+## 路徑檢查
+
+path-not-absolute = 路徑必須為絕對路徑: { $path }
+path-parent-component = 路徑不可包含 '..': { $path }
+path-outside-roots = 路徑必須在允許的目錄內: { $path }
+path-symlink = 路徑包含符號連結: { $path }
+path-validation-failed = 路徑驗證失敗: { $error }
+path-create-dir-failed = 無法建立目錄 { $path }: { $error }
+path-symlink-or-not-dir = 路徑包含符號連結或非目錄: { $path }
+path-not-found = 路徑不存在: { $path }
+path-not-writable = 目錄無法寫入 { $path }: { $error }
+path-free-space-unavailable = 無法取得可用空間: { $error }
+path-low-free-space = 可用空間不足: 剩餘 { $available } MB，至少需要 { $required } MB
+
+## 目標應用程式
+
+target-url-invalid = 無效的網址 { $url }: { $error }
+target-url-unsupported-scheme = 不支援的網址協定: { $scheme }
+target-id-invalid = 目標 ID 格式不正確: { $id }
+target-id-duplicate = 目標 ID 重複: { $id }
+target-name-empty = 目標名稱不能為空
+target-username-required = 此認證方式需要使用者名稱
+
+## 安全儲存
+
+secret-store-failed = 無法儲存憑證: { $error }
+secret-entry-failed = 無法建立鑰匙圈項目: { $error }
+secret-not-found = 找不到憑證或無法存取: { $key }
+credential-unknown = 未知的憑證: { $key }
+credential-internal = 憑證 { $key } 僅供應用程式內部使用
+credential-rotation-due = { $name } 已 { $days } 天未更換，建議輪替
+credential-expiring-soon = { $name } 將於 { $date } 到期
+credential-expired = { $name } 已於 { $date } 到期
+credential-label-target-password = 目標應用程式密碼
+credential-label-proxy-password = 代理密碼
+credential-label-google-token = Google 授權
+credential-label-session-vault-key = 瀏覽器工作階段保存庫金鑰
+secret-keychain-failed = 無法存取系統鑰匙圈: { $error }
+secret-vault-locked = 加密憑證檔已鎖定，請輸入密碼解鎖
+secret-vault-passphrase-required = 必須輸入加密憑證檔密碼
+secret-vault-wrong-passphrase = 加密憑證檔密碼錯誤或檔案已損毀
+secret-vault-version = 不支援的加密憑證檔版本 { $version }
+secret-vault-read-failed = 讀取加密憑證檔失敗: { $error }
+secret-vault-write-failed = 寫入加密憑證檔失敗: { $error }
+secret-migration-verify-failed = 憑證 { $key } 遷移後讀回結果不一致
+legacy-secrets-read-failed = 無法讀取設定檔以檢查明文憑證: { $error }
+legacy-secrets-backup-failed = 無法處理設定檔備份: { $error }
+legacy-secrets-write-failed = 無法從設定檔移除明文憑證: { $error }
+secret-diag-no-dbus = 沒有 D-Bus 工作階段匯流排 (未設定 DBUS_SESSION_BUS_ADDRESS)，無法連線 Secret Service
+secret-diag-no-secret-service = 沒有執行中的 Secret Service，請安裝並啟動 gnome-keyring 或 KeePassXC
+secret-diag-keyring-locked = 系統鑰匙圈已鎖定，請解鎖後再試
+secret-diag-keyring-error = 系統鑰匙圈回報: { $error }
+
+## 加密
+
+crypto-kdf-limits = 金鑰衍生參數超出支援範圍
+crypto-kdf-invalid = 金鑰衍生參數無效: { $error }
+crypto-kdf-failed = 金鑰衍生失敗: { $error }
+crypto-passphrase-empty = 密碼不可為空
+crypto-encrypt-failed = 加密失敗
+crypto-unsupported-scheme = 不支援的加密方式: { $scheme }
+crypto-corrupted = 加密資料已損毀 ({ $field })
+crypto-wrong-passphrase = 解密失敗: 密碼錯誤或資料已損毀
+crypto-wrong-key = 解密失敗: 金鑰錯誤或資料已損毀
+
+## HTTP 與代理
+
+proxy-url-invalid = 代理 URL 格式不正確
+proxy-url-invalid-detail = 代理 URL 格式不正確: { $error }
+proxy-unsupported-scheme = 不支援的代理協定: { $scheme }
+proxy-invalid = 代理設定無效: { $error }
+proxy-auth-required = 代理伺服器要求驗證，請檢查代理帳號與密碼
+proxy-test-http-error = 目標伺服器回應錯誤: { $status }
+proxy-test-ok = 連線成功 ({ $status })
+ca-read-failed = 無法讀取 CA 憑證檔 { $path }: { $error }
+ca-malformed = CA 憑證檔格式不正確: { $error }
+ca-empty = CA 憑證檔不含任何憑證: { $path }
+http-client-build-failed = 建立 HTTP 客戶端失敗: { $error }
+
+## 連線測試
+
+check-hint-proxy = 無法透過代理伺服器連線，請檢查進階設定中的代理設定
+check-hint-dns = 無法解析主機名稱，請檢查網址與 DNS 設定
+check-hint-connect = 無法建立連線，請確認服務已啟動且網址與埠號正確
+check-hint-tls = TLS 驗證失敗，如使用企業代理請設定 CA 憑證檔
+check-hint-timeout = 連線逾時，請檢查網路或代理設定
+check-hint-config = 連線設定無效，請檢查代理與 CA 憑證設定
+check-hint-retry = 請稍後再試
+check-hint-http-client = 請檢查進階設定中的代理與 CA 憑證設定
+check-claude-no-key = 尚未設定 API Key
+check-claude-no-key-hint = 請在認證設定中輸入 Claude API Key
+check-claude-key-valid = API Key 有效
+check-claude-key-invalid = API Key 無效或已停用
+check-claude-key-invalid-hint = 請至 Anthropic Console 確認 API Key 是否正確且仍有效
+check-claude-rate-limited = API Key 有效，但目前已達速率限制
+check-claude-rate-limited-hint = 請降低 API 速率限制設定或稍後再試
+check-claude-api-error = API 回應錯誤: { $status }
+check-claude-api-error-hint = Anthropic 服務可能暫時無法使用，請稍後再試
+check-llm-no-key-hint = 請在認證設定中輸入 { $provider } 的金鑰
+check-llm-ok = 服務可連線且模型可用
+check-llm-model-missing = 服務可連線，但找不到模型 { $model }
+check-llm-model-missing-hint = 請確認模型名稱，或先在伺服器上下載該模型
+check-llm-key-invalid-hint = 請確認 { $provider } 的金鑰正確且仍有效
+check-llm-api-error-hint = { $provider } 可能暫時無法使用；請確認基礎網址或稍後再試
+check-mcp-url-invalid = MCP Server URL 格式不正確
+check-mcp-url-invalid-detail = MCP Server URL 格式不正確: { $error }
+check-mcp-url-hint = 請輸入完整網址，例如 http://localhost
+check-mcp-responded = MCP 伺服器回應於 { $url }
+check-mcp-not-running-hint = 請確認 Chrome MCP 伺服器已啟動，並檢查埠號設定
+check-google-no-credentials = 尚未設定憑證檔案
+check-google-no-credentials-hint = 如需輸出至 Google Docs，請選擇 OAuth 憑證檔案
+check-google-credentials-hint = 請從 Google Cloud Console 下載「桌面應用程式」類型的 OAuth 用戶端 JSON
+check-google-client-valid = OAuth 用戶端有效
+check-google-client-invalid = OAuth 用戶端無效
+check-google-client-invalid-hint = 憑證檔案中的 client_id 或 client_secret 不正確，請重新下載
+check-google-error = Google 回應錯誤: { $status }
+check-google-error-hint = Google 服務可能暫時無法使用，請稍後再試
+check-google-unknown = 無法確認 OAuth 用戶端狀態: { $status } { $error }
+check-target-no-url = 尚未設定入口網址
+check-target-error = 目標網站回應錯誤: { $status }
+check-target-error-hint = 目標網站可能暫時無法使用
+check-target-reachable = 目標網站可連線 ({ $status })
+
+## Google 授權
+
+google-credentials-read-failed = 無法讀取憑證檔案: { $error }
+google-credentials-malformed = 憑證檔案格式不正確: { $error }
+google-credentials-missing-section = 憑證檔案缺少 installed 或 web 區段
+google-credentials-not-configured = 尚未設定 Google OAuth 憑證檔案
+google-token-serialize-failed = 序列化 Token 失敗: { $error }
+google-connect-failed = 無法連線至 Google: { $error }
+google-response-read-failed = 讀取 Google 回應失敗: { $error }
+google-auth-failed = Google 授權失敗 ({ $detail })
+google-response-malformed = Google 回應格式不正確: { $error }
+google-no-refresh-token = Google 未回傳 refresh token，請重新授權
+google-auth-url-invalid = 授權網址格式不正確: { $error }
+google-open-browser-failed = 無法開啟瀏覽器: { $error }
+google-callback-success-title = AutoDoc Agent 已取得授權
+google-callback-success-body = 您可以關閉此視窗。
+google-callback-failure-title = AutoDoc Agent 授權失敗
+google-callback-failure-body = 請回到應用程式重試。
+google-callback-wait-failed = 等待授權回呼失敗: { $error }
+google-consent-denied = 使用者拒絕授權或授權失敗: { $error }
+google-callback-missing-code = 授權回呼缺少授權碼
+google-callback-server-failed = 無法建立本機回呼伺服器: { $error }
+google-auth-timeout = 等待授權逾時，請重試
+google-not-authorized = 尚未授權 Google 帳戶
+google-token-file-malformed = Token 檔案格式不正確: { $error }
+google-token-file-no-refresh = Token 檔案缺少 refresh token
+google-token-file-read-failed = 無法讀取 Token 檔案: { $error }
+google-token-file-delete-failed = 無法刪除 Token 檔案: { $error }
+google-token-verify-failed = 無法從安全儲存區讀回 Token，已保留 Token 檔案
+
+## 開機自動啟動
+
+autostart-exe-path-failed = 無法取得執行檔路徑: { $error }
+autostart-create-dir-failed = 無法建立自動啟動目錄: { $error }
+autostart-write-failed = 無法寫入自動啟動設定: { $error }
+autostart-remove-failed = 無法移除自動啟動設定: { $error }
+
+## 視窗狀態
+
+window-state-serialize-failed = 序列化視窗狀態失敗: { $error }
+window-state-write-failed = 寫入視窗狀態失敗: { $error }
+
+## 後端程序
+
+sidecar-port-range = 埠號必須在 1024-65535 之間
+sidecar-already-running = Backend 已經在運行
+sidecar-backend-missing = 找不到 Backend 檔案: { $path }
+sidecar-start-failed = 啟動後端失敗: { $error }
+sidecar-stop-failed = 停止後端失敗: { $error }
+sidecar-not-running = Backend 未在運行
+sidecar-current-dir-failed = 無法取得目前目錄: { $error }
+sidecar-resource-dir-failed = 無法取得資源目錄: { $error }
+sidecar-started = Backend 已在端口 { $port } 啟動
+sidecar-stopped = Backend 已停止
+sidecar-restarted = Backend 已在端口 { $port } 重啟
+
+## 快照與保留期限
+
+snapshot-manifest-read-failed = 無法讀取快照資訊 { $path }: { $error }
+snapshot-manifest-malformed = 快照資訊格式不正確 { $path }: { $error }
+retention-item-failed = 無法清理 { $path }: { $error }
+retention-log-write-failed = 寫入清理紀錄失敗: { $error }
+retention-already-running = 清理作業正在執行中，請稍後再試
+usage-cache-write-failed = 寫入用量快取失敗: { $error }
+usage-low-space = { $path } 所在磁碟僅剩 { $available } MB，低於設定的 { $required } MB
+store-recompress-item-failed = 無法重新壓縮 { $path }: { $error }
+store-recompress-running = 重新壓縮作業正在執行中，請稍後再試
+
+## 儲存位置搬移
+
+move-journal-invalid = 無法讀取搬移紀錄: { $error }
+move-journal-write-failed = 無法寫入搬移紀錄: { $error }
+move-target-overlaps = 新位置 { $target } 不能與原位置 { $source } 互相包含
+move-target-not-empty = 新位置 { $path } 已有其他檔案，請選擇空資料夾
+move-insufficient-space = { $path } 可用空間不足：需要 { $required } MB，僅剩 { $available } MB
+move-copy-failed = 複製 { $path } 失敗: { $error }
+move-cleanup-failed = 無法刪除原位置的 { $path }: { $error }
+move-other-pending = 另一個從 { $source } 搬移到 { $target } 的作業尚未完成，請先繼續或取消該作業
+move-interrupted = 搬移已暫停，可稍後繼續
+move-already-running = 搬移作業正在執行中
+
+## 備份與還原
+
+backup-write-failed = 寫入備份失敗: { $error }
+backup-read-failed = 讀取備份失敗: { $error }
+backup-manifest-missing = 備份缺少 manifest，檔案可能不完整
+backup-manifest-invalid = 備份 manifest 格式不正確: { $error }
+backup-version-too-new = 備份格式版本 { $version } 過新，請先更新應用程式
+backup-checksum-mismatch = 備份中的 { $path } 校驗碼不符，檔案可能已損毀
+backup-entry-missing = 備份缺少 { $path }
+backup-entry-invalid = 備份中的路徑 { $path } 不合法
+backup-entry-unexpected = 備份包含 manifest 未列出的項目 { $path }
+backup-restore-file-failed = 還原 { $path } 失敗: { $error }
+backup-restore-exists = { $path } 已存在；請允許覆寫既有檔案後再還原
+
+## 使用統計
+
+telemetry-write-failed = 寫入使用統計失敗: { $error }
+telemetry-export-failed = 匯出使用統計失敗: { $error }
+telemetry-purge-failed = 清除使用統計失敗: { $error }
+
+## API 閘道
+gateway-bind-failed = 啟動本機 API 閘道失敗: { $error }
+gateway-unauthorized = 閘道工作階段權杖無效
+gateway-body-too-large = 請求內容過大
+gateway-no-api-key = 尚未設定 Claude API 金鑰
+gateway-invalid-api-key = Claude API 金鑰含有無效字元
+gateway-upstream-failed = 無法連線到 Claude API: { $error }
+gateway-keys-rejected = 所有 API 金鑰都被拒絕，請在設定中檢查金鑰
+gateway-telemetry-invalid = 使用統計事件無效: { $error }
+gateway-telemetry-unsupported = 只能回報探索與錯誤事件
+
+## 用量帳本
+ledger-open-failed = 開啟用量帳本失敗: { $error }
+ledger-query-failed = 查詢用量帳本失敗: { $error }
+ledger-export-failed = 匯出用量失敗: { $error }
+ledger-budget-invalid-limit = 預算上限必須大於零
+ledger-budget-not-found = 預算 { $id } 不存在
+ledger-budget-paused = Claude 請求已暫停: 已使用 { $spent } / { $limit } USD 預算
+
+## LLM 服務
+llm-key-empty = { $provider } 金鑰不能為空
+llm-key-malformed = { $provider } 金鑰不能包含空白
+llm-base-url-invalid = 無效的基礎網址: { $url }
+llm-model-empty = 模型不能為空
+
+## API 金鑰集區
+key-pool-id-invalid = 無效的金鑰 ID: { $id }
+key-pool-id-duplicate = 金鑰 ID 重複: { $id }
+key-pool-name-empty = 金鑰名稱不能為空
+key-pool-rate-limit-invalid = 每分鐘請求上限必須大於零
+
+## 工作階段保存庫
+
+session-key-invalid = 無法讀取工作階段保存庫金鑰
+session-target-invalid = 未知的目標: { $target }
+session-not-found = 目標 { $target } 沒有已儲存的工作階段
+session-read-failed = 無法讀取已儲存的工作階段: { $error }
+session-write-failed = 無法儲存工作階段: { $error }
+session-delete-failed = 無法刪除已儲存的工作階段: { $error }
+session-version-unsupported = 不支援的工作階段版本 { $version }
+
+## 更新
+
+updater-no-notes = 暫無更新
+updater-install-unsupported = 更新功能尚未完全實現，請手動下載最新版本
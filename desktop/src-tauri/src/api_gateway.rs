@@ -0,0 +1,1333 @@
+use crate::config::{self, AppConfig};
+use crate::i18n::tr;
+use crate::key_pool::{KeyOutcome, KeyPool, KeyStatus, Lease};
+use crate::llm_provider::{self, LlmProvider};
+use crate::secure_storage::SecretStore;
+use crate::session_vault::{self, BrowserSession};
+use crate::telemetry::{self, TelemetryEvent};
+use crate::usage_ledger::{self, UsageRecord};
+use crate::{credential_registry, google_oauth, http_client, secure_storage};
+use axum::body::{Body, Bytes};
+use axum::extract::{Path as UrlPath, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get, post};
+use axum::Router;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use chrono::Utc;
+use futures_util::StreamExt;
+use log::{info, warn};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// Requests above this size are refused before reaching upstream
+const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+/// Upstream statuses worth retrying: rate limited and overloaded
+const RETRY_STATUSES: [u16; 2] = [429, 529];
+/// Upstream statuses rejecting the key itself; another key is tried if there is one
+const AUTH_FAILURE_STATUSES: [u16; 2] = [401, 403];
+const MAX_RETRIES: u32 = 3;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Long enough for streamed completions
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(600);
+/// Request headers the gateway never forwards; credentials are replaced
+const DROPPED_REQUEST_HEADERS: [&str; 7] = [
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "accept-encoding",
+    "x-api-key",
+    "authorization",
+];
+const DROPPED_RESPONSE_HEADERS: [&str; 3] = ["connection", "transfer-encoding", "content-length"];
+/// Optional request headers attributing usage; they are not forwarded upstream
+const PROJECT_HEADER: &str = "x-autodoc-project";
+const SESSION_HEADER: &str = "x-autodoc-session";
+/// Saved target logins for the backend; handled here, never forwarded upstream
+const SESSIONS_ROUTE: &str = "/autodoc/sessions/{target_id}";
+/// Google access token for the backend's Docs export; the webview never receives it
+const GOOGLE_TOKEN_ROUTE: &str = "/autodoc/google/token";
+/// Exploration and error events from the backend, kept in the local telemetry journal
+const TELEMETRY_ROUTE: &str = "/autodoc/telemetry";
+/// Non-streamed responses larger than this are not inspected for usage
+const MAX_TAPPED_BYTES: usize = 4 * 1024 * 1024;
+
+/// The running gateway, so settings changes can reach it
+static GATEWAY: OnceLock<Arc<GatewayState>> = OnceLock::new();
+
+/// Reads a key from the keychain entry a pool member names
+type KeyProvider = Box<dyn Fn(&str) -> Result<String, String> + Send + Sync>;
+/// The secret store holding the Google token
+type SecretsProvider = Box<dyn Fn() -> Arc<dyn SecretStore> + Send + Sync>;
+
+/// Where the backend finds the gateway; passed to it as environment variables
+#[derive(Debug, Clone)]
+pub struct GatewayEndpoint {
+    pub url: String,
+    pub session_token: String,
+}
+
+impl GatewayEndpoint {
+    /// The selected provider and model, reached through the gateway, and the gateway
+    /// itself for the backend-only routes.
+    /// Changing provider takes effect when the backend is restarted.
+    pub fn backend_env(&self, config: &AppConfig) -> Vec<(&'static str, String)> {
+        let active = llm_provider::active(config);
+        let mut env = vec![
+            ("AUTODOC_LLM_PROVIDER", active.provider.id().to_string()),
+            ("AUTODOC_LLM_BASE_URL", self.url.clone()),
+            ("AUTODOC_LLM_API_KEY", self.session_token.clone()),
+            ("AUTODOC_LLM_MODEL", active.model),
+            ("AUTODOC_GATEWAY_URL", self.url.clone()),
+            ("AUTODOC_GATEWAY_TOKEN", self.session_token.clone()),
+        ];
+        // Read by the Anthropic SDK in the backend
+        if active.provider == LlmProvider::Anthropic {
+            env.push(("ANTHROPIC_BASE_URL", self.url.clone()));
+            env.push(("ANTHROPIC_API_KEY", self.session_token.clone()));
+        }
+        env
+    }
+}
+
+// ============= Rate Limiting =============
+
+/// Allows `per_minute` requests per minute with bursts up to the same number
+pub(crate) struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(per_minute: u32, now: Instant) -> Self {
+        let capacity = per_minute.max(1) as f64;
+        TokenBucket {
+            capacity,
+            tokens: capacity,
+            refill_per_sec: capacity / 60.0,
+            last: now,
+        }
+    }
+
+    pub(crate) fn set_rate(&mut self, per_minute: u32, now: Instant) {
+        self.refill(now);
+        self.capacity = per_minute.max(1) as f64;
+        self.refill_per_sec = self.capacity / 60.0;
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last = now;
+    }
+
+    /// Take a token, or report how long until one is available
+    pub(crate) fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.check(now)?;
+        self.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Like `try_take`, but leaves the token in the bucket
+    pub(crate) fn check(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+// ============= Gateway State =============
+
+/// Where request usage is recorded
+#[derive(Debug, Clone)]
+struct LedgerTarget {
+    database: PathBuf,
+    provider: LlmProvider,
+    /// Used when neither request nor response names the model
+    default_model: String,
+}
+
+impl LedgerTarget {
+    fn from_config(config: &AppConfig) -> Option<Self> {
+        let active = llm_provider::active(config);
+        match usage_ledger::database_path(&config.storage) {
+            Ok(database) => Some(LedgerTarget {
+                database,
+                provider: active.provider,
+                default_model: active.model,
+            }),
+            Err(e) => {
+                warn!("Usage ledger disabled: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// The selected provider's API root
+#[derive(Debug, Clone)]
+struct Upstream {
+    provider: LlmProvider,
+    base_url: String,
+}
+
+impl Upstream {
+    fn from_config(config: &AppConfig) -> Self {
+        let active = llm_provider::active(config);
+        Upstream {
+            provider: active.provider,
+            base_url: active.base_url,
+        }
+    }
+}
+
+pub struct GatewayState {
+    session_token: String,
+    upstream: RwLock<Upstream>,
+    client: RwLock<reqwest::Client>,
+    /// Keys of the selected provider, each with its own rate limit
+    pool: Arc<Mutex<KeyPool>>,
+    ledger: RwLock<Option<LedgerTarget>>,
+    api_key: KeyProvider,
+    secrets: SecretsProvider,
+    /// Signalled when the app exits; in-flight requests are allowed to finish
+    shutdown: tokio::sync::Notify,
+}
+
+impl GatewayState {
+    fn new(
+        config: &AppConfig,
+        ledger: Option<LedgerTarget>,
+        api_key: KeyProvider,
+        secrets: SecretsProvider,
+    ) -> Result<Self, String> {
+        let advanced = &config.advanced;
+        let mut token = [0u8; 32];
+        OsRng.fill_bytes(&mut token);
+
+        Ok(GatewayState {
+            session_token: URL_SAFE_NO_PAD.encode(token),
+            upstream: RwLock::new(Upstream::from_config(config)),
+            client: RwLock::new(http_client::build_client(advanced, UPSTREAM_TIMEOUT)?),
+            pool: Arc::new(Mutex::new(KeyPool::from_config(config))),
+            ledger: RwLock::new(ledger),
+            api_key,
+            secrets,
+            shutdown: tokio::sync::Notify::new(),
+        })
+    }
+
+    fn apply(&self, config: &AppConfig) -> Result<(), String> {
+        let advanced = &config.advanced;
+        *self.upstream.write().unwrap() = Upstream::from_config(config);
+        *self.client.write().unwrap() = http_client::build_client(advanced, UPSTREAM_TIMEOUT)?;
+        self.pool.lock().unwrap().reconfigure(config);
+        *self.ledger.write().unwrap() = LedgerTarget::from_config(config);
+        Ok(())
+    }
+
+    /// Wait until a key outside `skip` is ready to send under its rate limit
+    async fn lease(&self, skip: &[String]) -> Result<Lease, String> {
+        loop {
+            let wait = match self.pool.lock().unwrap().lease(Instant::now(), skip) {
+                Ok(lease) => return Ok(lease),
+                Err(Some(wait)) => wait,
+                Err(None) => return Err(tr!("gateway-keys-rejected")),
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Record how a key fared; true when a key outside `tried` could take over
+    fn report(&self, id: &str, outcome: KeyOutcome, tried: &[String]) -> bool {
+        let mut pool = self.pool.lock().unwrap();
+        pool.report(id, outcome, Instant::now());
+        pool.has_untried(tried, Instant::now())
+    }
+
+    /// Only the backend we started knows the session token
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let presented = headers
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| {
+                headers
+                    .get(header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.strip_prefix("Bearer "))
+            });
+        presented
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), self.session_token.as_bytes()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// ============= Usage Accounting =============
+
+/// Image blocks anywhere in a Messages API request, including inside tool results
+fn count_images(value: &Value) -> u32 {
+    match value {
+        Value::Array(items) => items.iter().map(count_images).sum(),
+        Value::Object(fields) => {
+            let own = u32::from(fields.get("type").and_then(Value::as_str) == Some("image"));
+            own + fields.values().map(count_images).sum::<u32>()
+        }
+        _ => 0,
+    }
+}
+
+/// Watches a response body as it streams past and records its token usage when done
+struct UsageTap {
+    ledger: Option<LedgerTarget>,
+    /// Pool and key the request was sent with, credited with the tokens
+    pool: Arc<Mutex<KeyPool>>,
+    key_id: String,
+    project_id: String,
+    session_id: String,
+    model: Option<String>,
+    images: u32,
+    /// Server-sent events or newline-delimited JSON, inspected line by line
+    streaming: bool,
+    pending: Vec<u8>,
+    input_tokens: Option<u64>,
+    output_tokens: u64,
+}
+
+impl UsageTap {
+    fn new(
+        ledger: Option<LedgerTarget>,
+        pool: Arc<Mutex<KeyPool>>,
+        key_id: String,
+        headers: &HeaderMap,
+        request: &[u8],
+    ) -> Self {
+        let header_value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let request: Value = serde_json::from_slice(request).unwrap_or_default();
+
+        UsageTap {
+            ledger,
+            pool,
+            key_id,
+            project_id: header_value(PROJECT_HEADER),
+            session_id: header_value(SESSION_HEADER),
+            model: request["model"].as_str().map(str::to_string),
+            images: count_images(&request["messages"]),
+            streaming: false,
+            pending: Vec::new(),
+            input_tokens: None,
+            output_tokens: 0,
+        }
+    }
+
+    fn feed(&mut self, chunk: &[u8]) {
+        if !self.streaming {
+            if self.pending.len() + chunk.len() <= MAX_TAPPED_BYTES {
+                self.pending.extend_from_slice(chunk);
+            }
+            return;
+        }
+
+        // Claude streams usage in message_start and message_delta, others in the last chunk
+        self.pending.extend_from_slice(chunk);
+        while let Some(end) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let event = std::str::from_utf8(&line)
+                .ok()
+                .map(|line| line.trim())
+                .map(|line| line.strip_prefix("data:").unwrap_or(line))
+                .and_then(|data| serde_json::from_str::<Value>(data.trim()).ok());
+            if let Some(event) = event {
+                self.observe(&event);
+            }
+        }
+    }
+
+    fn observe(&mut self, event: &Value) {
+        let message = if event["type"] == "message_start" {
+            &event["message"]
+        } else {
+            event
+        };
+        if let Some(model) = message["model"].as_str() {
+            self.model = Some(model.to_string());
+        }
+        let usage = &message["usage"];
+        // Anthropic, OpenAI-compatible and Ollama field names
+        let input = usage["input_tokens"]
+            .as_u64()
+            .or_else(|| usage["prompt_tokens"].as_u64())
+            .or_else(|| message["prompt_eval_count"].as_u64());
+        let output = usage["output_tokens"]
+            .as_u64()
+            .or_else(|| usage["completion_tokens"].as_u64())
+            .or_else(|| message["eval_count"].as_u64());
+        if let Some(tokens) = input {
+            self.input_tokens = Some(tokens);
+        }
+        // Streams report a running total
+        if let Some(tokens) = output {
+            self.output_tokens = tokens;
+        }
+    }
+}
+
+impl Drop for UsageTap {
+    fn drop(&mut self) {
+        if !self.streaming {
+            if let Ok(message) = serde_json::from_slice::<Value>(&self.pending) {
+                self.observe(&message);
+            }
+        }
+        // Responses without usage (errors, model listings) are not requests we pay for
+        let Some(input_tokens) = self.input_tokens else {
+            return;
+        };
+        if let Ok(mut pool) = self.pool.lock() {
+            pool.add_usage(&self.key_id, input_tokens, self.output_tokens);
+        }
+        let Some(ledger) = self.ledger.take() else {
+            return;
+        };
+
+        let model = self
+            .model
+            .clone()
+            .unwrap_or_else(|| ledger.default_model.clone());
+        let mut usage = UsageRecord::new(
+            &self.project_id,
+            &self.session_id,
+            &model,
+            input_tokens,
+            self.output_tokens,
+            self.images,
+        );
+        // Local models cost nothing per token
+        if ledger.provider == LlmProvider::Ollama {
+            usage.cost_usd = 0.0;
+        }
+        let database = ledger.database;
+        let record = move || {
+            let result = usage_ledger::record(&database, &usage).and_then(|_| {
+                usage_ledger::exceeded_budgets(&database, &usage.project_id, Utc::now())
+            });
+            match result {
+                Ok(exceeded) => usage_ledger::notify_exceeded(&exceeded),
+                Err(e) => warn!("Failed to record API usage: {}", e),
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn_blocking(record);
+            }
+            Err(_) => record(),
+        }
+    }
+}
+
+/// Error message when a pausing budget covering the request's project is used up
+async fn paused_by_budget(ledger: &LedgerTarget, headers: &HeaderMap) -> Option<String> {
+    let database = ledger.database.clone();
+    let project_id = headers
+        .get(PROJECT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let exceeded = tokio::task::spawn_blocking(move || {
+        usage_ledger::exceeded_budgets(&database, &project_id, Utc::now())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+
+    match exceeded {
+        Ok(exceeded) => exceeded
+            .iter()
+            .find(|status| status.budget.action == usage_ledger::BudgetAction::Pause)
+            .map(|status| {
+                tr!(
+                    "ledger-budget-paused",
+                    spent = format!("{:.2}", status.spent_usd),
+                    limit = format!("{:.2}", status.budget.limit_usd)
+                )
+            }),
+        Err(e) => {
+            // Never block requests because the ledger is unreadable
+            warn!("Failed to check usage budgets: {}", e);
+            None
+        }
+    }
+}
+
+// ============= Proxy =============
+
+/// Error body in the same shape the Anthropic API uses, so SDK error handling applies
+fn error_response(status: StatusCode, kind: &str, message: String) -> Response {
+    let body = serde_json::json!({
+        "type": "error",
+        "error": { "type": kind, "message": message },
+    });
+    (status, axum::Json(body)).into_response()
+}
+
+/// `retry-after` in seconds when present, otherwise exponential backoff
+fn retry_delay(headers: &reqwest::header::HeaderMap, attempt: u32) -> Duration {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| Duration::from_millis(500 * 2u64.pow(attempt)))
+        .min(MAX_RETRY_DELAY)
+}
+
+fn forwarded_headers(
+    headers: &HeaderMap,
+    provider: LlmProvider,
+    api_key: &str,
+) -> Result<HeaderMap, String> {
+    let mut forwarded = HeaderMap::new();
+    for (name, value) in headers {
+        if !DROPPED_REQUEST_HEADERS.contains(&name.as_str())
+            && !name.as_str().starts_with("x-autodoc-")
+        {
+            forwarded.append(name.clone(), value.clone());
+        }
+    }
+    if api_key.is_empty() {
+        return Ok(forwarded);
+    }
+
+    let (name, value) = match provider {
+        LlmProvider::Anthropic => (
+            header::HeaderName::from_static("x-api-key"),
+            api_key.to_string(),
+        ),
+        _ => (header::AUTHORIZATION, format!("Bearer {}", api_key)),
+    };
+    let mut key = HeaderValue::from_str(&value).map_err(|_| tr!("gateway-invalid-api-key"))?;
+    key.set_sensitive(true);
+    forwarded.insert(name, key);
+    Ok(forwarded)
+}
+
+/// Stream the upstream response back without buffering it
+fn relay(response: reqwest::Response, tap: UsageTap) -> Response {
+    let mut builder = Response::builder().status(response.status());
+    for (name, value) in response.headers() {
+        if !DROPPED_RESPONSE_HEADERS.contains(&name.as_str()) {
+            builder = builder.header(name, value);
+        }
+    }
+
+    let mut tap = Some(tap).filter(|_| response.status().is_success());
+    if let Some(tap) = tap.as_mut() {
+        tap.streaming = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| {
+                v.starts_with("text/event-stream") || v.starts_with("application/x-ndjson")
+            });
+    }
+    // The tap is dropped, and usage recorded, together with the body stream
+    let stream = response.bytes_stream().map(move |chunk| {
+        if let (Some(tap), Ok(bytes)) = (tap.as_mut(), &chunk) {
+            tap.feed(bytes);
+        }
+        chunk
+    });
+
+    builder
+        .body(Body::from_stream(stream))
+        .unwrap_or_else(|e| error_response(StatusCode::BAD_GATEWAY, "api_error", e.to_string()))
+}
+
+async fn proxy(State(state): State<Arc<GatewayState>>, request: Request) -> Response {
+    if !state.is_authorized(request.headers()) {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "authentication_error",
+            tr!("gateway-unauthorized"),
+        );
+    }
+
+    let (parts, body) = request.into_parts();
+    let body: Bytes = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return error_response(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "request_too_large",
+                tr!("gateway-body-too-large"),
+            )
+        }
+    };
+
+    let ledger = state.ledger.read().unwrap().clone();
+    if let Some(ledger) = &ledger {
+        if let Some(message) = paused_by_budget(ledger, &parts.headers).await {
+            return error_response(StatusCode::FORBIDDEN, "permission_error", message);
+        }
+    }
+
+    let upstream = state.upstream.read().unwrap().clone();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let url = format!("{}{}", upstream.base_url, path);
+
+    let mut attempt = 0;
+    // Keys that already failed this request, skipped while failing over
+    let mut tried: Vec<String> = Vec::new();
+    loop {
+        let lease = match state.lease(&tried).await {
+            Ok(lease) => lease,
+            Err(message) => {
+                return error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "authentication_error",
+                    message,
+                )
+            }
+        };
+        let api_key = match (state.api_key)(&lease.secret_key) {
+            Ok(key) if !key.is_empty() => key,
+            _ if !upstream.provider.requires_key() => String::new(),
+            _ => {
+                return error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "authentication_error",
+                    tr!("gateway-no-api-key"),
+                )
+            }
+        };
+        let headers = match forwarded_headers(&parts.headers, upstream.provider, &api_key) {
+            Ok(headers) => headers,
+            Err(message) => {
+                return error_response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "authentication_error",
+                    message,
+                )
+            }
+        };
+
+        let client = state.client.read().unwrap().clone();
+        let result = client
+            .request(parts.method.clone(), &url)
+            .headers(headers)
+            .body(body.clone())
+            .send()
+            .await;
+
+        match result {
+            Ok(response) => {
+                let status = response.status();
+                let outcome = if AUTH_FAILURE_STATUSES.contains(&status.as_u16()) {
+                    KeyOutcome::AuthFailed(status.to_string())
+                } else if status == StatusCode::TOO_MANY_REQUESTS {
+                    // The key cools down for as long as a retry would wait
+                    KeyOutcome::RateLimited(retry_delay(response.headers(), attempt))
+                } else {
+                    KeyOutcome::Success
+                };
+                let failed = outcome != KeyOutcome::Success;
+                tried.push(lease.id.clone());
+                if state.report(&lease.id, outcome, &tried) && failed {
+                    warn!("Key {} got {}, failing over", lease.id, status);
+                    continue;
+                }
+                // Every key has had its turn; retries may use any of them again
+                tried.clear();
+                if RETRY_STATUSES.contains(&status.as_u16()) && attempt < MAX_RETRIES {
+                    let delay = retry_delay(response.headers(), attempt);
+                    warn!("Upstream returned {}, retrying in {:?}", status, delay);
+                    // A rate limited key waits out its cooldown when leased again
+                    if status != StatusCode::TOO_MANY_REQUESTS {
+                        tokio::time::sleep(delay).await;
+                    }
+                } else {
+                    let tap = UsageTap::new(
+                        ledger,
+                        state.pool.clone(),
+                        lease.id,
+                        &parts.headers,
+                        &body,
+                    );
+                    return relay(response, tap);
+                }
+            }
+            Err(e) if (e.is_connect() || e.is_timeout()) && attempt < MAX_RETRIES => {
+                warn!("Upstream request failed, retrying: {}", e);
+                tokio::time::sleep(retry_delay(&Default::default(), attempt)).await;
+            }
+            Err(e) => {
+                return error_response(
+                    StatusCode::BAD_GATEWAY,
+                    "api_error",
+                    tr!("gateway-upstream-failed", error = e),
+                )
+            }
+        }
+        attempt += 1;
+    }
+}
+
+// ============= Session Vault =============
+
+/// Lets the backend restore (GET), save (PUT) and drop (DELETE) a target's browser session
+async fn session_request(
+    State(state): State<Arc<GatewayState>>,
+    UrlPath(target_id): UrlPath<String>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !state.is_authorized(&headers) {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "authentication_error",
+            tr!("gateway-unauthorized"),
+        );
+    }
+
+    // Keychain and file access block
+    let result = tokio::task::spawn_blocking(move || match method {
+        Method::GET => session_vault::restore(&target_id).map(|session| match session {
+            Some(session) => axum::Json(session).into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        }),
+        Method::PUT => serde_json::from_slice::<BrowserSession>(&body)
+            .map_err(|e| tr!("session-read-failed", error = e))
+            .and_then(|session| session_vault::capture(&target_id, &session))
+            .map(|_| StatusCode::NO_CONTENT.into_response()),
+        Method::DELETE => {
+            session_vault::invalidate(&target_id).map(|_| StatusCode::NO_CONTENT.into_response())
+        }
+        _ => Ok(StatusCode::METHOD_NOT_ALLOWED.into_response()),
+    })
+    .await;
+
+    match result {
+        Ok(Ok(response)) => response,
+        Ok(Err(message)) => {
+            error_response(StatusCode::BAD_REQUEST, "invalid_request_error", message)
+        }
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "api_error",
+            e.to_string(),
+        ),
+    }
+}
+
+// ============= Google Token =============
+
+/// Hands the backend a fresh Google access token, refreshed as needed
+async fn google_token_request(
+    State(state): State<Arc<GatewayState>>,
+    headers: HeaderMap,
+) -> Response {
+    if !state.is_authorized(&headers) {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "authentication_error",
+            tr!("gateway-unauthorized"),
+        );
+    }
+
+    match google_oauth::access_token_in((state.secrets)(), &config::load_settings()).await {
+        Ok(token) => axum::Json(serde_json::json!({ "access_token": token })).into_response(),
+        Err(message) => error_response(StatusCode::BAD_REQUEST, "invalid_request_error", message),
+    }
+}
+
+// ============= Telemetry =============
+
+/// Records an exploration or error event reported by the backend
+async fn telemetry_request(
+    State(state): State<Arc<GatewayState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !state.is_authorized(&headers) {
+        return error_response(
+            StatusCode::UNAUTHORIZED,
+            "authentication_error",
+            tr!("gateway-unauthorized"),
+        );
+    }
+
+    let event = match serde_json::from_slice::<TelemetryEvent>(&body) {
+        Ok(event) => event,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                tr!("gateway-telemetry-invalid", error = e),
+            )
+        }
+    };
+    // Settings and journal access block
+    let recorded = tokio::task::spawn_blocking(move || match event {
+        TelemetryEvent::Exploration {
+            duration_ms,
+            pages,
+            outcome,
+            error_code,
+        } => {
+            telemetry::record_exploration(duration_ms, pages, outcome, error_code);
+            true
+        }
+        TelemetryEvent::Error { code, detail } => {
+            telemetry::record_error(code, detail);
+            true
+        }
+        // App lifecycle events are only recorded by the desktop app itself
+        _ => false,
+    })
+    .await;
+
+    match recorded {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            tr!("gateway-telemetry-unsupported"),
+        ),
+        Err(e) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "api_error",
+            e.to_string(),
+        ),
+    }
+}
+
+/// Bind on loopback and serve until the process exits
+async fn serve(state: Arc<GatewayState>) -> Result<SocketAddr, String> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| tr!("gateway-bind-failed", error = e))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|e| tr!("gateway-bind-failed", error = e))?;
+
+    let app = Router::new()
+        .route(SESSIONS_ROUTE, any(session_request))
+        .route(GOOGLE_TOKEN_ROUTE, get(google_token_request))
+        .route(TELEMETRY_ROUTE, post(telemetry_request))
+        .fallback(proxy)
+        .with_state(state.clone());
+    tokio::spawn(async move {
+        let stopped = async move { state.shutdown.notified().await };
+        if let Err(e) = axum::serve(listener, app).with_graceful_shutdown(stopped).await {
+            warn!("API gateway stopped: {}", e);
+        }
+    });
+    Ok(addr)
+}
+
+// ============= Lifecycle =============
+
+/// Start the gateway with the selected provider's keys taken from the keychain on every request
+pub async fn start(config: &AppConfig) -> Result<GatewayEndpoint, String> {
+    let state = Arc::new(GatewayState::new(
+        config,
+        LedgerTarget::from_config(config),
+        Box::new(|secret_key| {
+            let api_key = secure_storage::get_credential(secret_key)?;
+            credential_registry::record_used(secret_key);
+            Ok(api_key)
+        }),
+        Box::new(secure_storage::active_store),
+    )?);
+    let addr = serve(state.clone()).await?;
+    let endpoint = GatewayEndpoint {
+        url: format!("http://{}", addr),
+        session_token: state.session_token.clone(),
+    };
+
+    if GATEWAY.set(state).is_err() {
+        warn!("API gateway was already started");
+    }
+    info!("API gateway listening on {}", endpoint.url);
+    Ok(endpoint)
+}
+
+/// Pick up changed provider, keys, rate limit, proxy and database settings
+pub fn apply_settings(config: &AppConfig) {
+    if let Some(state) = GATEWAY.get() {
+        if let Err(e) = state.apply(config) {
+            warn!("Failed to update API gateway settings: {}", e);
+        }
+    }
+}
+
+/// Stop accepting requests; called when the app exits
+pub fn stop() {
+    if let Some(state) = GATEWAY.get() {
+        state.shutdown.notify_one();
+        info!("API gateway stopped");
+    }
+}
+
+/// A key was stored again; stop skipping it if upstream had rejected it
+pub fn reset_key_health(secret_key: &str) {
+    if let Some(state) = GATEWAY.get() {
+        state.pool.lock().unwrap().reset_health(secret_key);
+    }
+}
+
+/// Health and usage of the running gateway's keys
+pub fn key_pool_status() -> Vec<KeyStatus> {
+    GATEWAY
+        .get()
+        .map(|state| state.pool.lock().unwrap().statuses(Instant::now()))
+        .unwrap_or_default()
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::google_oauth::StoredToken;
+    use crate::key_pool::{self, ClaudeKey};
+    use crate::secure_storage::MemoryStore;
+    use crate::usage_ledger::{BudgetAction, BudgetPeriod, ReportGrouping, UsageBudget};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    const MESSAGE: &str = r#"{"type":"message","model":"claude-sonnet-4-20250514","usage":{"input_tokens":1000,"output_tokens":200}}"#;
+
+    /// Upstream stand-in answering with `statuses` in order, reporting each request
+    fn sequence_server(statuses: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for status in statuses {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    break;
+                };
+                let mut buffer = [0u8; 8192];
+                let n = stream.read(&mut buffer).unwrap_or(0);
+                let _ = tx.send(String::from_utf8_lossy(&buffer[..n]).into_owned());
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nRetry-After: 0\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    MESSAGE.len(),
+                    MESSAGE
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        (base, rx)
+    }
+
+    async fn gateway(upstream: &str, ledger: Option<LedgerTarget>) -> (String, String) {
+        let mut config = AppConfig::default();
+        config.advanced.endpoints.claude_api_base = upstream.to_string();
+        config.advanced.api_rate_limit = 60;
+        let (url, state) = gateway_with(&config, ledger).await;
+        (url, state.session_token.clone())
+    }
+
+    /// Pooled keys read as `sk-<id>`, the single key as `sk-real-key`; other secrets are
+    /// kept in a store of the gateway's own
+    async fn gateway_with(
+        config: &AppConfig,
+        ledger: Option<LedgerTarget>,
+    ) -> (String, Arc<GatewayState>) {
+        let keys: KeyProvider = Box::new(|secret_key| {
+            Ok(key_pool::key_id_from_key(secret_key)
+                .map_or("sk-real-key".to_string(), |id| format!("sk-{}", id)))
+        });
+        let secrets: Arc<dyn SecretStore> = Arc::new(MemoryStore::default());
+        let state = Arc::new(
+            GatewayState::new(config, ledger, keys, Box::new(move || secrets.clone())).unwrap(),
+        );
+        let addr = serve(state.clone()).await.unwrap();
+        (format!("http://{}", addr), state)
+    }
+
+    #[tokio::test]
+    async fn test_gateway_injects_key_and_relays_response() {
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let (url, token) = gateway(&upstream, None).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/messages?beta=true", url))
+            .header("x-api-key", &token)
+            .header("anthropic-version", "2023-06-01")
+            .body(r#"{"model":"claude"}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap(), MESSAGE);
+
+        let seen = requests.recv().unwrap().to_lowercase();
+        assert!(seen.starts_with("post /v1/messages?beta=true"));
+        assert!(seen.contains("x-api-key: sk-real-key"));
+        assert!(seen.contains("anthropic-version: 2023-06-01"));
+        assert!(!seen.contains(&token.to_lowercase()));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_requests_without_session_token() {
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let (url, _) = gateway(&upstream, None).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/messages", url))
+            .header("x-api-key", "guessed")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_serves_sessions_to_backend_only() {
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let (url, token) = gateway(&upstream, None).await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/autodoc/sessions/crm", url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+
+        let response = client
+            .get(format!("{}/autodoc/sessions/gateway-none", url))
+            .header("x-api-key", &token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 404);
+
+        let response = client
+            .put(format!("{}/autodoc/sessions/gateway-none", url))
+            .header("x-api-key", &token)
+            .body("not json")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        // Session requests never reach upstream
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_records_backend_telemetry() {
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let (url, token) = gateway(&upstream, None).await;
+        let client = reqwest::Client::new();
+        let event = serde_json::json!({
+            "event": "exploration",
+            "duration_ms": 1500,
+            "pages": 3,
+            "outcome": "failed",
+            "error_code": "GATEWAY_TELEMETRY_TEST",
+        });
+
+        let response = client
+            .post(format!("{}/autodoc/telemetry", url))
+            .json(&event)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+
+        let response = client
+            .post(format!("{}/autodoc/telemetry", url))
+            .header("x-api-key", &token)
+            .json(&event)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+        let recorded = telemetry::get_telemetry_events(None).unwrap();
+        assert!(recorded.iter().any(|record| record.event
+            == TelemetryEvent::Exploration {
+                duration_ms: 1500,
+                pages: 3,
+                outcome: telemetry::ExplorationOutcome::Failed,
+                error_code: Some("GATEWAY_TELEMETRY_TEST".to_string()),
+            }));
+
+        // The backend cannot forge app lifecycle events
+        let response = client
+            .post(format!("{}/autodoc/telemetry", url))
+            .header("x-api-key", &token)
+            .json(&serde_json::json!({ "event": "backend_crash", "uptime_secs": 1 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_serves_google_token_to_backend_only() {
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let mut config = AppConfig::default();
+        config.advanced.endpoints.claude_api_base = upstream;
+        let (url, state) = gateway_with(&config, None).await;
+        let token = StoredToken {
+            access_token: "ya29.test-access-token".to_string(),
+            refresh_token: "1//test-refresh-token".to_string(),
+            expires_at: Utc::now() + chrono::Duration::hours(1),
+            scope: None,
+        };
+        (state.secrets)()
+            .set(
+                google_oauth::TOKEN_KEY,
+                &serde_json::to_string(&token).unwrap(),
+            )
+            .unwrap();
+        let client = reqwest::Client::new();
+
+        let response = client
+            .get(format!("{}/autodoc/google/token", url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 401);
+
+        let response = client
+            .get(format!("{}/autodoc/google/token", url))
+            .header("x-api-key", &state.session_token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["access_token"], "ya29.test-access-token");
+
+        let response = client
+            .post(format!("{}/autodoc/google/token", url))
+            .header("x-api-key", &state.session_token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 405);
+
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_stops_listening_on_shutdown() {
+        let (upstream, _requests) = sequence_server(vec!["200 OK"]);
+        let mut config = AppConfig::default();
+        config.advanced.endpoints.claude_api_base = upstream;
+        let (url, state) = gateway_with(&config, None).await;
+
+        state.shutdown.notify_one();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(reqwest::get(format!("{}/v1/models", url)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_retries_rate_limited_and_overloaded() {
+        let (upstream, requests) =
+            sequence_server(vec!["429 Too Many Requests", "529 Overloaded", "200 OK"]);
+        let (url, token) = gateway(&upstream, None).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/messages", url))
+            .header("x-api-key", &token)
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.try_iter().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gateway_fails_over_to_next_key() {
+        let (upstream, requests) =
+            sequence_server(vec!["429 Too Many Requests", "401 Unauthorized", "200 OK"]);
+        let mut config = AppConfig::default();
+        config.advanced.endpoints.claude_api_base = upstream;
+        config.auth.claude_keys = [("primary", 10), ("spare", 5), ("backup", 0)]
+            .into_iter()
+            .map(|(id, priority)| ClaudeKey {
+                id: id.to_string(),
+                name: id.to_string(),
+                rate_limit: 60,
+                priority,
+                enabled: true,
+                secret: None,
+            })
+            .collect();
+        let (url, state) = gateway_with(&config, None).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/v1/messages", url))
+            .header("x-api-key", &state.session_token)
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        response.text().await.unwrap();
+
+        let keys: Vec<String> = requests
+            .try_iter()
+            .map(|seen| {
+                let seen = seen.to_lowercase();
+                let line = seen.lines().find(|l| l.starts_with("x-api-key:")).unwrap();
+                line.trim_start_matches("x-api-key:").trim().to_string()
+            })
+            .collect();
+        assert_eq!(keys, vec!["sk-primary", "sk-spare", "sk-backup"]);
+
+        let mut statuses = Vec::new();
+        for _ in 0..50 {
+            statuses = state.pool.lock().unwrap().statuses(Instant::now());
+            if statuses[2].input_tokens > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(statuses[0].rate_limited, 1);
+        assert!(matches!(
+            statuses[1].health,
+            key_pool::KeyHealth::Invalid { .. }
+        ));
+        assert_eq!(statuses[1].last_error.as_deref(), Some("401 Unauthorized"));
+        assert_eq!((statuses[2].requests, statuses[2].input_tokens), (1, 1000));
+    }
+
+    #[tokio::test]
+    async fn test_gateway_records_usage_and_pauses_on_budget() {
+        let dir = std::env::temp_dir().join("autodoc-api-gateway-test-ledger");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = dir.join("autodoc.db");
+        let ledger = LedgerTarget {
+            database: database.clone(),
+            provider: LlmProvider::Anthropic,
+            default_model: "claude-sonnet-4-20250514".to_string(),
+        };
+
+        let (upstream, requests) = sequence_server(vec!["200 OK"]);
+        let (url, token) = gateway(&upstream, Some(ledger)).await;
+        let send = || {
+            reqwest::Client::new()
+                .post(format!("{}/v1/messages", url))
+                .header("x-api-key", &token)
+                .header(PROJECT_HEADER, "p1")
+                .header(SESSION_HEADER, "s1")
+                .body(r#"{"messages":[{"role":"user","content":[{"type":"image"},{"type":"text"}]}]}"#)
+                .send()
+        };
+
+        let response = send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        response.text().await.unwrap();
+        assert!(!requests.recv().unwrap().contains(PROJECT_HEADER));
+
+        // Usage is written in the background once the body has been relayed
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            rows =
+                usage_ledger::report(&database, ReportGrouping::Project, None, None, None).unwrap();
+            if !rows.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, "p1");
+        assert_eq!((rows[0].input_tokens, rows[0].output_tokens), (1000, 200));
+        assert_eq!(rows[0].images, 1);
+
+        usage_ledger::save_budget(
+            &database,
+            &UsageBudget {
+                id: None,
+                project_id: Some("p1".to_string()),
+                period: BudgetPeriod::Daily,
+                limit_usd: 0.001,
+                action: BudgetAction::Pause,
+            },
+        )
+        .unwrap();
+        assert_eq!(send().await.unwrap().status(), 403);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_forwarded_headers_use_provider_auth_scheme() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("session"));
+        headers.insert(PROJECT_HEADER, HeaderValue::from_static("p1"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        let claude = forwarded_headers(&headers, LlmProvider::Anthropic, "sk-ant").unwrap();
+        assert_eq!(claude["x-api-key"], "sk-ant");
+        assert!(claude.get(PROJECT_HEADER).is_none());
+
+        let openai = forwarded_headers(&headers, LlmProvider::OpenaiCompatible, "corp").unwrap();
+        assert_eq!(openai[header::AUTHORIZATION], "Bearer corp");
+        assert!(openai.get("x-api-key").is_none());
+        assert_eq!(openai["content-type"], "application/json");
+
+        let ollama = forwarded_headers(&headers, LlmProvider::Ollama, "").unwrap();
+        assert!(ollama.get(header::AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn test_token_bucket_limits_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        let wait = bucket.try_take(start).unwrap_err();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30));
+
+        // Half a minute refills one token at 2 per minute
+        assert!(bucket.try_take(start + Duration::from_secs(30)).is_ok());
+
+        bucket.set_rate(60, start + Duration::from_secs(30));
+        assert!(bucket.try_take(start + Duration::from_secs(31)).is_ok());
+    }
+}
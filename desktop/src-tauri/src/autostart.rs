@@ -0,0 +1,255 @@
+// Registration is implemented for XDG desktops only; other platforms report unsupported
+#![cfg_attr(not(target_os = "linux"), allow(dead_code))]
+
+use crate::config;
+use crate::i18n::tr;
+use log::{info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+const DESKTOP_FILE_NAME: &str = "autodoc-agent.desktop";
+
+/// Launch flag telling the app to start hidden in the tray
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct AutostartStatus {
+    /// Whether this platform supports autostart registration
+    pub supported: bool,
+    /// What the saved settings ask for
+    pub enabled_in_config: bool,
+    /// Whether an autostart entry actually exists
+    pub registered: bool,
+    /// Whether the registered entry launches this installation
+    pub points_to_current_binary: bool,
+    pub entry_path: Option<PathBuf>,
+}
+
+// ============= Desktop Entry =============
+
+/// `~/.config/autostart`, per the XDG autostart specification
+#[cfg(not(test))]
+fn autostart_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("autostart"))
+}
+
+/// Saving settings in tests must not register the test binary with the real session
+#[cfg(test)]
+fn autostart_dir() -> Option<PathBuf> {
+    Some(std::env::temp_dir().join("autodoc-autostart-test-session"))
+}
+
+/// Binary the session should launch. Inside an AppImage `current_exe` is a temporary
+/// mount, so the AppImage file itself is used instead.
+fn launch_target() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| tr!("autostart-exe-path-failed", error = e))
+}
+
+/// Quote an Exec argument as required by the Desktop Entry specification.
+/// `%` is doubled so it is not read as a field code, and since Exec is also a
+/// string value, every backslash left by the quoting is escaped once more.
+fn quote_exec_arg(arg: &str) -> String {
+    let needs_quotes = arg
+        .chars()
+        .any(|c| c.is_whitespace() || "\"'\\><~|&;$*?#()`".contains(c));
+
+    let mut quoted = String::new();
+    if needs_quotes {
+        quoted.push('"');
+    }
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' if needs_quotes => quoted.push_str("\\\\"),
+            '\\' => quoted.push_str("\\\\\\"),
+            '%' => quoted.push('%'),
+            _ => {}
+        }
+        quoted.push(c);
+    }
+    if needs_quotes {
+        quoted.push('"');
+    }
+    quoted
+}
+
+fn exec_line(binary: &Path) -> String {
+    format!(
+        "{} {}",
+        quote_exec_arg(&binary.to_string_lossy()),
+        MINIMIZED_FLAG
+    )
+}
+
+fn desktop_entry(binary: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=AutoDoc Agent\n\
+         Comment=Start AutoDoc Agent in the system tray\n\
+         Exec={}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        exec_line(binary)
+    )
+}
+
+fn read_exec(entry_path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(entry_path).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Exec="))
+        .map(|exec| exec.trim().to_string())
+}
+
+// ============= Registration =============
+
+fn enable_in(dir: &Path, binary: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| tr!("autostart-create-dir-failed", error = e))?;
+    let entry_path = dir.join(DESKTOP_FILE_NAME);
+    std::fs::write(&entry_path, desktop_entry(binary))
+        .map_err(|e| tr!("autostart-write-failed", error = e))?;
+    Ok(entry_path)
+}
+
+fn disable_in(dir: &Path) -> Result<(), String> {
+    let entry_path = dir.join(DESKTOP_FILE_NAME);
+    match std::fs::remove_file(&entry_path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(tr!("autostart-remove-failed", error = e)),
+    }
+}
+
+fn status_in(dir: &Path, binary: &Path, enabled_in_config: bool) -> AutostartStatus {
+    let entry_path = dir.join(DESKTOP_FILE_NAME);
+    let exec = read_exec(&entry_path);
+
+    AutostartStatus {
+        supported: true,
+        enabled_in_config,
+        registered: exec.is_some(),
+        points_to_current_binary: exec.as_deref() == Some(exec_line(binary).as_str()),
+        entry_path: exec.map(|_| entry_path),
+    }
+}
+
+/// Make the autostart registration match `enabled`
+#[cfg(target_os = "linux")]
+pub fn sync(enabled: bool) -> Result<(), String> {
+    let dir = autostart_dir().ok_or_else(|| tr!("common-config-dir-unavailable"))?;
+    if enabled {
+        let entry_path = enable_in(&dir, &launch_target()?)?;
+        info!("Autostart entry written to {}", entry_path.display());
+    } else {
+        disable_in(&dir)?;
+        info!("Autostart entry removed");
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn sync(enabled: bool) -> Result<(), String> {
+    if enabled {
+        info!("Autostart is not supported on this platform yet");
+    }
+    Ok(())
+}
+
+// ============= Tauri Commands =============
+
+/// Report whether autostart is actually registered, independent of the saved setting
+#[tauri::command]
+pub fn get_autostart_status() -> AutostartStatus {
+    let enabled_in_config = config::load_settings().basic.auto_start;
+
+    let dir = match autostart_dir() {
+        Some(dir) if cfg!(target_os = "linux") => dir,
+        _ => {
+            return AutostartStatus {
+                supported: false,
+                enabled_in_config,
+                registered: false,
+                points_to_current_binary: false,
+                entry_path: None,
+            };
+        }
+    };
+
+    let binary = launch_target().unwrap_or_else(|e| {
+        warn!("Cannot determine autostart binary: {}", e);
+        PathBuf::new()
+    });
+    status_in(&dir, &binary, enabled_in_config)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-autostart-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_enable_writes_entry_with_minimized_flag() {
+        let dir = test_dir("enable");
+        let binary = Path::new("/opt/AutoDoc Agent/autodoc-agent");
+
+        let entry_path = enable_in(&dir, binary).unwrap();
+        let content = std::fs::read_to_string(&entry_path).unwrap();
+        assert!(content.starts_with("[Desktop Entry]"));
+        assert!(content.contains("Exec=\"/opt/AutoDoc Agent/autodoc-agent\" --minimized"));
+
+        let status = status_in(&dir, binary, true);
+        assert!(status.registered);
+        assert!(status.points_to_current_binary);
+        assert_eq!(status.entry_path, Some(entry_path));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_disable_removes_entry_and_is_idempotent() {
+        let dir = test_dir("disable");
+        let binary = Path::new("/usr/bin/autodoc-agent");
+
+        enable_in(&dir, binary).unwrap();
+        disable_in(&dir).unwrap();
+        disable_in(&dir).unwrap();
+
+        let status = status_in(&dir, binary, false);
+        assert!(!status.registered);
+        assert_eq!(status.entry_path, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_entry_for_other_binary_is_reported() {
+        let dir = test_dir("stale");
+        enable_in(&dir, Path::new("/old/autodoc-agent")).unwrap();
+
+        let status = status_in(&dir, Path::new("/new/autodoc-agent"), true);
+        assert!(status.registered);
+        assert!(!status.points_to_current_binary);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quote_exec_arg() {
+        assert_eq!(quote_exec_arg("/usr/bin/autodoc"), "/usr/bin/autodoc");
+        assert_eq!(quote_exec_arg("/a b/c"), "\"/a b/c\"");
+        assert_eq!(quote_exec_arg("/a$b"), "\"/a\\\\$b\"");
+        assert_eq!(quote_exec_arg("/a%b"), "/a%%b");
+        assert_eq!(quote_exec_arg("/a b%c"), "\"/a b%%c\"");
+        assert_eq!(quote_exec_arg("C:\\x"), "\"C:\\\\\\\\x\"");
+    }
+}
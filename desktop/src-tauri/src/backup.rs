@@ -0,0 +1,1058 @@
+use crate::config::{self, AppConfig, StorageSettings, ValidationIssue};
+use crate::config_bundle::{self, ConfigBundle, OpenedBundle, RemappedPath};
+use crate::credential_registry::CredentialSource;
+use crate::i18n::tr;
+use crate::{path_guard, retention, secure_storage, sidecar, snapshots};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Bumped whenever the archive layout changes incompatibly
+const BACKUP_FORMAT_VERSION: u32 = 1;
+/// Written last, once every checksum is known
+const MANIFEST_ENTRY: &str = "manifest.json";
+/// A `ConfigBundle` in JSON, with secrets encrypted as in a config export
+const CONFIG_ENTRY: &str = "config.json";
+const ZSTD_LEVEL: i32 = 3;
+/// SQLite side files that belong to the database
+const DATABASE_SIDE_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+/// How long the database copy waits for the backend's write lock
+const DATABASE_BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Data directories in the archive, each under its own top-level folder
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupComponent {
+    Database,
+    Snapshots,
+    Screenshots,
+}
+
+impl BackupComponent {
+    const ALL: [BackupComponent; 3] = [
+        BackupComponent::Database,
+        BackupComponent::Snapshots,
+        BackupComponent::Screenshots,
+    ];
+
+    fn prefix(self) -> &'static str {
+        match self {
+            BackupComponent::Database => "database",
+            BackupComponent::Snapshots => "snapshots",
+            BackupComponent::Screenshots => "screenshots",
+        }
+    }
+
+    /// Directory the component's relative paths are resolved against
+    fn base_dir(self, storage: &StorageSettings) -> PathBuf {
+        match self {
+            BackupComponent::Database => storage
+                .database_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            BackupComponent::Snapshots => storage.snapshot_storage_path.clone(),
+            BackupComponent::Screenshots => storage.screenshot_storage_path.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupFile {
+    /// Archive path, always `/`-separated
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupProject {
+    pub project_id: String,
+    pub snapshot_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    /// File name of the database, so restores can find it under another path
+    pub database_file: Option<String>,
+    pub projects: Vec<BackupProject>,
+    pub secrets_included: usize,
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupSummary {
+    pub path: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+    pub projects: usize,
+    pub secrets_included: usize,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RestoreReport {
+    pub config_restored: bool,
+    pub remapped_paths: Vec<RemappedPath>,
+    pub projects: Vec<String>,
+    pub files: usize,
+    pub bytes: u64,
+    pub secrets_imported: usize,
+    pub issues: Vec<ValidationIssue>,
+    pub applied: bool,
+}
+
+// ============= Archive Paths =============
+
+/// Archive path for `relative` below a component, `/`-separated on every platform
+fn archive_path(component: BackupComponent, relative: &Path) -> String {
+    let mut path = component.prefix().to_string();
+    for part in relative.components() {
+        path.push('/');
+        path.push_str(&part.as_os_str().to_string_lossy());
+    }
+    path
+}
+
+/// Split an archive path into component and relative path. Anything that is
+/// not a plain relative path (`..`, absolute, unknown prefix) is rejected.
+fn parse_archive_path(path: &str) -> Option<(BackupComponent, PathBuf)> {
+    let (prefix, rest) = path.split_once('/')?;
+    let component = BackupComponent::ALL
+        .into_iter()
+        .find(|c| c.prefix() == prefix)?;
+
+    let relative = PathBuf::from(rest);
+    let plain = relative
+        .components()
+        .all(|part| matches!(part, Component::Normal(_)));
+    (plain && relative.components().next().is_some()).then_some((component, relative))
+}
+
+/// Snapshot id a data file belongs to, matching how snapshots and screenshots are laid out
+fn owning_snapshot(component: BackupComponent, relative: &Path) -> Option<String> {
+    let mut parts = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().into_owned());
+    match component {
+        BackupComponent::Snapshots => {
+            let first = parts.next()?;
+            (first == snapshots::SNAPSHOTS_DIR_NAME)
+                .then(|| parts.next())
+                .flatten()
+        }
+        // Only folders count; loose files have no owner
+        BackupComponent::Screenshots => {
+            let first = parts.next()?;
+            parts.next().map(|_| first)
+        }
+        BackupComponent::Database => None,
+    }
+}
+
+// ============= Creating =============
+
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+    read: u64,
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.read += read as u64;
+        Ok(read)
+    }
+}
+
+/// Append `reader` as `path`, hashing exactly the bytes that go into the archive
+fn append_entry<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    size: u64,
+    mtime: u64,
+    reader: impl Read,
+) -> io::Result<BackupFile> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+
+    let mut hashing = HashingReader {
+        inner: reader.take(size),
+        hasher: Sha256::new(),
+        read: 0,
+    };
+    builder.append_data(&mut header, path, &mut hashing)?;
+    if hashing.read != size {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{} changed while it was being archived", path),
+        ));
+    }
+
+    Ok(BackupFile {
+        path: path.to_string(),
+        size,
+        sha256: format!("{:x}", hashing.hasher.finalize()),
+    })
+}
+
+/// Consistent copy of the database, even while the backend writes to it. The copy
+/// includes whatever is still in the WAL and needs no side files.
+fn copy_database(source: &Path, copy: &Path) -> rusqlite::Result<()> {
+    let _ = std::fs::remove_file(copy);
+    let conn = Connection::open_with_flags(
+        source,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(DATABASE_BUSY_TIMEOUT)?;
+    conn.execute("VACUUM INTO ?1", [copy.to_string_lossy().into_owned()])?;
+    Ok(())
+}
+
+/// Snapshot and screenshot files as (archive path, absolute path); the database
+/// is copied separately
+fn collect_sources(storage: &StorageSettings, exclude: &[&Path]) -> Vec<(String, PathBuf)> {
+    let mut sources = Vec::new();
+    for component in [BackupComponent::Snapshots, BackupComponent::Screenshots] {
+        let base = component.base_dir(storage);
+        for (path, _) in path_guard::walk_files(&base) {
+            // A backup written into storage must not include itself
+            if exclude.contains(&path.as_path()) {
+                continue;
+            }
+            if let Ok(relative) = path.strip_prefix(&base) {
+                sources.push((archive_path(component, relative), path.clone()));
+            }
+        }
+    }
+    sources
+}
+
+fn project_index(snapshot_root: &Path) -> Vec<BackupProject> {
+    let mut projects: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for entry in snapshots::list_snapshots(snapshot_root) {
+        projects
+            .entry(entry.manifest.project_id)
+            .or_default()
+            .push(entry.manifest.id);
+    }
+    projects
+        .into_iter()
+        .map(|(project_id, snapshot_ids)| BackupProject {
+            project_id,
+            snapshot_ids,
+        })
+        .collect()
+}
+
+/// Write the archive to `output` through a temporary file, so a failed backup
+/// never leaves a truncated archive behind
+pub(crate) fn write_backup(
+    output: &Path,
+    config: &AppConfig,
+    secrets: &BTreeMap<String, String>,
+    passphrase: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<BackupManifest, String> {
+    let storage = &config.storage;
+    let bundle = config_bundle::build_bundle(config, secrets, passphrase, now)?;
+    let bundle_json = serde_json::to_vec_pretty(&bundle)
+        .map_err(|e| tr!("common-serialize-config-failed", error = e))?;
+
+    let mut part = output.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let mut database_copy = part.as_os_str().to_owned();
+    database_copy.push("-database");
+    let database_copy = PathBuf::from(database_copy);
+
+    let write = || -> io::Result<BackupManifest> {
+        let encoder = zstd::Encoder::new(File::create(&part)?, ZSTD_LEVEL)?;
+        let mut builder = tar::Builder::new(encoder);
+
+        let mtime = now.timestamp().max(0) as u64;
+        let mut files = vec![append_entry(
+            &mut builder,
+            CONFIG_ENTRY,
+            bundle_json.len() as u64,
+            mtime,
+            bundle_json.as_slice(),
+        )?];
+
+        let database = &storage.database_path;
+        if let (Some(name), true) = (database.file_name(), database.is_file()) {
+            copy_database(database, &database_copy).map_err(io::Error::other)?;
+            let file = File::open(&database_copy)?;
+            let size = file.metadata()?.len();
+            files.push(append_entry(
+                &mut builder,
+                &archive_path(BackupComponent::Database, Path::new(name)),
+                size,
+                mtime,
+                file,
+            )?);
+        }
+
+        for (archive_path, source) in collect_sources(storage, &[output, &part, &database_copy]) {
+            let file = File::open(&source)?;
+            let metadata = file.metadata()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .map(|t| DateTime::<Utc>::from(t).timestamp().max(0) as u64)
+                .unwrap_or(mtime);
+            files.push(append_entry(
+                &mut builder,
+                &archive_path,
+                metadata.len(),
+                modified,
+                file,
+            )?);
+        }
+
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now,
+            database_file: storage
+                .database_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned()),
+            projects: project_index(&storage.snapshot_storage_path),
+            secrets_included: if bundle.secrets.is_some() {
+                secrets.len()
+            } else {
+                0
+            },
+            files,
+        };
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        append_entry(
+            &mut builder,
+            MANIFEST_ENTRY,
+            manifest_json.len() as u64,
+            mtime,
+            manifest_json.as_slice(),
+        )?;
+
+        builder.into_inner()?.finish()?.sync_all()?;
+        Ok(manifest)
+    };
+
+    let result = write();
+    let _ = std::fs::remove_file(&database_copy);
+    let manifest = result.map_err(|e| {
+        let _ = std::fs::remove_file(&part);
+        tr!("backup-write-failed", error = e)
+    })?;
+    std::fs::rename(&part, output).map_err(|e| tr!("backup-write-failed", error = e))?;
+    Ok(manifest)
+}
+
+// ============= Validating =============
+
+fn open_archive(
+    path: &Path,
+) -> Result<tar::Archive<zstd::Decoder<'static, io::BufReader<File>>>, String> {
+    let file = File::open(path).map_err(|e| tr!("backup-read-failed", error = e))?;
+    let decoder = zstd::Decoder::new(file).map_err(|e| tr!("backup-read-failed", error = e))?;
+    Ok(tar::Archive::new(decoder))
+}
+
+/// A checked archive: manifest and config bundle parsed, every entry matching its checksum
+pub(crate) struct VerifiedBackup {
+    pub manifest: BackupManifest,
+    pub bundle: ConfigBundle,
+}
+
+/// Read the whole archive once, hashing every entry, then check it against the manifest
+pub(crate) fn verify_backup(path: &Path) -> Result<VerifiedBackup, String> {
+    let mut archive = open_archive(path)?;
+    let mut seen: HashMap<String, (u64, String)> = HashMap::new();
+    let mut manifest_json = None;
+    let mut bundle_json = None;
+
+    let entries = archive
+        .entries()
+        .map_err(|e| tr!("backup-read-failed", error = e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| tr!("backup-read-failed", error = e))?;
+        let name = entry
+            .path()
+            .map_err(|e| tr!("backup-read-failed", error = e))?
+            .to_string_lossy()
+            .into_owned();
+
+        let mut content = Vec::new();
+        let mut hashing = HashingReader {
+            inner: &mut entry,
+            hasher: Sha256::new(),
+            read: 0,
+        };
+        if name == MANIFEST_ENTRY || name == CONFIG_ENTRY {
+            hashing
+                .read_to_end(&mut content)
+                .map_err(|e| tr!("backup-read-failed", error = e))?;
+        } else {
+            io::copy(&mut hashing, &mut io::sink())
+                .map_err(|e| tr!("backup-read-failed", error = e))?;
+        }
+        let digest = format!("{:x}", hashing.hasher.finalize());
+        let size = hashing.read;
+
+        match name.as_str() {
+            MANIFEST_ENTRY => manifest_json = Some(content),
+            CONFIG_ENTRY => {
+                bundle_json = Some(content);
+                seen.insert(name, (size, digest));
+            }
+            _ => {
+                seen.insert(name, (size, digest));
+            }
+        }
+    }
+
+    let manifest: BackupManifest = manifest_json
+        .ok_or_else(|| tr!("backup-manifest-missing"))
+        .and_then(|json| {
+            serde_json::from_slice(&json).map_err(|e| tr!("backup-manifest-invalid", error = e))
+        })?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(tr!(
+            "backup-version-too-new",
+            version = manifest.format_version
+        ));
+    }
+
+    for file in &manifest.files {
+        match seen.remove(&file.path) {
+            Some((size, digest)) if size == file.size && digest == file.sha256 => {}
+            Some(_) => return Err(tr!("backup-checksum-mismatch", path = file.path)),
+            None => return Err(tr!("backup-entry-missing", path = file.path)),
+        }
+        if file.path != CONFIG_ENTRY && parse_archive_path(&file.path).is_none() {
+            return Err(tr!("backup-entry-invalid", path = file.path));
+        }
+    }
+    if let Some(extra) = seen.keys().next() {
+        return Err(tr!("backup-entry-unexpected", path = extra));
+    }
+
+    let bundle = bundle_json
+        .ok_or_else(|| tr!("backup-entry-missing", path = CONFIG_ENTRY))
+        .and_then(|json| {
+            config_bundle::parse_bundle(
+                &String::from_utf8_lossy(&json),
+                config_bundle::BundleFormat::Json,
+            )
+        })?;
+
+    Ok(VerifiedBackup { manifest, bundle })
+}
+
+// ============= Restoring =============
+
+/// Snapshot ids to restore, or `None` for everything
+fn selected_snapshots(
+    manifest: &BackupManifest,
+    projects: Option<&[String]>,
+) -> Option<BTreeSet<String>> {
+    let projects = projects?;
+    Some(
+        manifest
+            .projects
+            .iter()
+            .filter(|p| projects.contains(&p.project_id))
+            .flat_map(|p| p.snapshot_ids.iter().cloned())
+            .collect(),
+    )
+}
+
+/// Where an archive entry goes under the destination storage settings
+fn destination(
+    component: BackupComponent,
+    relative: &Path,
+    manifest: &BackupManifest,
+    storage: &StorageSettings,
+) -> PathBuf {
+    if component == BackupComponent::Database {
+        // The database may have been renamed; keep its side files next to it
+        if let (Some(original), Some(current)) = (
+            manifest.database_file.as_deref(),
+            storage.database_path.file_name(),
+        ) {
+            let name = relative.to_string_lossy();
+            if let Some(suffix) = name.strip_prefix(original) {
+                let mut renamed = current.to_os_string();
+                renamed.push(suffix);
+                return component.base_dir(storage).join(renamed);
+            }
+        }
+    }
+    component.base_dir(storage).join(relative)
+}
+
+/// Extract the selected data entries into `storage`. With `dry_run` only counts.
+/// Existing files are only replaced when `overwrite` is set.
+pub(crate) fn extract(
+    archive_path: &Path,
+    manifest: &BackupManifest,
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+    projects: Option<&[String]>,
+    dry_run: bool,
+    overwrite: bool,
+) -> Result<(usize, u64), String> {
+    let selected = selected_snapshots(manifest, projects);
+    let expected: HashMap<&str, &BackupFile> = manifest
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f))
+        .collect();
+
+    let mut archive = open_archive(archive_path)?;
+    let entries = archive
+        .entries()
+        .map_err(|e| tr!("backup-read-failed", error = e))?;
+    let (mut files, mut bytes) = (0, 0);
+
+    for entry in entries {
+        let mut entry = entry.map_err(|e| tr!("backup-read-failed", error = e))?;
+        let name = entry
+            .path()
+            .map_err(|e| tr!("backup-read-failed", error = e))?
+            .to_string_lossy()
+            .into_owned();
+        let Some((component, relative)) = parse_archive_path(&name) else {
+            continue;
+        };
+        let Some(file) = expected.get(name.as_str()) else {
+            return Err(tr!("backup-entry-unexpected", path = name));
+        };
+
+        if let Some(ref selected) = selected {
+            let owned =
+                owning_snapshot(component, &relative).is_some_and(|id| selected.contains(&id));
+            if !owned {
+                continue;
+            }
+        }
+
+        let target = path_guard::validate_within(
+            &destination(component, &relative, manifest, storage),
+            roots,
+        )?;
+        if !overwrite && std::fs::symlink_metadata(&target).is_ok() {
+            return Err(tr!("backup-restore-exists", path = target.display()));
+        }
+        files += 1;
+        bytes += file.size;
+        if dry_run {
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            path_guard::create_dir_secure(parent, roots)?;
+        }
+        let mut part = target.as_os_str().to_owned();
+        part.push(".part");
+        let part = PathBuf::from(part);
+
+        // The archive may have changed since it was verified; check again on the way out
+        let mut hashing = HashingReader {
+            inner: &mut entry,
+            hasher: Sha256::new(),
+            read: 0,
+        };
+        let mut write = || -> io::Result<()> {
+            let mut out = File::create(&part)?;
+            io::copy(&mut hashing, &mut out)?;
+            out.sync_all()
+        };
+        write().map_err(|e| {
+            tr!(
+                "backup-restore-file-failed",
+                path = target.display(),
+                error = e
+            )
+        })?;
+        if format!("{:x}", hashing.hasher.finalize()) != file.sha256 {
+            let _ = std::fs::remove_file(&part);
+            return Err(tr!("backup-checksum-mismatch", path = name));
+        }
+        std::fs::rename(&part, &target).map_err(|e| {
+            tr!(
+                "backup-restore-file-failed",
+                path = target.display(),
+                error = e
+            )
+        })?;
+
+        // Side files of a replaced database would be applied to the restored one
+        let is_database = component == BackupComponent::Database
+            && manifest.database_file.as_deref().map(Path::new) == Some(relative.as_path());
+        if is_database {
+            for suffix in DATABASE_SIDE_SUFFIXES {
+                if expected.contains_key(format!("{}{}", name, suffix).as_str()) {
+                    continue;
+                }
+                let mut side = target.as_os_str().to_owned();
+                side.push(suffix);
+                match std::fs::remove_file(&side) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        return Err(tr!(
+                            "backup-restore-file-failed",
+                            path = Path::new(&side).display(),
+                            error = e
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((files, bytes))
+}
+
+// ============= Tauri Commands =============
+
+/// Package config, database, snapshots and screenshots into a `.tar.zst` archive
+#[tauri::command]
+pub async fn create_backup(
+    path: PathBuf,
+    include_secrets: bool,
+    passphrase: Option<String>,
+) -> Result<BackupSummary, String> {
+    let output = config::validate_path(&path)?;
+    if include_secrets && passphrase.as_deref().unwrap_or("").is_empty() {
+        return Err(tr!("bundle-export-password-required"));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let current = config::load_config_with_secrets()?;
+        let mut secrets = BTreeMap::new();
+        if include_secrets {
+            for key in config_bundle::exportable_secret_keys(&current) {
+                if let Ok(value) = secure_storage::get_credential(&key) {
+                    secrets.insert(key, value);
+                }
+            }
+        }
+
+        let manifest = write_backup(
+            &output,
+            &current,
+            &secrets,
+            passphrase.as_deref(),
+            Utc::now(),
+        )?;
+        info!(
+            "Backup written to {} ({} files)",
+            output.display(),
+            manifest.files.len()
+        );
+
+        Ok(BackupSummary {
+            path: output,
+            files: manifest.files.len(),
+            bytes: manifest.files.iter().map(|f| f.size).sum(),
+            projects: manifest.projects.len(),
+            secrets_included: manifest.secrets_included,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Verify an archive and list its contents, e.g. to pick projects to restore
+#[tauri::command]
+pub async fn inspect_backup(path: PathBuf) -> Result<BackupManifest, String> {
+    let input = config::validate_path(&path)?;
+    tokio::task::spawn_blocking(move || verify_backup(&input).map(|backup| backup.manifest))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Restore a backup. With `projects` only those projects' snapshots and screenshots
+/// are restored into the current storage paths; otherwise everything is restored,
+/// including config and, given the passphrase, keychain secrets. Files already on
+/// disk are left alone and fail the restore unless `overwrite` is set.
+#[tauri::command]
+pub async fn restore_backup<R: Runtime>(
+    app: AppHandle<R>,
+    path: PathBuf,
+    passphrase: Option<String>,
+    projects: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    overwrite: Option<bool>,
+) -> Result<RestoreReport, String> {
+    let input = config::validate_path(&path)?;
+    let dry_run = dry_run.unwrap_or(false);
+    let overwrite = overwrite.unwrap_or(false);
+
+    tokio::task::spawn_blocking(move || {
+        let VerifiedBackup {
+            manifest,
+            mut bundle,
+        } = verify_backup(&input)?;
+
+        // Destinations are checked against the saved roots, never the archive's own
+        let roots = path_guard::storage_roots();
+        if let Some(ref projects) = projects {
+            let storage = config::load_settings().storage;
+            if !dry_run {
+                // Existing files are found before anything is written
+                extract(&input, &manifest, &storage, &roots, Some(projects), true, overwrite)?;
+            }
+            let (files, bytes) = extract(
+                &input,
+                &manifest,
+                &storage,
+                &roots,
+                Some(projects),
+                dry_run,
+                overwrite,
+            )?;
+            return Ok(RestoreReport {
+                projects: projects.clone(),
+                files,
+                bytes,
+                applied: !dry_run,
+                ..Default::default()
+            });
+        }
+
+        // Secrets stay in the keychain as they are unless the passphrase is given
+        if passphrase.is_none() {
+            bundle.secrets = None;
+        }
+        let home = dirs::home_dir();
+        let OpenedBundle {
+            config: imported,
+            remapped_paths,
+            secrets,
+        } = config_bundle::open_bundle(bundle, passphrase.as_deref(), home.as_deref())?;
+
+        let issues = config::validate_settings(&imported);
+        let (files, bytes) = extract(
+            &input,
+            &manifest,
+            &imported.storage,
+            &roots,
+            None,
+            true,
+            overwrite,
+        )?;
+        let mut report = RestoreReport {
+            config_restored: false,
+            remapped_paths,
+            projects: manifest
+                .projects
+                .iter()
+                .map(|p| p.project_id.clone())
+                .collect(),
+            files,
+            bytes,
+            secrets_imported: 0,
+            issues,
+            applied: false,
+        };
+        if !report.issues.is_empty() || dry_run {
+            return Ok(report);
+        }
+
+        // The backend keeps the database open and retention deletes snapshots;
+        // neither may run while their files are replaced
+        let _retention = retention::lock()?;
+        let backend = app.try_state::<sidecar::BackendProcess>();
+        let suspended = match &backend {
+            Some(backend) => backend.suspend()?,
+            None => None,
+        };
+
+        let mut apply = || -> Result<(), String> {
+            // Data first, so the config never points at storage that was not restored
+            extract(
+                &input,
+                &manifest,
+                &imported.storage,
+                &roots,
+                None,
+                false,
+                overwrite,
+            )?;
+
+            for (key, value) in &secrets {
+                if !config_bundle::is_importable_secret(key, &imported) {
+                    warn!("Ignoring unknown secret '{}' in backup", key);
+                    continue;
+                }
+                secure_storage::store_credential_from(key, value, CredentialSource::Import)?;
+                report.secrets_imported += 1;
+            }
+            config::save_config(imported.without_secrets())
+        };
+        let result = apply();
+
+        // Restarted with the restored settings
+        if let (Some(backend), Some(launch)) = (backend, suspended) {
+            if let Err(e) = sidecar::resume(&app, &backend, launch) {
+                warn!("Failed to restart the backend after the restore: {}", e);
+            }
+        }
+        result?;
+
+        info!(
+            "Backup restored from {} ({} files, {} paths remapped)",
+            input.display(),
+            report.files,
+            report.remapped_paths.len()
+        );
+        report.config_restored = true;
+        report.applied = true;
+        Ok(report)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshots::test_support::write_snapshot;
+
+    fn fixture(name: &str) -> (PathBuf, AppConfig) {
+        let root = std::env::temp_dir().join(format!("autodoc-backup-test-{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let mut config = AppConfig::default();
+        config.storage.allowed_roots = vec![root.clone()];
+        config.storage.snapshot_storage_path = root.join("src/snapshots");
+        config.storage.screenshot_storage_path = root.join("src/screenshots");
+        config.storage.database_path = root.join("src/autodoc.db");
+
+        let now = Utc::now();
+        let storage = &config.storage;
+        write_snapshot(
+            &storage.snapshot_storage_path,
+            "a1",
+            "proj-a",
+            (1, 0, 0),
+            now,
+        );
+        write_snapshot(
+            &storage.snapshot_storage_path,
+            "b1",
+            "proj-b",
+            (1, 0, 0),
+            now,
+        );
+        std::fs::create_dir_all(storage.screenshot_storage_path.join("a1")).unwrap();
+        std::fs::create_dir_all(storage.screenshot_storage_path.join("b1")).unwrap();
+        std::fs::write(
+            storage.screenshot_storage_path.join("a1/home.png"),
+            b"png-a",
+        )
+        .unwrap();
+        std::fs::write(
+            storage.screenshot_storage_path.join("b1/home.png"),
+            b"png-b",
+        )
+        .unwrap();
+        let db = Connection::open(&storage.database_path).unwrap();
+        db.execute_batch(
+            "PRAGMA journal_mode = WAL; CREATE TABLE pages (url TEXT); \
+             INSERT INTO pages VALUES ('https://example.com/');",
+        )
+        .unwrap();
+        (root, config)
+    }
+
+    fn restored_storage(root: &Path) -> StorageSettings {
+        let mut storage = AppConfig::default().storage;
+        storage.allowed_roots = vec![root.to_path_buf()];
+        storage.snapshot_storage_path = root.join("dst/snapshots");
+        storage.screenshot_storage_path = root.join("dst/screenshots");
+        storage.database_path = root.join("dst/data.db");
+        storage
+    }
+
+    #[test]
+    fn test_backup_round_trip_with_secrets_and_remapped_paths() {
+        let (root, config) = fixture("roundtrip");
+        let archive = root.join("backup.tar.zst");
+        let secrets = BTreeMap::from([("claude_api_key".to_string(), "sk-test".to_string())]);
+
+        // A write the backend has not checkpointed yet, still only in the WAL
+        let live = Connection::open(&config.storage.database_path).unwrap();
+        live.execute_batch(
+            "PRAGMA wal_autocheckpoint = 0; INSERT INTO pages VALUES ('https://example.com/new');",
+        )
+        .unwrap();
+        assert!(root.join("src/autodoc.db-wal").exists());
+
+        let written = write_backup(&archive, &config, &secrets, Some("pass"), Utc::now()).unwrap();
+        drop(live);
+        assert_eq!(written.secrets_included, 1);
+        assert_eq!(written.projects.len(), 2);
+        assert!(!written.files.iter().any(|f| f.path.ends_with("-wal")));
+
+        let verified = verify_backup(&archive).unwrap();
+        assert_eq!(verified.manifest, written);
+        let opened = config_bundle::open_bundle(verified.bundle, Some("pass"), None).unwrap();
+        assert_eq!(
+            opened.secrets.get("claude_api_key").map(String::as_str),
+            Some("sk-test")
+        );
+
+        // Restore into different paths, including a renamed database
+        let storage = restored_storage(&root);
+        let roots = &storage.allowed_roots;
+        // Left over from a database that is no longer there
+        std::fs::create_dir_all(root.join("dst")).unwrap();
+        std::fs::write(root.join("dst/data.db-wal"), b"stale").unwrap();
+        let (files, _) = extract(&archive, &written, &storage, roots, None, false, false).unwrap();
+        assert_eq!(files, written.files.len() - 1);
+        assert!(!root.join("dst/data.db-wal").exists());
+        let restored = Connection::open(root.join("dst/data.db")).unwrap();
+        let pages: i64 = restored
+            .query_row("SELECT COUNT(*) FROM pages", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(pages, 2);
+        assert_eq!(
+            snapshots::list_snapshots(&storage.snapshot_storage_path).len(),
+            2
+        );
+        assert!(root.join("dst/screenshots/b1/home.png").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_selective_restore_of_one_project() {
+        let (root, config) = fixture("selective");
+        let archive = root.join("backup.tar.zst");
+        let manifest = write_backup(&archive, &config, &BTreeMap::new(), None, Utc::now()).unwrap();
+
+        let storage = restored_storage(&root);
+        let projects = vec!["proj-a".to_string()];
+        extract(
+            &archive,
+            &manifest,
+            &storage,
+            &storage.allowed_roots,
+            Some(&projects),
+            false,
+            false,
+        )
+        .unwrap();
+
+        let restored = snapshots::list_snapshots(&storage.snapshot_storage_path);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].manifest.project_id, "proj-a");
+        assert!(root.join("dst/screenshots/a1/home.png").exists());
+        assert!(!root.join("dst/screenshots/b1").exists());
+        assert!(!root.join("dst/data.db").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_malicious_archive_stays_within_saved_roots() {
+        // The archive's own config claims every path is allowed and points its
+        // storage at data that already exists on this machine
+        let (root, mut config) = fixture("malicious");
+        config.storage.allowed_roots = vec![PathBuf::from("/")];
+        let archive = root.join("backup.tar.zst");
+        write_backup(&archive, &config, &BTreeMap::new(), None, Utc::now()).unwrap();
+        std::fs::write(&config.storage.database_path, b"victim").unwrap();
+
+        let verified = verify_backup(&archive).unwrap();
+        let opened = config_bundle::open_bundle(verified.bundle, None, None).unwrap();
+        let storage = &opened.config.storage;
+        assert_eq!(storage.allowed_roots, vec![PathBuf::from("/")]);
+
+        // Checked against the saved roots, the archive's destinations are refused
+        let saved_roots = vec![root.join("dst")];
+        let manifest = &verified.manifest;
+        assert!(extract(&archive, manifest, storage, &saved_roots, None, false, true).is_err());
+
+        // Inside the roots, existing files are only replaced when asked to
+        let roots = vec![root.clone()];
+        assert!(extract(&archive, manifest, storage, &roots, None, true, false).is_err());
+        assert!(extract(&archive, manifest, storage, &roots, None, false, false).is_err());
+        assert_eq!(std::fs::read(&storage.database_path).unwrap(), b"victim");
+
+        extract(&archive, manifest, storage, &roots, None, false, true).unwrap();
+        let restored = std::fs::read(&storage.database_path).unwrap();
+        assert!(restored.starts_with(b"SQLite format 3\0"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_tampered_archive_is_rejected() {
+        let (root, config) = fixture("tampered");
+        let archive = root.join("backup.tar.zst");
+        let mut manifest =
+            write_backup(&archive, &config, &BTreeMap::new(), None, Utc::now()).unwrap();
+
+        // Rewrite the archive with one entry's checksum changed in the manifest
+        let data = zstd::decode_all(File::open(&archive).unwrap()).unwrap();
+        let mut source = tar::Archive::new(data.as_slice());
+        let mut builder = tar::Builder::new(Vec::new());
+        for entry in source.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            if name == MANIFEST_ENTRY {
+                manifest.files[1].sha256 = "0".repeat(64);
+                content = serde_json::to_vec(&manifest).unwrap();
+            }
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            builder
+                .append_data(&mut header, &name, content.as_slice())
+                .unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+        std::fs::write(&archive, zstd::encode_all(tar.as_slice(), 1).unwrap()).unwrap();
+
+        assert!(verify_backup(&archive).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_archive_paths_reject_traversal() {
+        assert_eq!(
+            parse_archive_path("screenshots/a1/home.png"),
+            Some((BackupComponent::Screenshots, PathBuf::from("a1/home.png")))
+        );
+        assert!(parse_archive_path("screenshots/../../etc/passwd").is_none());
+        assert!(parse_archive_path("snapshots//etc/passwd").is_none());
+        assert!(parse_archive_path("other/file").is_none());
+        assert!(parse_archive_path("database/").is_none());
+    }
+}
@@ -1,7 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use anyhow::Result;
-use crate::secure_storage;
+use log::warn;
+use crate::{
+    api_gateway, autostart, config_history, i18n, key_pool, path_guard, secure_storage,
+    session_vault, targets,
+};
+use crate::llm_provider::{self, LlmProvider, ProviderEndpoint};
+use crate::secure_storage::SecretBackendPreference;
+use crate::i18n::tr;
+use crate::retention::RetentionAction;
 
 // ============= 配置結構定義 =============
 
@@ -26,18 +34,39 @@ pub struct BasicSettings {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthSettings {
     // Note: claude_api_key and target_password are stored securely in OS keychain
-    // These fields are only used temporarily and not persisted to config file
-    #[serde(skip)]
+    // These fields only carry new values from the UI and are never serialized
+    #[serde(default, skip_serializing)]
     pub claude_api_key: String,
     pub claude_model: String,
     pub google_credentials_path: Option<PathBuf>,
     pub google_token_path: Option<PathBuf>,
     pub chrome_mcp_url: String,
     pub chrome_mcp_port: u16,
+    // Legacy single-target settings; migrated into `targets` on load
     pub target_auth_type: String,
     pub target_username: Option<String>,
-    #[serde(skip)]
+    #[serde(default, skip_serializing)]
     pub target_password: Option<String>,
+    /// Target applications, each with its own login; secrets live in the keychain
+    #[serde(default)]
+    pub targets: Vec<targets::TargetDefinition>,
+    /// LLM used for exploration; `anthropic` uses the Claude settings above
+    #[serde(default)]
+    pub llm_provider: LlmProvider,
+    #[serde(default = "llm_provider::default_openai_compatible")]
+    pub openai_compatible: ProviderEndpoint,
+    #[serde(default = "llm_provider::default_ollama")]
+    pub ollama: ProviderEndpoint,
+    /// Additional Claude keys shared out by the gateway; empty uses `claude_api_key` alone
+    #[serde(default)]
+    pub claude_keys: Vec<key_pool::ClaudeKey>,
+    /// Saved browser sessions are not reused after this many hours; 0 relies on cookie expiry alone
+    #[serde(default = "default_session_max_age_hours")]
+    pub session_max_age_hours: u32,
+}
+
+fn default_session_max_age_hours() -> u32 {
+    24
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,6 +87,27 @@ pub struct StorageSettings {
     pub enable_compression: bool,
     pub auto_cleanup: bool,
     pub retention_days: u32,
+    /// Directories storage paths must live under; empty means the built-in defaults
+    #[serde(default)]
+    pub allowed_roots: Vec<PathBuf>,
+    /// Minimum free space (MB) required on the volume of each storage path
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    /// Delete or archive data older than `retention_days`
+    #[serde(default)]
+    pub retention_action: RetentionAction,
+}
+
+fn default_min_free_space_mb() -> u64 {
+    500
+}
+
+fn default_credential_rotation_days() -> u32 {
+    90
+}
+
+fn default_credential_expiry_warning_days() -> u32 {
+    7
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -65,9 +115,48 @@ pub struct AdvancedSettings {
     pub log_level: String,
     pub enable_telemetry: bool,
     pub concurrent_tabs: u32,
+    /// Requests per minute across all API keys; pooled keys also have their own limits
     pub api_rate_limit: u32,
     pub proxy_url: Option<String>,
+    /// Hosts that bypass the proxy, comma separated (NO_PROXY syntax)
+    pub no_proxy: Option<String>,
+    pub proxy_username: Option<String>,
+    // Note: proxy_password is stored securely in OS keychain
+    #[serde(default, skip_serializing)]
+    pub proxy_password: Option<String>,
+    /// PEM bundle of additional trusted CA certificates
+    pub ca_bundle_path: Option<PathBuf>,
     pub custom_user_agent: Option<String>,
+    /// External service endpoints; overridable to point at local stand-ins
+    #[serde(default)]
+    pub endpoints: ServiceEndpoints,
+    /// Where credentials are kept; `auto` falls back to an encrypted file without a keychain
+    #[serde(default)]
+    pub secret_backend: SecretBackendPreference,
+    /// Remind to rotate credentials unchanged for this many days; 0 turns it off
+    #[serde(default = "default_credential_rotation_days")]
+    pub credential_rotation_days: u32,
+    /// Remind this many days before a credential's recorded expiry
+    #[serde(default = "default_credential_expiry_warning_days")]
+    pub credential_expiry_warning_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct ServiceEndpoints {
+    pub claude_api_base: String,
+    pub google_auth_url: String,
+    pub google_token_url: String,
+}
+
+impl Default for ServiceEndpoints {
+    fn default() -> Self {
+        ServiceEndpoints {
+            claude_api_base: "https://api.anthropic.com".to_string(),
+            google_auth_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            google_token_url: "https://oauth2.googleapis.com/token".to_string(),
+        }
+    }
 }
 
 // ============= 預設配置 =============
@@ -75,6 +164,7 @@ pub struct AdvancedSettings {
 impl Default for AppConfig {
     fn default() -> Self {
         let docs_dir = dirs::document_dir()
+            .or_else(dirs::home_dir)
             .unwrap_or_else(|| PathBuf::from("."))
             .join("AutoDoc");
 
@@ -96,6 +186,12 @@ impl Default for AppConfig {
                 target_auth_type: "none".to_string(),
                 target_username: None,
                 target_password: None,
+                targets: Vec::new(),
+                llm_provider: LlmProvider::default(),
+                openai_compatible: llm_provider::default_openai_compatible(),
+                ollama: llm_provider::default_ollama(),
+                claude_keys: Vec::new(),
+                session_max_age_hours: default_session_max_age_hours(),
             },
             exploration: ExplorationSettings {
                 strategy: "importance".to_string(),
@@ -112,6 +208,9 @@ impl Default for AppConfig {
                 enable_compression: true,
                 auto_cleanup: false,
                 retention_days: 0,
+                allowed_roots: Vec::new(),
+                min_free_space_mb: default_min_free_space_mb(),
+                retention_action: RetentionAction::default(),
             },
             advanced: AdvancedSettings {
                 log_level: "info".to_string(),
@@ -119,65 +218,51 @@ impl Default for AppConfig {
                 concurrent_tabs: 3,
                 api_rate_limit: 20,
                 proxy_url: None,
+                no_proxy: None,
+                proxy_username: None,
+                proxy_password: None,
+                ca_bundle_path: None,
                 custom_user_agent: None,
+                endpoints: ServiceEndpoints::default(),
+                secret_backend: SecretBackendPreference::default(),
+                credential_rotation_days: default_credential_rotation_days(),
+                credential_expiry_warning_days: default_credential_expiry_warning_days(),
             },
         }
     }
 }
 
-// ============= Path Validation =============
-
-/// Validate that a path is within allowed directories
-fn validate_path(path: &Path) -> Result<PathBuf, String> {
-    // Get allowed base directories
-    let allowed_bases = vec![
-        dirs::document_dir(),
-        dirs::data_dir(),
-        dirs::config_dir(),
-        dirs::home_dir(),
-    ];
-
-    // Canonicalize the path (this also checks if parent directories exist)
-    let canonical = path.canonicalize().or_else(|_| {
-        // If path doesn't exist, try to canonicalize parent and append filename
-        if let Some(parent) = path.parent() {
-            if let Some(filename) = path.file_name() {
-                parent
-                    .canonicalize()
-                    .map(|p| p.join(filename))
-                    .map_err(|e| format!("路徑驗證失敗: {}", e))
-            } else {
-                Err("無效的路徑".to_string())
-            }
-        } else {
-            Err("無效的路徑".to_string())
+impl AppConfig {
+    /// Copy of this config with every keychain-backed secret cleared
+    pub fn without_secrets(&self) -> AppConfig {
+        let mut sanitized = self.clone();
+        sanitized.auth.claude_api_key = String::new();
+        sanitized.auth.openai_compatible.api_key = String::new();
+        sanitized.auth.ollama.api_key = String::new();
+        sanitized.auth.target_password = None;
+        sanitized.advanced.proxy_password = None;
+        for target in &mut sanitized.auth.targets {
+            target.secret = None;
         }
-    })?;
-
-    // Check if the path is within any allowed base directory
-    let is_allowed = allowed_bases.iter().any(|base| {
-        if let Some(base_path) = base {
-            canonical.starts_with(base_path)
-        } else {
-            false
+        for key in &mut sanitized.auth.claude_keys {
+            key.secret = None;
         }
-    });
-
-    if !is_allowed {
-        return Err(format!(
-            "路徑必須在用戶目錄內: {}",
-            canonical.display()
-        ));
+        sanitized
     }
+}
+
+// ============= Path Validation =============
 
-    Ok(canonical)
+/// Validate that a path is within allowed directories and free of symlinked components
+pub(crate) fn validate_path(path: &Path) -> Result<PathBuf, String> {
+    path_guard::validate_within(path, &path_guard::default_roots())
 }
 
 /// Validate all paths in storage settings
-fn validate_storage_paths(storage: &StorageSettings) -> Result<(), String> {
-    validate_path(&storage.snapshot_storage_path)?;
-    validate_path(&storage.screenshot_storage_path)?;
-    validate_path(&storage.database_path)?;
+fn validate_storage_paths(storage: &StorageSettings, roots: &[PathBuf]) -> Result<(), String> {
+    path_guard::validate_within(&storage.snapshot_storage_path, roots)?;
+    path_guard::validate_within(&storage.screenshot_storage_path, roots)?;
+    path_guard::validate_within(&storage.database_path, roots)?;
     Ok(())
 }
 
@@ -194,32 +279,99 @@ fn validate_auth_paths(auth: &AuthSettings) -> Result<(), String> {
 
 // ============= Tauri Commands =============
 
+/// Settings for the webview: secrets never leave Rust, the UI asks
+/// `get_credential_status` whether one is stored
 #[tauri::command]
 pub fn load_config() -> Result<AppConfig, String> {
     let mut config: AppConfig = confy::load("autodoc-agent", "config")
-        .map_err(|e| format!("載入配置失敗: {}", e))?;
+        .map_err(|e| tr!("config-load-failed", error = e))?;
+    targets::migrate_legacy_target(&mut config.auth);
+    Ok(config.without_secrets())
+}
+
+/// Settings with every secret filled in from the keychain, for Rust callers only
+pub fn load_config_with_secrets() -> Result<AppConfig, String> {
+    let mut config: AppConfig = confy::load("autodoc-agent", "config")
+        .map_err(|e| tr!("config-load-failed", error = e))?;
 
     // Load sensitive credentials from OS keychain
-    if let Ok(api_key) = secure_storage::get_credential("claude_api_key") {
-        config.auth.claude_api_key = api_key;
+    for provider in [LlmProvider::Anthropic, LlmProvider::OpenaiCompatible, LlmProvider::Ollama] {
+        if let Ok(api_key) = secure_storage::get_credential(provider.secret_key()) {
+            *llm_provider::api_key_mut(&mut config, provider) = api_key;
+        }
     }
 
     if let Ok(password) = secure_storage::get_credential("target_password") {
         config.auth.target_password = Some(password);
     }
 
+    if let Ok(password) = secure_storage::get_credential("proxy_password") {
+        config.advanced.proxy_password = Some(password);
+    }
+
+    // The legacy target keeps its keychain password until the next save
+    targets::migrate_legacy_target(&mut config.auth);
+    for target in &mut config.auth.targets {
+        if let Ok(secret) = secure_storage::get_credential(&targets::secret_key(&target.id)) {
+            target.secret = Some(secret);
+        }
+    }
+    for key in &mut config.auth.claude_keys {
+        if let Ok(secret) = secure_storage::get_credential(&key_pool::secret_key(&key.id)) {
+            key.secret = Some(secret);
+        }
+    }
+
     Ok(config)
 }
 
+/// Directory holding the config file and the app's other state files,
+/// e.g. `~/.config/autodoc-agent`
+pub fn config_dir() -> Result<PathBuf, String> {
+    let config_file = confy::get_configuration_file_path("autodoc-agent", "config")
+        .map_err(|e| tr!("config-path-unavailable", error = e))?;
+    config_file
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| tr!("config-path-invalid"))
+}
+
+/// Settings as stored on disk, without keychain secrets.
+/// Falls back to defaults so background callers never fail on a broken config file.
+pub fn load_settings() -> AppConfig {
+    let mut config: AppConfig = confy::load("autodoc-agent", "config").unwrap_or_else(|e| {
+        warn!("Failed to load config, using defaults: {}", e);
+        AppConfig::default()
+    });
+    targets::migrate_legacy_target(&mut config.auth);
+    config
+}
+
 #[tauri::command]
-pub fn save_config(config: AppConfig) -> Result<(), String> {
+pub fn save_config(mut config: AppConfig) -> Result<(), String> {
+    // Allowed roots are only changed by editing the config file, never through a save
+    let previous = load_settings();
+    config.storage.allowed_roots = previous.storage.allowed_roots.clone();
+
     // Validate all paths before saving
-    validate_storage_paths(&config.storage)?;
+    validate_storage_paths(&config.storage, &path_guard::storage_roots())?;
     validate_auth_paths(&config.auth)?;
 
+    targets::assign_ids(&mut config.auth.targets);
+    if let Some((_, message)) = targets::validate_targets(&config.auth.targets).into_iter().next() {
+        return Err(message);
+    }
+    key_pool::assign_ids(&mut config.auth.claude_keys);
+    if let Some((_, message)) = key_pool::validate_keys(&config.auth.claude_keys).into_iter().next() {
+        return Err(message);
+    }
+
     // Store sensitive credentials in OS keychain (not in config file)
-    if !config.auth.claude_api_key.is_empty() {
-        secure_storage::store_credential("claude_api_key", &config.auth.claude_api_key)?;
+    for provider in [LlmProvider::Anthropic, LlmProvider::OpenaiCompatible, LlmProvider::Ollama] {
+        let api_key = llm_provider::api_key_mut(&mut config, provider);
+        if !api_key.is_empty() {
+            secure_storage::store_credential(provider.secret_key(), api_key)?;
+        }
     }
 
     if let Some(ref password) = config.auth.target_password {
@@ -228,59 +380,183 @@ pub fn save_config(config: AppConfig) -> Result<(), String> {
         }
     }
 
+    if let Some(ref password) = config.advanced.proxy_password {
+        if !password.is_empty() {
+            secure_storage::store_credential("proxy_password", password)?;
+        }
+    }
+
+    for target in &config.auth.targets {
+        if let Some(ref secret) = target.secret {
+            if !secret.is_empty() {
+                secure_storage::store_credential(&targets::secret_key(&target.id), secret)?;
+            }
+        }
+    }
+
+    for key in &config.auth.claude_keys {
+        if let Some(ref secret) = key.secret {
+            if !secret.is_empty() {
+                secure_storage::store_credential(&key_pool::secret_key(&key.id), secret)?;
+            }
+        }
+    }
+    targets::migrate_legacy_secret(&config.auth)?;
+
+    // Drop keychain entries of targets and pooled keys that were removed
+    for target in previous.auth.targets {
+        if targets::find_target(&config.auth, &target.id).is_none() {
+            secure_storage::delete_credential(&targets::secret_key(&target.id))?;
+            session_vault::invalidate(&target.id)?;
+        }
+    }
+    for key in previous.auth.claude_keys {
+        if key_pool::find_key(&config.auth, &key.id).is_none() {
+            secure_storage::delete_credential(&key_pool::secret_key(&key.id))?;
+        }
+    }
+
     // Create a copy without sensitive data for file storage
-    let mut config_to_save = config.clone();
-    config_to_save.auth.claude_api_key = String::new();
-    config_to_save.auth.target_password = None;
+    let config_to_save = config.without_secrets();
+
+    // Keep the previous settings recoverable; history problems never block a save
+    if let Err(e) = config_history::capture_baseline() {
+        warn!("Failed to capture config baseline: {}", e);
+    }
+
+    confy::store("autodoc-agent", "config", &config_to_save)
+        .map_err(|e| tr!("config-save-failed", error = e))?;
+
+    if let Err(e) = config_history::record_snapshot(&config_to_save) {
+        warn!("Failed to record config history: {}", e);
+    }
+
+    // The status command reports the real registration if this fails
+    if let Err(e) = autostart::sync(config_to_save.basic.auto_start) {
+        warn!("Failed to update autostart registration: {}", e);
+    }
+
+    i18n::set_language(&config_to_save.basic.language);
+    api_gateway::apply_settings(&config_to_save);
 
-    confy::store("autodoc-agent", "config", config_to_save)
-        .map_err(|e| format!("保存配置失敗: {}", e))
+    Ok(())
 }
 
-#[tauri::command]
-pub fn validate_config(config: AppConfig) -> Result<Vec<String>, String> {
-    let mut errors = Vec::new();
+/// A single validation problem, tied to the dotted config field it concerns
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
 
-    // 驗證 Claude API Key
-    if config.auth.claude_api_key.is_empty() {
-        errors.push("Claude API Key 不能為空".to_string());
-    } else if !config.auth.claude_api_key.starts_with("sk-") {
-        errors.push("Claude API Key 格式不正確".to_string());
+impl ValidationIssue {
+    pub(crate) fn new(field: &str, message: String) -> Self {
+        ValidationIssue {
+            field: field.to_string(),
+            message,
+        }
     }
+}
+
+/// Structured validation of all non-credential settings.
+/// Shared by `validate_config` and configuration import.
+pub fn validate_settings(config: &AppConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let roots = path_guard::storage_roots();
 
     // 驗證探索設定
     if config.exploration.max_depth == 0 || config.exploration.max_depth > 10 {
-        errors.push("最大深度必須在 1-10 之間".to_string());
+        issues.push(ValidationIssue::new(
+            "exploration.max_depth",
+            tr!("config-max-depth-range"),
+        ));
     }
 
     if config.exploration.max_pages < 10 || config.exploration.max_pages > 1000 {
-        errors.push("最大頁面數必須在 10-1000 之間".to_string());
+        issues.push(ValidationIssue::new(
+            "exploration.max_pages",
+            tr!("config-max-pages-range"),
+        ));
     }
 
     // 驗證儲存路徑（防止路徑穿越）
-    if let Err(e) = validate_storage_paths(&config.storage) {
-        errors.push(format!("儲存路徑驗證失敗: {}", e));
+    if let Err(e) = validate_storage_paths(&config.storage, &roots) {
+        issues.push(ValidationIssue::new(
+            "storage",
+            tr!("config-storage-paths-invalid", error = e),
+        ));
+    }
+
+    // 檢查寫入權限與可用空間（僅針對通過路徑驗證的項目）
+    for report in path_guard::check_storage(&config.storage, &roots) {
+        if report.resolved.is_some() && !report.ok {
+            issues.push(ValidationIssue::new(
+                &report.field,
+                tr!("config-storage-check-failed", problems = report.problems.join(", ")),
+            ));
+        }
     }
 
     // 驗證認證路徑
     if let Err(e) = validate_auth_paths(&config.auth) {
-        errors.push(format!("認證路徑驗證失敗: {}", e));
+        issues.push(ValidationIssue::new(
+            "auth",
+            tr!("config-auth-paths-invalid", error = e),
+        ));
+    }
+
+    // 驗證目標應用程式
+    for (field, message) in targets::validate_targets(&config.auth.targets) {
+        issues.push(ValidationIssue::new(&field, message));
+    }
+
+    // 驗證 Claude API Key 集區
+    for (field, message) in key_pool::validate_keys(&config.auth.claude_keys) {
+        issues.push(ValidationIssue::new(&field, message));
+    }
+
+    // 驗證 LLM 服務設定
+    issues.extend(llm_provider::validate_settings(config));
+
+    issues
+}
+
+#[tauri::command]
+pub fn validate_config(config: AppConfig) -> Result<Vec<String>, String> {
+    validate_config_with(config, |key| secure_storage::get_credential(key).ok())
+}
+
+/// `stored_key` looks up a keychain secret; the UI leaves the API key empty to keep the stored one
+fn validate_config_with(
+    config: AppConfig,
+    stored_key: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<String>, String> {
+    let mut errors = Vec::new();
+
+    // 驗證所選 LLM 服務的 API Key
+    let mut active = llm_provider::active(&config);
+    if active.api_key.is_empty() {
+        active.api_key = stored_key(active.provider.secret_key()).unwrap_or_default();
     }
+    if let Err(e) = llm_provider::validate_key(active.provider, &active.api_key) {
+        errors.push(e);
+    }
+
+    errors.extend(validate_settings(&config).into_iter().map(|issue| issue.message));
 
     // 創建目錄（僅在路徑驗證通過後）
     if errors.is_empty() {
-        // Safely create directories only after validation
-        let validated_snapshot_path = validate_path(&config.storage.snapshot_storage_path)
-            .map_err(|e| format!("快照路徑驗證失敗: {}", e))?;
-
-        if !validated_snapshot_path.exists() {
-            std::fs::create_dir_all(&validated_snapshot_path)
-                .map_err(|e| format!("無法建立快照目錄: {}", e))?;
-        }
+        // Create directories component by component so a symlink swapped in after
+        // validation is still refused
+        path_guard::create_dir_secure(
+            &config.storage.snapshot_storage_path,
+            &path_guard::storage_roots(),
+        )
+        .map_err(|e| tr!("config-snapshot-dir-failed", error = e))?;
     }
 
     if errors.is_empty() {
-        Ok(vec!["配置驗證通過".to_string()])
+        Ok(vec![tr!("config-valid")])
     } else {
         Err(errors.join("; "))
     }
@@ -346,13 +622,23 @@ mod tests {
     #[test]
     fn test_validate_config_empty_api_key() {
         let config = AppConfig::default();
-        // API Key 為空
+        // API Key 為空，且鑰匙圈中也沒有
 
-        let result = validate_config(config);
+        let result = validate_config_with(config, |_| None);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Claude API Key 不能為空"));
     }
 
+    #[test]
+    fn test_validate_config_uses_stored_api_key() {
+        let config = AppConfig::default();
+
+        let result = validate_config_with(config, |key| {
+            (key == "claude_api_key").then(|| "sk-ant-api03-stored".to_string())
+        });
+        assert!(!result.is_err_and(|e| e.contains("API Key")));
+    }
+
     #[test]
     fn test_validate_config_invalid_api_key_format() {
         let mut config = AppConfig::default();
@@ -562,7 +848,7 @@ mod tests {
         let _ = save_config(config.clone());
 
         // Load config from file
-        let loaded = load_config();
+        let loaded = load_config_with_secrets();
         if let Ok(loaded_config) = loaded {
             // API key should be empty in the loaded config (loaded from keychain instead)
             // Note: This depends on the implementation, adjust as needed
@@ -571,6 +857,11 @@ mod tests {
                 "API key should be retrieved from keychain"
             );
         }
+
+        // The webview never gets the key back
+        if let Ok(webview_config) = load_config() {
+            assert!(webview_config.auth.claude_api_key.is_empty());
+        }
     }
 
     #[test]
@@ -0,0 +1,493 @@
+use crate::config::{self, AppConfig, ValidationIssue};
+use crate::credential_registry::CredentialSource;
+use crate::crypto::{self, EncryptedBlob};
+use crate::i18n::tr;
+use crate::{key_pool, secure_storage, targets};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the bundle layout changes incompatibly
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Keychain entries that may travel inside a bundle
+const EXPORTABLE_SECRETS: &[&str] = &[
+    "claude_api_key",
+    "openai_compatible_api_key",
+    "ollama_api_key",
+    "target_password",
+    "proxy_password",
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleFormat {
+    Toml,
+    Json,
+}
+
+impl BundleFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "toml" => Ok(BundleFormat::Toml),
+            "json" => Ok(BundleFormat::Json),
+            other => Err(tr!("bundle-unsupported-format", format = other)),
+        }
+    }
+
+    /// Explicit format wins; otherwise infer from the file extension, defaulting to TOML
+    fn resolve(explicit: Option<&str>, path: &Path) -> Result<Self, String> {
+        if let Some(format) = explicit {
+            return Self::parse(format);
+        }
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(BundleFormat::Json),
+            _ => Ok(BundleFormat::Toml),
+        }
+    }
+}
+
+/// Portable configuration bundle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigBundle {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    /// Home directory of the exporting user, used to remap storage paths on import
+    pub source_home: Option<PathBuf>,
+    pub config: AppConfig,
+    /// Keychain secrets encrypted with the export passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<EncryptedBlob>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportSummary {
+    pub path: PathBuf,
+    pub format: BundleFormat,
+    pub secrets_included: usize,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct RemappedPath {
+    pub field: String,
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Result of decrypting and remapping a parsed bundle
+pub(crate) struct OpenedBundle {
+    pub config: AppConfig,
+    pub remapped_paths: Vec<RemappedPath>,
+    pub secrets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportReport {
+    pub config: AppConfig,
+    pub remapped_paths: Vec<RemappedPath>,
+    pub secrets_imported: usize,
+    pub issues: Vec<ValidationIssue>,
+    pub applied: bool,
+}
+
+// ============= Bundle Construction =============
+
+/// Fixed keychain entries plus one per configured target and pooled Claude key
+pub(crate) fn exportable_secret_keys(config: &AppConfig) -> Vec<String> {
+    EXPORTABLE_SECRETS
+        .iter()
+        .map(|key| key.to_string())
+        .chain(config.auth.targets.iter().map(|t| targets::secret_key(&t.id)))
+        .chain(config.auth.claude_keys.iter().map(|k| key_pool::secret_key(&k.id)))
+        .collect()
+}
+
+/// Per-target and pooled key secrets are only accepted when present in the imported config
+pub(crate) fn is_importable_secret(key: &str, config: &AppConfig) -> bool {
+    EXPORTABLE_SECRETS.contains(&key)
+        || targets::target_id_from_key(key)
+            .is_some_and(|id| targets::find_target(&config.auth, id).is_some())
+        || key_pool::key_id_from_key(key)
+            .is_some_and(|id| key_pool::find_key(&config.auth, id).is_some())
+}
+
+pub(crate) fn build_bundle(
+    config: &AppConfig,
+    secrets: &BTreeMap<String, String>,
+    passphrase: Option<&str>,
+    exported_at: DateTime<Utc>,
+) -> Result<ConfigBundle, String> {
+    let sanitized = config.without_secrets();
+
+    let encrypted = match passphrase {
+        Some(passphrase) if !secrets.is_empty() => {
+            let plaintext =
+                serde_json::to_vec(secrets).map_err(|e| tr!("bundle-serialize-secrets-failed", error = e))?;
+            Some(crypto::encrypt_with_passphrase(&plaintext, passphrase)?)
+        }
+        _ => None,
+    };
+
+    Ok(ConfigBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at,
+        source_home: dirs::home_dir(),
+        config: sanitized,
+        secrets: encrypted,
+    })
+}
+
+fn serialize_bundle(bundle: &ConfigBundle, format: BundleFormat) -> Result<String, String> {
+    match format {
+        BundleFormat::Toml => toml::to_string_pretty(bundle).map_err(|e| tr!("common-serialize-config-failed", error = e)),
+        BundleFormat::Json => {
+            serde_json::to_string_pretty(bundle).map_err(|e| tr!("common-serialize-config-failed", error = e))
+        }
+    }
+}
+
+pub(crate) fn parse_bundle(content: &str, format: BundleFormat) -> Result<ConfigBundle, String> {
+    let bundle: ConfigBundle = match format {
+        BundleFormat::Toml => toml::from_str(content).map_err(|e| tr!("bundle-parse-failed", error = e))?,
+        BundleFormat::Json => {
+            serde_json::from_str(content).map_err(|e| tr!("bundle-parse-failed", error = e))?
+        }
+    };
+
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(tr!(
+            "bundle-version-too-new",
+            version = bundle.format_version
+        ));
+    }
+
+    Ok(bundle)
+}
+
+// ============= Path Remapping =============
+
+/// Rebase `path` from one home directory onto another
+pub(crate) fn remap_home(path: &Path, from_home: &Path, to_home: &Path) -> Option<PathBuf> {
+    if from_home == to_home {
+        return None;
+    }
+    path.strip_prefix(from_home)
+        .ok()
+        .map(|relative| to_home.join(relative))
+}
+
+fn remap_config_paths(config: &mut AppConfig, from_home: &Path, to_home: &Path) -> Vec<RemappedPath> {
+    let mut remapped = Vec::new();
+
+    let mut remap = |field: &str, path: &mut PathBuf| {
+        if let Some(new_path) = remap_home(path, from_home, to_home) {
+            remapped.push(RemappedPath {
+                field: field.to_string(),
+                from: path.clone(),
+                to: new_path.clone(),
+            });
+            *path = new_path;
+        }
+    };
+
+    remap("storage.snapshot_storage_path", &mut config.storage.snapshot_storage_path);
+    remap("storage.screenshot_storage_path", &mut config.storage.screenshot_storage_path);
+    remap("storage.database_path", &mut config.storage.database_path);
+    if let Some(ref mut path) = config.auth.google_credentials_path {
+        remap("auth.google_credentials_path", path);
+    }
+    if let Some(ref mut path) = config.auth.google_token_path {
+        remap("auth.google_token_path", path);
+    }
+
+    remapped
+}
+
+/// Decrypt secrets and remap paths of a parsed bundle for the importing user
+pub(crate) fn open_bundle(
+    bundle: ConfigBundle,
+    passphrase: Option<&str>,
+    target_home: Option<&Path>,
+) -> Result<OpenedBundle, String> {
+    let secrets = match (&bundle.secrets, passphrase) {
+        (None, _) => BTreeMap::new(),
+        (Some(_), None) => return Err(tr!("bundle-password-required")),
+        (Some(blob), Some(passphrase)) => {
+            let plaintext = crypto::decrypt_with_passphrase(blob, passphrase)?;
+            serde_json::from_slice(&plaintext).map_err(|e| tr!("bundle-parse-secrets-failed", error = e))?
+        }
+    };
+
+    let mut config = bundle.config;
+    let remapped = match (bundle.source_home.as_deref(), target_home) {
+        (Some(from), Some(to)) => remap_config_paths(&mut config, from, to),
+        _ => Vec::new(),
+    };
+
+    Ok(OpenedBundle {
+        config,
+        remapped_paths: remapped,
+        secrets,
+    })
+}
+
+// ============= Tauri Commands =============
+
+#[tauri::command]
+pub fn export_config(
+    path: PathBuf,
+    format: Option<String>,
+    include_secrets: bool,
+    passphrase: Option<String>,
+) -> Result<ExportSummary, String> {
+    let output_path = config::validate_path(&path)?;
+    let format = BundleFormat::resolve(format.as_deref(), &output_path)?;
+
+    if include_secrets && passphrase.as_deref().unwrap_or("").is_empty() {
+        return Err(tr!("bundle-export-password-required"));
+    }
+
+    let current = config::load_config_with_secrets()?;
+    let mut secrets = BTreeMap::new();
+    if include_secrets {
+        for key in exportable_secret_keys(&current) {
+            if let Ok(value) = secure_storage::get_credential(&key) {
+                secrets.insert(key, value);
+            }
+        }
+    }
+
+    let bundle = build_bundle(&current, &secrets, passphrase.as_deref(), Utc::now())?;
+    let content = serialize_bundle(&bundle, format)?;
+
+    std::fs::write(&output_path, content).map_err(|e| tr!("bundle-write-failed", error = e))?;
+
+    let secrets_included = if bundle.secrets.is_some() { secrets.len() } else { 0 };
+    info!(
+        "Config exported to {} ({} secrets included)",
+        output_path.display(),
+        secrets_included
+    );
+
+    Ok(ExportSummary {
+        path: output_path,
+        format,
+        secrets_included,
+    })
+}
+
+#[tauri::command]
+pub fn import_config(
+    path: PathBuf,
+    format: Option<String>,
+    passphrase: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+    let input_path = config::validate_path(&path)?;
+    let format = BundleFormat::resolve(format.as_deref(), &input_path)?;
+
+    let content =
+        std::fs::read_to_string(&input_path).map_err(|e| tr!("bundle-read-failed", error = e))?;
+    let bundle = parse_bundle(&content, format)?;
+
+    let home = dirs::home_dir();
+    let OpenedBundle {
+        config: mut imported,
+        remapped_paths,
+        secrets,
+    } = open_bundle(bundle, passphrase.as_deref(), home.as_deref())?;
+
+    // Structured validation covers ranges and storage/auth path checks
+    let issues = config::validate_settings(&imported);
+    let dry_run = dry_run.unwrap_or(false);
+
+    if !issues.is_empty() || dry_run {
+        return Ok(ImportReport {
+            config: imported,
+            remapped_paths,
+            secrets_imported: 0,
+            issues,
+            applied: false,
+        });
+    }
+
+    let mut secrets_imported = 0;
+    for (key, value) in &secrets {
+        if !is_importable_secret(key, &imported) {
+            warn!("Ignoring unknown secret '{}' in config bundle", key);
+            continue;
+        }
+        secure_storage::store_credential_from(key, value, CredentialSource::Import)?;
+        secrets_imported += 1;
+    }
+
+    // Without secrets in the bundle, keep whatever is already in the keychain
+    imported = imported.without_secrets();
+    config::save_config(imported.clone())?;
+
+    info!(
+        "Config imported from {} ({} paths remapped, {} secrets)",
+        input_path.display(),
+        remapped_paths.len(),
+        secrets_imported
+    );
+
+    Ok(ImportReport {
+        config: imported,
+        remapped_paths,
+        secrets_imported,
+        issues,
+        applied: true,
+    })
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_secrets() -> BTreeMap<String, String> {
+        let mut secrets = BTreeMap::new();
+        secrets.insert("claude_api_key".to_string(), "sk-ant-bundle-secret".to_string());
+        secrets
+    }
+
+    #[test]
+    fn test_target_secrets_importable_only_for_known_targets() {
+        let mut config = AppConfig::default();
+        config.auth.targets.push(crate::targets::TargetDefinition {
+            id: "crm".to_string(),
+            name: "CRM".to_string(),
+            entry_url: "https://crm.example.com".to_string(),
+            auth_type: crate::targets::TargetAuthType::Form,
+            username: Some("alice".to_string()),
+            login_url: None,
+            selectors: Default::default(),
+            secret: None,
+        });
+
+        assert!(exportable_secret_keys(&config).contains(&"target_secret:crm".to_string()));
+        assert!(is_importable_secret("target_secret:crm", &config));
+        assert!(is_importable_secret("claude_api_key", &config));
+        assert!(!is_importable_secret("target_secret:unknown", &config));
+        assert!(!is_importable_secret("some_other_key", &config));
+
+        // Pooled Claude keys follow the same rule
+        assert!(!is_importable_secret("claude_api_key:team-a", &config));
+        config.auth.claude_keys.push(crate::key_pool::ClaudeKey {
+            id: "team-a".to_string(),
+            name: "Team A".to_string(),
+            rate_limit: 50,
+            priority: 0,
+            enabled: true,
+            secret: None,
+        });
+        assert!(exportable_secret_keys(&config).contains(&"claude_api_key:team-a".to_string()));
+        assert!(is_importable_secret("claude_api_key:team-a", &config));
+    }
+
+    #[test]
+    fn test_toml_roundtrip_without_secrets() {
+        let mut config = AppConfig::default();
+        config.auth.claude_api_key = "sk-ant-should-not-export".to_string();
+        config.storage.retention_days = 14;
+
+        let bundle = build_bundle(&config, &BTreeMap::new(), None, Utc::now()).unwrap();
+        let text = serialize_bundle(&bundle, BundleFormat::Toml).unwrap();
+        assert!(!text.contains("sk-ant-should-not-export"));
+
+        let parsed = parse_bundle(&text, BundleFormat::Toml).unwrap();
+        assert!(parsed.secrets.is_none());
+        assert_eq!(parsed.config.storage.retention_days, 14);
+    }
+
+    #[test]
+    fn test_toml_roundtrip_with_encrypted_secrets() {
+        let mut config = AppConfig::default();
+        config.storage.retention_days = 14;
+        let bundle = build_bundle(&config, &sample_secrets(), Some("pass phrase"), Utc::now()).unwrap();
+        let text = serialize_bundle(&bundle, BundleFormat::Toml).unwrap();
+        assert!(!text.contains("sk-ant-bundle-secret"));
+
+        let parsed = parse_bundle(&text, BundleFormat::Toml).unwrap();
+        let opened = open_bundle(parsed, Some("pass phrase"), None).unwrap();
+        assert_eq!(opened.secrets, sample_secrets());
+        assert_eq!(opened.config.storage.retention_days, 14);
+    }
+
+    #[test]
+    fn test_json_roundtrip_with_encrypted_secrets() {
+        let config = AppConfig::default();
+        let bundle = build_bundle(&config, &sample_secrets(), Some("pass phrase"), Utc::now()).unwrap();
+        let text = serialize_bundle(&bundle, BundleFormat::Json).unwrap();
+        assert!(!text.contains("sk-ant-bundle-secret"));
+
+        let parsed = parse_bundle(&text, BundleFormat::Json).unwrap();
+        let opened = open_bundle(parsed, Some("pass phrase"), None).unwrap();
+        assert_eq!(opened.secrets, sample_secrets());
+    }
+
+    #[test]
+    fn test_encrypted_bundle_requires_correct_passphrase() {
+        let bundle =
+            build_bundle(&AppConfig::default(), &sample_secrets(), Some("right"), Utc::now()).unwrap();
+
+        assert!(open_bundle(bundle.clone(), None, None).is_err());
+        assert!(open_bundle(bundle, Some("wrong"), None).is_err());
+    }
+
+    #[test]
+    fn test_paths_remapped_to_importing_home() {
+        let mut config = AppConfig::default();
+        config.storage.snapshot_storage_path = PathBuf::from("/home/alice/Documents/AutoDoc/snapshots");
+        config.storage.database_path = PathBuf::from("/srv/shared/autodoc.db");
+
+        let mut bundle = build_bundle(&config, &BTreeMap::new(), None, Utc::now()).unwrap();
+        bundle.source_home = Some(PathBuf::from("/home/alice"));
+
+        let opened = open_bundle(bundle, None, Some(Path::new("/home/bob"))).unwrap();
+
+        assert_eq!(
+            opened.config.storage.snapshot_storage_path,
+            PathBuf::from("/home/bob/Documents/AutoDoc/snapshots")
+        );
+        // Paths outside the exporting user's home are left alone
+        assert_eq!(
+            opened.config.storage.database_path,
+            PathBuf::from("/srv/shared/autodoc.db")
+        );
+        assert_eq!(opened.remapped_paths.len(), 1);
+        assert_eq!(opened.remapped_paths[0].field, "storage.snapshot_storage_path");
+    }
+
+    #[test]
+    fn test_newer_bundle_version_rejected() {
+        let mut bundle = build_bundle(&AppConfig::default(), &BTreeMap::new(), None, Utc::now()).unwrap();
+        bundle.format_version = BUNDLE_FORMAT_VERSION + 1;
+        let text = serialize_bundle(&bundle, BundleFormat::Json).unwrap();
+
+        assert!(parse_bundle(&text, BundleFormat::Json).is_err());
+    }
+
+    #[test]
+    fn test_format_resolution() {
+        assert_eq!(
+            BundleFormat::resolve(None, Path::new("team.json")).unwrap(),
+            BundleFormat::Json
+        );
+        assert_eq!(
+            BundleFormat::resolve(None, Path::new("team.toml")).unwrap(),
+            BundleFormat::Toml
+        );
+        assert_eq!(
+            BundleFormat::resolve(Some("JSON"), Path::new("team.toml")).unwrap(),
+            BundleFormat::Json
+        );
+        assert!(BundleFormat::resolve(Some("yaml"), Path::new("team")).is_err());
+    }
+}
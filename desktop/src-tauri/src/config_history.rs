@@ -0,0 +1,336 @@
+use crate::config::{self, AppConfig};
+use crate::i18n::tr;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Number of saved configurations kept on disk
+const MAX_HISTORY_ENTRIES: usize = 20;
+
+const HISTORY_DIR_NAME: &str = "history";
+const HISTORY_FILE_PREFIX: &str = "config-";
+const HISTORY_FILE_EXTENSION: &str = "toml";
+const VERSION_ID_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigVersion {
+    pub id: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ConfigFieldChange {
+    /// Dotted field path, e.g. `storage.retention_days`
+    pub field: String,
+    pub from: Option<serde_json::Value>,
+    pub to: Option<serde_json::Value>,
+}
+
+// ============= History Directory =============
+
+/// History lives next to the confy config file, e.g. `~/.config/autodoc-agent/history`
+fn history_dir() -> Result<PathBuf, String> {
+    Ok(config::config_dir()?.join(HISTORY_DIR_NAME))
+}
+
+fn version_file(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    // Version IDs are generated by us; reject anything that could escape the directory
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(tr!("history-invalid-id", id = id));
+    }
+    Ok(dir.join(format!(
+        "{}{}.{}",
+        HISTORY_FILE_PREFIX, id, HISTORY_FILE_EXTENSION
+    )))
+}
+
+fn parse_version_id(id: &str) -> Option<DateTime<Utc>> {
+    chrono::NaiveDateTime::parse_from_str(id, VERSION_ID_FORMAT)
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+// ============= History Operations =============
+
+fn record_in(dir: &Path, config: &AppConfig, now: DateTime<Utc>) -> Result<ConfigVersion, String> {
+    std::fs::create_dir_all(dir).map_err(|e| tr!("history-create-dir-failed", error = e))?;
+
+    // Secrets are `#[serde(skip_serializing)]`, but clear them explicitly so a future field change
+    // can never leak them into history
+    let sanitized = config.without_secrets();
+
+    let content =
+        toml::to_string_pretty(&sanitized).map_err(|e| tr!("common-serialize-config-failed", error = e))?;
+
+    // Two saves within the same millisecond must not overwrite each other
+    let mut saved_at = now;
+    let mut id = saved_at.format(VERSION_ID_FORMAT).to_string();
+    while version_file(dir, &id)?.exists() {
+        saved_at += chrono::Duration::milliseconds(1);
+        id = saved_at.format(VERSION_ID_FORMAT).to_string();
+    }
+
+    std::fs::write(version_file(dir, &id)?, content)
+        .map_err(|e| tr!("history-write-failed", error = e))?;
+
+    prune_in(dir, MAX_HISTORY_ENTRIES)?;
+
+    Ok(ConfigVersion { id, saved_at })
+}
+
+fn list_in(dir: &Path) -> Result<Vec<ConfigVersion>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(dir).map_err(|e| tr!("history-read-failed", error = e))?;
+
+    let mut versions: Vec<ConfigVersion> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let id = name
+                .strip_prefix(HISTORY_FILE_PREFIX)?
+                .strip_suffix(&format!(".{}", HISTORY_FILE_EXTENSION))?
+                .to_string();
+            let saved_at = parse_version_id(&id)?;
+            Some(ConfigVersion { id, saved_at })
+        })
+        .collect();
+
+    // Newest first
+    versions.sort_by_key(|v| std::cmp::Reverse(v.saved_at));
+    Ok(versions)
+}
+
+fn load_in(dir: &Path, id: &str) -> Result<AppConfig, String> {
+    let path = version_file(dir, id)?;
+    let content =
+        std::fs::read_to_string(&path).map_err(|_| tr!("history-version-not-found", id = id))?;
+    toml::from_str(&content).map_err(|e| tr!("history-parse-failed", error = e))
+}
+
+fn prune_in(dir: &Path, keep: usize) -> Result<(), String> {
+    for version in list_in(dir)?.into_iter().skip(keep) {
+        let path = version_file(dir, &version.id)?;
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("Failed to prune config version {}: {}", version.id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Flatten a config into `dotted.path -> leaf value` pairs
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, serde_json::Value)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, child, out);
+            }
+        }
+        leaf => out.push((prefix.to_string(), leaf.clone())),
+    }
+}
+
+/// Compare two configurations field by field
+pub fn diff_configs(from: &AppConfig, to: &AppConfig) -> Result<Vec<ConfigFieldChange>, String> {
+    let to_fields = |config: &AppConfig| -> Result<Vec<(String, serde_json::Value)>, String> {
+        let value = serde_json::to_value(config).map_err(|e| tr!("common-serialize-config-failed", error = e))?;
+        let mut fields = Vec::new();
+        flatten("", &value, &mut fields);
+        Ok(fields)
+    };
+
+    let from_fields: std::collections::BTreeMap<_, _> = to_fields(from)?.into_iter().collect();
+    let to_fields: std::collections::BTreeMap<_, _> = to_fields(to)?.into_iter().collect();
+
+    let mut keys: Vec<&String> = from_fields.keys().chain(to_fields.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    Ok(keys
+        .into_iter()
+        .filter_map(|key| {
+            let old = from_fields.get(key);
+            let new = to_fields.get(key);
+            if old == new {
+                None
+            } else {
+                Some(ConfigFieldChange {
+                    field: key.clone(),
+                    from: old.cloned(),
+                    to: new.cloned(),
+                })
+            }
+        })
+        .collect())
+}
+
+/// Record a saved configuration in the history
+pub fn record_snapshot(config: &AppConfig) -> Result<ConfigVersion, String> {
+    let version = record_in(&history_dir()?, config, Utc::now())?;
+    info!("Config version {} recorded", version.id);
+    Ok(version)
+}
+
+/// Record the configuration currently on disk if the history is still empty,
+/// so the very first save after an upgrade can also be undone
+pub fn capture_baseline() -> Result<(), String> {
+    let dir = history_dir()?;
+    if !list_in(&dir)?.is_empty() {
+        return Ok(());
+    }
+
+    let config_file = confy::get_configuration_file_path("autodoc-agent", "config")
+        .map_err(|e| tr!("config-path-unavailable", error = e))?;
+    if !config_file.exists() {
+        return Ok(());
+    }
+
+    let current: AppConfig =
+        confy::load_path(&config_file).map_err(|e| tr!("config-load-failed", error = e))?;
+    record_in(&dir, &current, Utc::now())?;
+    Ok(())
+}
+
+// ============= Tauri Commands =============
+
+#[tauri::command]
+pub fn list_config_history() -> Result<Vec<ConfigVersion>, String> {
+    list_in(&history_dir()?)
+}
+
+#[tauri::command]
+pub fn diff_config_versions(from: String, to: String) -> Result<Vec<ConfigFieldChange>, String> {
+    let dir = history_dir()?;
+    diff_configs(&load_in(&dir, &from)?, &load_in(&dir, &to)?)
+}
+
+#[tauri::command]
+pub fn restore_config_version(id: String) -> Result<AppConfig, String> {
+    let restored = load_in(&history_dir()?, &id)?;
+    info!("Restoring config version {}", id);
+
+    // Goes through the regular save path: validation, keychain handling and a new history entry
+    config::save_config(restored.clone())?;
+    Ok(restored)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-history-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_record_and_list_versions() {
+        let dir = test_dir("record");
+        let config = AppConfig::default();
+
+        record_in(&dir, &config, at(0)).unwrap();
+        record_in(&dir, &config, at(10)).unwrap();
+
+        let versions = list_in(&dir).unwrap();
+        assert_eq!(versions.len(), 2);
+        // Newest first
+        assert_eq!(versions[0].saved_at, at(10));
+        assert_eq!(versions[1].saved_at, at(0));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_same_timestamp_does_not_overwrite() {
+        let dir = test_dir("collision");
+        let config = AppConfig::default();
+
+        let first = record_in(&dir, &config, at(0)).unwrap();
+        let second = record_in(&dir, &config, at(0)).unwrap();
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(list_in(&dir).unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_history_is_pruned() {
+        let dir = test_dir("prune");
+        let config = AppConfig::default();
+
+        for i in 0..(MAX_HISTORY_ENTRIES as i64 + 5) {
+            record_in(&dir, &config, at(i)).unwrap();
+        }
+
+        let versions = list_in(&dir).unwrap();
+        assert_eq!(versions.len(), MAX_HISTORY_ENTRIES);
+        // The oldest entries are the ones removed
+        assert_eq!(versions.last().unwrap().saved_at, at(5));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_secrets_excluded_from_history() {
+        let dir = test_dir("secrets");
+        let mut config = AppConfig::default();
+        config.auth.claude_api_key = "sk-ant-secret-history".to_string();
+        config.auth.target_password = Some("hunter2".to_string());
+
+        let version = record_in(&dir, &config, at(0)).unwrap();
+        let content = std::fs::read_to_string(version_file(&dir, &version.id).unwrap()).unwrap();
+        assert!(!content.contains("sk-ant-secret-history"));
+        assert!(!content.contains("hunter2"));
+
+        let loaded = load_in(&dir, &version.id).unwrap();
+        assert_eq!(loaded.auth.claude_api_key, "");
+        assert_eq!(loaded.auth.target_password, None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_diff_configs_reports_changed_fields() {
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.storage.retention_days = 30;
+        new.basic.language = "en".to_string();
+
+        let changes = diff_configs(&old, &new).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, "basic.language");
+        assert_eq!(changes[0].to, Some(serde_json::json!("en")));
+        assert_eq!(changes[1].field, "storage.retention_days");
+        assert_eq!(changes[1].from, Some(serde_json::json!(0)));
+        assert_eq!(changes[1].to, Some(serde_json::json!(30)));
+    }
+
+    #[test]
+    fn test_diff_identical_configs_is_empty() {
+        let config = AppConfig::default();
+        assert!(diff_configs(&config, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_version_id_rejects_traversal() {
+        let dir = test_dir("traversal");
+        assert!(load_in(&dir, "../../etc/passwd").is_err());
+        assert!(version_file(&dir, "").is_err());
+    }
+}
@@ -0,0 +1,759 @@
+use crate::config::{self, AppConfig};
+use crate::http_client::{self, ConnectionStage};
+use crate::i18n::tr;
+use crate::llm_provider::{self, LlmProvider};
+use log::{info, warn};
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Reachable, but something needs attention (e.g. rate limited)
+    Warning,
+    Failed,
+    /// Not configured, nothing to test
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConnectionCheck {
+    /// Stable identifier, e.g. `claude_api` or `target:crm`
+    pub id: String,
+    pub name: String,
+    pub status: CheckStatus,
+    /// Where the connection broke, for network failures
+    pub stage: Option<ConnectionStage>,
+    pub http_status: Option<u16>,
+    pub latency_ms: u64,
+    pub message: String,
+    /// What the user can do about a failure
+    pub hint: Option<String>,
+}
+
+impl ConnectionCheck {
+    fn new(id: &str, name: &str, status: CheckStatus, message: String) -> Self {
+        ConnectionCheck {
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            stage: None,
+            http_status: None,
+            latency_ms: 0,
+            message,
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    fn with_response(mut self, status: StatusCode, latency_ms: u64) -> Self {
+        self.http_status = Some(status.as_u16());
+        self.latency_ms = latency_ms;
+        self
+    }
+
+    /// Failed check built from a transport error, with a hint for the stage it broke at
+    fn from_error(
+        id: &str,
+        name: &str,
+        error: &reqwest::Error,
+        via_proxy: bool,
+        latency_ms: u64,
+    ) -> Self {
+        let stage = http_client::classify_error(error, via_proxy);
+        let hint = match stage {
+            ConnectionStage::Proxy => tr!("check-hint-proxy"),
+            ConnectionStage::Dns => tr!("check-hint-dns"),
+            ConnectionStage::Connect => tr!("check-hint-connect"),
+            ConnectionStage::Tls => tr!("check-hint-tls"),
+            ConnectionStage::Timeout => tr!("check-hint-timeout"),
+            ConnectionStage::Config => tr!("check-hint-config"),
+            ConnectionStage::Http | ConnectionStage::Ok => tr!("check-hint-retry"),
+        };
+        warn!(
+            "Connectivity check '{}' failed at {:?}: {}",
+            id, stage, error
+        );
+
+        let mut check =
+            ConnectionCheck::new(id, name, CheckStatus::Failed, error.to_string()).with_hint(&hint);
+        check.stage = Some(stage);
+        check.latency_ms = latency_ms;
+        check
+    }
+}
+
+fn uses_proxy(config: &AppConfig) -> bool {
+    config
+        .advanced
+        .proxy_url
+        .as_deref()
+        .is_some_and(|url| !url.trim().is_empty())
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+// ============= Claude API =============
+
+async fn check_claude_api(config: &AppConfig, client: &reqwest::Client) -> ConnectionCheck {
+    const ID: &str = "claude_api";
+    const NAME: &str = "Claude API";
+
+    let api_key = &config.auth.claude_api_key;
+    if api_key.is_empty() {
+        return ConnectionCheck::new(ID, NAME, CheckStatus::Failed, tr!("check-claude-no-key"))
+            .with_hint(&tr!("check-claude-no-key-hint"));
+    }
+
+    let url = format!(
+        "{}/v1/models",
+        config
+            .advanced
+            .endpoints
+            .claude_api_base
+            .trim_end_matches('/')
+    );
+    let started = Instant::now();
+    let result = client
+        .get(&url)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .send()
+        .await;
+    let latency_ms = elapsed_ms(started);
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => return ConnectionCheck::from_error(ID, NAME, &e, uses_proxy(config), latency_ms),
+    };
+
+    let status = response.status();
+    let check = match status {
+        s if s.is_success() => {
+            ConnectionCheck::new(ID, NAME, CheckStatus::Ok, tr!("check-claude-key-valid"))
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Failed,
+            tr!("check-claude-key-invalid"),
+        )
+        .with_hint(&tr!("check-claude-key-invalid-hint")),
+        StatusCode::TOO_MANY_REQUESTS => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Warning,
+            tr!("check-claude-rate-limited"),
+        )
+        .with_hint(&tr!("check-claude-rate-limited-hint")),
+        s => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Failed,
+            tr!("check-claude-api-error", status = s),
+        )
+        .with_hint(&tr!("check-claude-api-error-hint")),
+    };
+    check.with_response(status, latency_ms)
+}
+
+// ============= Other LLM Providers =============
+
+/// Model names listed by `/models` (OpenAI-compatible) or `/api/tags` (Ollama)
+fn listed_models(body: &serde_json::Value) -> Vec<String> {
+    let entries = body["data"].as_array().or_else(|| body["models"].as_array());
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry["id"].as_str().or_else(|| entry["name"].as_str()))
+        .map(str::to_string)
+        .collect()
+}
+
+async fn check_llm_provider(config: &AppConfig, client: &reqwest::Client) -> ConnectionCheck {
+    let active = llm_provider::active(config);
+    let id = format!("llm:{}", active.provider.id());
+    let name = active.provider.name();
+
+    if active.api_key.is_empty() && active.provider.requires_key() {
+        return ConnectionCheck::new(&id, name, CheckStatus::Failed, tr!("check-claude-no-key"))
+            .with_hint(&tr!("check-llm-no-key-hint", provider = name));
+    }
+
+    let path = match active.provider {
+        LlmProvider::Ollama => "/api/tags",
+        _ => "/models",
+    };
+    let mut request = client.get(format!("{}{}", active.base_url, path));
+    if !active.api_key.is_empty() {
+        request = request.bearer_auth(&active.api_key);
+    }
+    let started = Instant::now();
+    let result = request.send().await;
+    let latency_ms = elapsed_ms(started);
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => return ConnectionCheck::from_error(&id, name, &e, uses_proxy(config), latency_ms),
+    };
+
+    let status = response.status();
+    let check = match status {
+        s if s.is_success() => {
+            let body = response.json().await.unwrap_or_default();
+            let models = listed_models(&body);
+            // Ollama lists tags as `name:tag`; a bare name means `latest`
+            let found = models.iter().any(|model| {
+                model == &active.model || model.strip_suffix(":latest") == Some(&active.model)
+            });
+            if found {
+                ConnectionCheck::new(&id, name, CheckStatus::Ok, tr!("check-llm-ok"))
+            } else {
+                ConnectionCheck::new(
+                    &id,
+                    name,
+                    CheckStatus::Warning,
+                    tr!("check-llm-model-missing", model = active.model.clone()),
+                )
+                .with_hint(&tr!("check-llm-model-missing-hint"))
+            }
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ConnectionCheck::new(
+            &id,
+            name,
+            CheckStatus::Failed,
+            tr!("check-claude-key-invalid"),
+        )
+        .with_hint(&tr!("check-llm-key-invalid-hint", provider = name)),
+        StatusCode::TOO_MANY_REQUESTS => ConnectionCheck::new(
+            &id,
+            name,
+            CheckStatus::Warning,
+            tr!("check-claude-rate-limited"),
+        )
+        .with_hint(&tr!("check-claude-rate-limited-hint")),
+        s => ConnectionCheck::new(
+            &id,
+            name,
+            CheckStatus::Failed,
+            tr!("check-claude-api-error", status = s),
+        )
+        .with_hint(&tr!("check-llm-api-error-hint", provider = name)),
+    };
+    check.with_response(status, latency_ms)
+}
+
+/// Probe whichever LLM provider is selected
+async fn check_llm(config: &AppConfig, client: &reqwest::Client) -> ConnectionCheck {
+    match config.auth.llm_provider {
+        LlmProvider::Anthropic => check_claude_api(config, client).await,
+        _ => check_llm_provider(config, client).await,
+    }
+}
+
+// ============= Chrome MCP =============
+
+fn chrome_mcp_url(config: &AppConfig) -> Result<Url, String> {
+    let mut url = Url::parse(&config.auth.chrome_mcp_url)
+        .map_err(|e| tr!("check-mcp-url-invalid-detail", error = e))?;
+    url.set_port(Some(config.auth.chrome_mcp_port))
+        .map_err(|_| tr!("check-mcp-url-invalid"))?;
+    Ok(url)
+}
+
+async fn check_chrome_mcp(config: &AppConfig, client: &reqwest::Client) -> ConnectionCheck {
+    const ID: &str = "chrome_mcp";
+    const NAME: &str = "Chrome MCP";
+
+    let url = match chrome_mcp_url(config) {
+        Ok(url) => url,
+        Err(e) => {
+            return ConnectionCheck::new(ID, NAME, CheckStatus::Failed, e)
+                .with_hint(&tr!("check-mcp-url-hint"));
+        }
+    };
+
+    let started = Instant::now();
+    let result = client.get(url.clone()).send().await;
+    let latency_ms = elapsed_ms(started);
+
+    match result {
+        // The MCP server has no health route; any HTTP answer means it is listening
+        Ok(response) => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Ok,
+            tr!("check-mcp-responded", url = url),
+        )
+        .with_response(response.status(), latency_ms),
+        Err(e) => {
+            let mut check = ConnectionCheck::from_error(ID, NAME, &e, false, latency_ms);
+            if check.stage == Some(ConnectionStage::Connect) {
+                check = check.with_hint(&tr!("check-mcp-not-running-hint"));
+            }
+            check
+        }
+    }
+}
+
+// ============= Google Credentials =============
+
+async fn check_google_credentials(config: &AppConfig, client: &reqwest::Client) -> ConnectionCheck {
+    const ID: &str = "google_credentials";
+    const NAME: &str = "Google OAuth";
+
+    let path = match config.auth.google_credentials_path {
+        Some(ref path) if !path.as_os_str().is_empty() => path,
+        _ => {
+            return ConnectionCheck::new(
+                ID,
+                NAME,
+                CheckStatus::Skipped,
+                tr!("check-google-no-credentials"),
+            )
+            .with_hint(&tr!("check-google-no-credentials-hint"));
+        }
+    };
+
+    let secrets = match crate::google_oauth::read_client_secrets(path) {
+        Ok(secrets) => secrets,
+        Err(e) => {
+            return ConnectionCheck::new(ID, NAME, CheckStatus::Failed, e)
+                .with_hint(&tr!("check-google-credentials-hint"));
+        }
+    };
+
+    // A refresh with a bogus token tells the two cases apart without user consent:
+    // an unknown client gets `invalid_client`, a valid one gets `invalid_grant`
+    let params = [
+        ("client_id", secrets.client_id.as_str()),
+        (
+            "client_secret",
+            secrets.client_secret.as_deref().unwrap_or(""),
+        ),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", "autodoc-connectivity-probe"),
+    ];
+
+    let started = Instant::now();
+    let result = client
+        .post(&config.advanced.endpoints.google_token_url)
+        .form(&params)
+        .send()
+        .await;
+    let latency_ms = elapsed_ms(started);
+
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => return ConnectionCheck::from_error(ID, NAME, &e, uses_proxy(config), latency_ms),
+    };
+
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    let error = body.get("error").and_then(|e| e.as_str()).unwrap_or("");
+
+    let check = match error {
+        "invalid_grant" => {
+            ConnectionCheck::new(ID, NAME, CheckStatus::Ok, tr!("check-google-client-valid"))
+        }
+        "invalid_client" | "unauthorized_client" => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Failed,
+            tr!("check-google-client-invalid"),
+        )
+        .with_hint(&tr!("check-google-client-invalid-hint")),
+        _ if status.is_server_error() => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Failed,
+            tr!("check-google-error", status = status),
+        )
+        .with_hint(&tr!("check-google-error-hint")),
+        other => ConnectionCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Warning,
+            tr!("check-google-unknown", status = status, error = other),
+        ),
+    };
+    check.with_response(status, latency_ms)
+}
+
+// ============= Target Applications =============
+
+async fn check_target(
+    config: &AppConfig,
+    client: &reqwest::Client,
+    target: &crate::targets::TargetDefinition,
+) -> ConnectionCheck {
+    let id = format!("target:{}", target.id);
+    let name = target.name.as_str();
+
+    if target.entry_url.is_empty() {
+        return ConnectionCheck::new(&id, name, CheckStatus::Skipped, tr!("check-target-no-url"));
+    }
+
+    let started = Instant::now();
+    let result = client.get(&target.entry_url).send().await;
+    let latency_ms = elapsed_ms(started);
+
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            // Login walls (401/403) still prove the application is reachable
+            let check = if status.is_server_error() {
+                ConnectionCheck::new(
+                    &id,
+                    name,
+                    CheckStatus::Failed,
+                    tr!("check-target-error", status = status),
+                )
+                .with_hint(&tr!("check-target-error-hint"))
+            } else {
+                ConnectionCheck::new(
+                    &id,
+                    name,
+                    CheckStatus::Ok,
+                    tr!("check-target-reachable", status = status),
+                )
+            };
+            check.with_response(status, latency_ms)
+        }
+        Err(e) => ConnectionCheck::from_error(&id, name, &e, uses_proxy(config), latency_ms),
+    }
+}
+
+// ============= Runner =============
+
+/// Probe every configured dependency concurrently
+pub async fn run_checks(config: &AppConfig) -> Vec<ConnectionCheck> {
+    let client = match http_client::build_client(&config.advanced, CHECK_TIMEOUT) {
+        Ok(client) => client,
+        Err(e) => {
+            let mut check = ConnectionCheck::new("http_client", "HTTP", CheckStatus::Failed, e)
+                .with_hint(&tr!("check-hint-http-client"));
+            check.stage = Some(ConnectionStage::Config);
+            return vec![check];
+        }
+    };
+
+    let (llm, mcp, google) = tokio::join!(
+        check_llm(config, &client),
+        check_chrome_mcp(config, &client),
+        check_google_credentials(config, &client),
+    );
+
+    let mut checks = vec![llm, mcp, google];
+    let mut targets = tokio::task::JoinSet::new();
+    for (index, target) in config.auth.targets.iter().cloned().enumerate() {
+        let config = config.clone();
+        let client = client.clone();
+        targets.spawn(async move { (index, check_target(&config, &client, &target).await) });
+    }
+
+    let mut target_checks = Vec::new();
+    while let Some(result) = targets.join_next().await {
+        match result {
+            Ok(entry) => target_checks.push(entry),
+            Err(e) => warn!("Target connectivity check panicked: {}", e),
+        }
+    }
+    target_checks.sort_by_key(|(index, _)| *index);
+    checks.extend(target_checks.into_iter().map(|(_, check)| check));
+
+    checks
+}
+
+/// Unsaved settings come from the UI without the stored key. It is only filled in
+/// while they point at the saved endpoint, so the UI cannot have the key sent to a
+/// host of its choosing; for any other endpoint the UI has to enter the key itself.
+fn fill_stored_key(
+    config: &mut AppConfig,
+    saved: &AppConfig,
+    stored: impl FnOnce(&str) -> Option<String>,
+) {
+    let provider = config.auth.llm_provider;
+    if llm_provider::base_url(config, provider) != llm_provider::base_url(saved, provider) {
+        return;
+    }
+    let api_key = llm_provider::api_key_mut(config, provider);
+    if api_key.is_empty() {
+        if let Some(key) = stored(provider.secret_key()) {
+            *api_key = key;
+        }
+    }
+}
+
+// ============= Tauri Commands =============
+
+/// Test connectivity to every external dependency. Accepts unsaved settings from
+/// the settings window; falls back to the saved configuration.
+#[tauri::command]
+pub async fn test_connections(config: Option<AppConfig>) -> Result<Vec<ConnectionCheck>, String> {
+    let config = match config {
+        Some(mut config) => {
+            fill_stored_key(&mut config, &config::load_settings(), |key| {
+                crate::secure_storage::get_credential(key).ok()
+            });
+            config
+        }
+        None => config::load_config_with_secrets()?,
+    };
+
+    info!("Running connectivity self-test");
+    Ok(run_checks(&config).await)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::test_support::stub_server;
+    use std::net::TcpListener;
+
+    fn unused_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    fn test_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.auth.claude_api_key = "sk-ant-test".to_string();
+        config
+    }
+
+    fn client() -> reqwest::Client {
+        http_client::build_client(&test_config().advanced, Duration::from_secs(5)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_claude_api_ok_sends_key() {
+        let (base, requests) = stub_server("200 OK", r#"{"data":[]}"#);
+        let mut config = test_config();
+        config.advanced.endpoints.claude_api_base = base;
+
+        let check = check_claude_api(&config, &client()).await;
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert_eq!(check.http_status, Some(200));
+
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /v1/models"));
+        assert!(request.contains("x-api-key: sk-ant-test"));
+    }
+
+    #[tokio::test]
+    async fn test_claude_api_invalid_key() {
+        let (base, _requests) = stub_server("401 Unauthorized", r#"{"error":{}}"#);
+        let mut config = test_config();
+        config.advanced.endpoints.claude_api_base = base;
+
+        let check = check_claude_api(&config, &client()).await;
+        assert_eq!(check.status, CheckStatus::Failed);
+        assert!(check.hint.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_claude_api_rate_limited_is_warning() {
+        let (base, _requests) = stub_server("429 Too Many Requests", "{}");
+        let mut config = test_config();
+        config.advanced.endpoints.claude_api_base = base;
+
+        assert_eq!(
+            check_claude_api(&config, &client()).await.status,
+            CheckStatus::Warning
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claude_api_missing_key() {
+        let config = AppConfig::default();
+        let check = check_claude_api(&config, &client()).await;
+        assert_eq!(check.status, CheckStatus::Failed);
+        assert_eq!(check.http_status, None);
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_and_ollama_checks() {
+        let (base, requests) = stub_server("200 OK", r#"{"data":[{"id":"corp-vision"}]}"#);
+        let mut config = test_config();
+        config.auth.llm_provider = LlmProvider::OpenaiCompatible;
+        config.auth.openai_compatible.base_url = format!("{}/v1", base);
+        config.auth.openai_compatible.model = "corp-vision".to_string();
+        config.auth.openai_compatible.api_key = "corp-token".to_string();
+
+        let check = check_llm(&config, &client()).await;
+        assert_eq!(check.id, "llm:openai_compatible");
+        assert_eq!(check.status, CheckStatus::Ok);
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /v1/models"));
+        assert!(request.contains("authorization: bearer corp-token"));
+
+        // Ollama needs no key; a model that was never pulled is only a warning
+        let (base, requests) = stub_server("200 OK", r#"{"models":[{"name":"llava:latest"}]}"#);
+        config.auth.llm_provider = LlmProvider::Ollama;
+        config.auth.ollama.base_url = base;
+        config.auth.ollama.model = "llava".to_string();
+        assert_eq!(check_llm(&config, &client()).await.status, CheckStatus::Ok);
+        let request = requests.recv().unwrap().to_lowercase();
+        assert!(request.starts_with("get /api/tags"));
+        assert!(!request.contains("authorization"));
+
+        let (base, _requests) = stub_server("200 OK", r#"{"models":[]}"#);
+        config.auth.ollama.base_url = base;
+        assert_eq!(check_llm(&config, &client()).await.status, CheckStatus::Warning);
+    }
+
+    #[tokio::test]
+    async fn test_chrome_mcp_listening_and_down() {
+        let (base, _requests) = stub_server("404 Not Found", "");
+        let port = Url::parse(&base).unwrap().port().unwrap();
+        let mut config = test_config();
+        config.auth.chrome_mcp_url = "http://127.0.0.1".to_string();
+        config.auth.chrome_mcp_port = port;
+        assert_eq!(
+            check_chrome_mcp(&config, &client()).await.status,
+            CheckStatus::Ok
+        );
+
+        config.auth.chrome_mcp_port = unused_port();
+        let check = check_chrome_mcp(&config, &client()).await;
+        assert_eq!(check.status, CheckStatus::Failed);
+        assert_eq!(check.stage, Some(ConnectionStage::Connect));
+        assert!(check.hint.unwrap().contains("Chrome MCP"));
+    }
+
+    #[tokio::test]
+    async fn test_google_credentials_checks() {
+        let dir = std::env::temp_dir().join("autodoc-connectivity-test-google");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("credentials.json");
+        std::fs::write(
+            &path,
+            r#"{"installed":{"client_id":"abc","client_secret":"xyz"}}"#,
+        )
+        .unwrap();
+
+        let mut config = test_config();
+        assert_eq!(
+            check_google_credentials(&config, &client()).await.status,
+            CheckStatus::Skipped
+        );
+
+        config.auth.google_credentials_path = Some(path.clone());
+        let (base, requests) = stub_server("400 Bad Request", r#"{"error":"invalid_grant"}"#);
+        config.advanced.endpoints.google_token_url = format!("{}/token", base);
+        assert_eq!(
+            check_google_credentials(&config, &client()).await.status,
+            CheckStatus::Ok
+        );
+        assert!(requests.recv().unwrap().contains("client_id=abc"));
+
+        let (base, _requests) = stub_server("401 Unauthorized", r#"{"error":"invalid_client"}"#);
+        config.advanced.endpoints.google_token_url = format!("{}/token", base);
+        assert_eq!(
+            check_google_credentials(&config, &client()).await.status,
+            CheckStatus::Failed
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(
+            check_google_credentials(&config, &client()).await.status,
+            CheckStatus::Failed
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_checks_includes_targets_in_order() {
+        let (claude, _r1) = stub_server("200 OK", "{}");
+        let (target_ok, _r2) = stub_server("403 Forbidden", "");
+        let (target_down, _r3) = stub_server("503 Service Unavailable", "");
+
+        let mut config = test_config();
+        config.advanced.endpoints.claude_api_base = claude;
+        config.auth.chrome_mcp_url = "http://127.0.0.1".to_string();
+        config.auth.chrome_mcp_port = unused_port();
+        for (id, url) in [("crm", target_ok), ("erp", target_down)] {
+            config.auth.targets.push(crate::targets::TargetDefinition {
+                id: id.to_string(),
+                name: id.to_uppercase(),
+                entry_url: url,
+                auth_type: crate::targets::TargetAuthType::None,
+                username: None,
+                login_url: None,
+                selectors: Default::default(),
+                secret: None,
+            });
+        }
+
+        let checks = run_checks(&config).await;
+        let summary: Vec<(&str, CheckStatus)> =
+            checks.iter().map(|c| (c.id.as_str(), c.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("claude_api", CheckStatus::Ok),
+                ("chrome_mcp", CheckStatus::Failed),
+                ("google_credentials", CheckStatus::Skipped),
+                ("target:crm", CheckStatus::Ok),
+                ("target:erp", CheckStatus::Failed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stored_key_only_sent_to_saved_endpoint() {
+        let saved = AppConfig::default();
+        let stored = |key: &str| Some(format!("stored-{}", key));
+
+        let mut unsaved = saved.clone();
+        fill_stored_key(&mut unsaved, &saved, stored);
+        assert_eq!(unsaved.auth.claude_api_key, "stored-claude_api_key");
+
+        // A key typed in the UI is used as is
+        let mut typed = saved.clone();
+        typed.auth.claude_api_key = "sk-ant-typed".to_string();
+        fill_stored_key(&mut typed, &saved, stored);
+        assert_eq!(typed.auth.claude_api_key, "sk-ant-typed");
+
+        // Another host never receives the stored key
+        let mut redirected = saved.clone();
+        redirected.advanced.endpoints.claude_api_base = "https://attacker.example".to_string();
+        fill_stored_key(&mut redirected, &saved, stored);
+        assert!(redirected.auth.claude_api_key.is_empty());
+
+        let mut other_provider = saved.clone();
+        other_provider.auth.llm_provider = LlmProvider::Ollama;
+        other_provider.auth.ollama.base_url = "http://attacker.example:11434".to_string();
+        fill_stored_key(&mut other_provider, &saved, stored);
+        assert!(other_provider.auth.ollama.api_key.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_proxy_reported_once() {
+        let mut config = test_config();
+        config.advanced.proxy_url = Some("ftp://nope".to_string());
+
+        let checks = run_checks(&config).await;
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].stage, Some(ConnectionStage::Config));
+    }
+}
@@ -0,0 +1,669 @@
+use crate::config::{self, AppConfig};
+use crate::i18n::tr;
+use crate::llm_provider::LlmProvider;
+use crate::{api_gateway, google_oauth, key_pool, secure_storage, session_vault, targets, tray_v2};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Sidecar file with per-credential metadata; never holds secret values
+const METADATA_FILE_NAME: &str = "credentials.json";
+/// Values shorter than this get no hint at all
+const MIN_HINT_LEN: usize = 12;
+/// Last-used times are only written this often per credential
+const USE_RESOLUTION: ChronoDuration = ChronoDuration::minutes(10);
+const REMINDER_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Event emitted when a credential newly needs rotating or is about to expire
+pub const REMINDER_EVENT: &str = "credential-reminder";
+
+/// Serializes read-modify-write of the metadata file
+static METADATA_LOCK: Mutex<()> = Mutex::new(());
+/// Last-used time written per credential, to keep the gateway's hot path off the disk
+static LAST_RECORDED_USE: Mutex<Option<HashMap<String, DateTime<Utc>>>> = Mutex::new(None);
+/// Credentials already reminded about, so the monitor does not repeat itself
+static REMINDED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// What the webview may do with a credential
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialPolicy {
+    /// Settable from the UI; only presence is ever reported back
+    WriteOnly,
+    /// Settable from the UI; reported back as a masked hint like `sk-…Ab3d`
+    MaskedRead,
+    /// Written and read by Rust only, e.g. OAuth tokens
+    InternalOnly,
+}
+
+impl CredentialPolicy {
+    fn writable_from_ui(self) -> bool {
+        self != CredentialPolicy::InternalOnly
+    }
+}
+
+/// Policy of a known credential ID; unknown IDs are refused
+pub fn policy(key: &str) -> Option<CredentialPolicy> {
+    let provider_keys = [
+        LlmProvider::Anthropic,
+        LlmProvider::OpenaiCompatible,
+        LlmProvider::Ollama,
+    ]
+    .map(LlmProvider::secret_key);
+
+    if provider_keys.contains(&key) || key_pool::key_id_from_key(key).is_some() {
+        Some(CredentialPolicy::MaskedRead)
+    } else if matches!(key, "target_password" | "proxy_password")
+        || targets::target_id_from_key(key).is_some()
+    {
+        Some(CredentialPolicy::WriteOnly)
+    } else if key == google_oauth::TOKEN_KEY || key == session_vault::VAULT_KEY {
+        Some(CredentialPolicy::InternalOnly)
+    } else {
+        None
+    }
+}
+
+fn require_policy(key: &str) -> Result<CredentialPolicy, String> {
+    policy(key).ok_or_else(|| tr!("credential-unknown", key = key))
+}
+
+/// First three and last four characters, enough to tell keys apart
+fn mask(value: &str) -> Option<String> {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() < MIN_HINT_LEN {
+        return None;
+    }
+    let head: String = chars[..3].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    Some(format!("{}…{}", head, tail))
+}
+
+// ============= Metadata =============
+
+/// Where the current value of a credential came from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// Entered in the settings window
+    Settings,
+    /// Restored from a backup or configuration bundle
+    Import,
+    /// Moved out of a plaintext config file
+    Migration,
+    /// Written by the app itself, e.g. an OAuth token refresh
+    App,
+}
+
+/// Fields are optional because credentials stored before tracking began have no history
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CredentialMetadata {
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Set by the user, e.g. for a key issued with a fixed lifetime
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub source: Option<CredentialSource>,
+}
+
+#[cfg(not(test))]
+fn metadata_path() -> Result<PathBuf, String> {
+    Ok(crate::config::config_dir()?.join(METADATA_FILE_NAME))
+}
+
+/// Tests never touch the user's config directory
+#[cfg(test)]
+fn metadata_path() -> Result<PathBuf, String> {
+    Ok(std::env::temp_dir()
+        .join("autodoc-credential-registry-test-metadata")
+        .join(METADATA_FILE_NAME))
+}
+
+fn read_metadata(path: &Path) -> BTreeMap<String, CredentialMetadata> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable credential metadata: {}", e);
+            BTreeMap::new()
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => {
+            warn!("Failed to read credential metadata: {}", e);
+            BTreeMap::new()
+        }
+    }
+}
+
+fn write_metadata(path: &Path, metadata: &BTreeMap<String, CredentialMetadata>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(metadata)?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content).and_then(|_| std::fs::rename(&temp, path))
+}
+
+/// Apply `change` to the stored metadata; failures are logged, never fatal
+fn update_metadata(change: impl FnOnce(&mut BTreeMap<String, CredentialMetadata>)) {
+    let _guard = METADATA_LOCK.lock().unwrap();
+    let result = metadata_path().and_then(|path| {
+        let mut metadata = read_metadata(&path);
+        change(&mut metadata);
+        write_metadata(&path, &metadata).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        warn!("Failed to update credential metadata: {}", e);
+    }
+}
+
+/// Called by `secure_storage` after a credential got a new value.
+/// A new value starts a new lifetime, so any expiry of the old one is dropped.
+pub fn record_updated(key: &str, source: CredentialSource) {
+    let now = Utc::now();
+    update_metadata(|metadata| {
+        let entry = metadata.entry(key.to_string()).or_default();
+        entry.created_at.get_or_insert(now);
+        entry.updated_at = Some(now);
+        entry.expires_at = None;
+        entry.source = Some(source);
+    });
+}
+
+/// Note that a credential was just used to talk to a service
+pub fn record_used(key: &str) {
+    let now = Utc::now();
+    {
+        let mut recorded = LAST_RECORDED_USE.lock().unwrap();
+        let recorded = recorded.get_or_insert_with(HashMap::new);
+        if recorded
+            .get(key)
+            .is_some_and(|last| now - *last < USE_RESOLUTION)
+        {
+            return;
+        }
+        recorded.insert(key.to_string(), now);
+    }
+    update_metadata(|metadata| {
+        metadata.entry(key.to_string()).or_default().last_used_at = Some(now);
+    });
+}
+
+/// Called by `secure_storage` after a credential was deleted
+pub fn forget(key: &str) {
+    update_metadata(|metadata| {
+        metadata.remove(key);
+    });
+    if let Some(recorded) = LAST_RECORDED_USE.lock().unwrap().as_mut() {
+        recorded.remove(key);
+    }
+}
+
+fn metadata_for(key: &str) -> Option<CredentialMetadata> {
+    all_metadata().remove(key)
+}
+
+fn all_metadata() -> BTreeMap<String, CredentialMetadata> {
+    let _guard = METADATA_LOCK.lock().unwrap();
+    metadata_path()
+        .map(|path| read_metadata(&path))
+        .unwrap_or_default()
+}
+
+// ============= Rotation Reminders =============
+
+/// Why a credential needs attention
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CredentialReminder {
+    /// Unchanged for longer than the configured rotation period
+    RotationDue {
+        age_days: i64,
+    },
+    ExpiringSoon {
+        expires_at: DateTime<Utc>,
+    },
+    Expired {
+        expires_at: DateTime<Utc>,
+    },
+}
+
+impl CredentialReminder {
+    fn message(&self, label: &str) -> String {
+        match self {
+            CredentialReminder::RotationDue { age_days } => {
+                tr!("credential-rotation-due", name = label, days = *age_days)
+            }
+            CredentialReminder::ExpiringSoon { expires_at } => tr!(
+                "credential-expiring-soon",
+                name = label,
+                date = expires_at.format("%Y-%m-%d").to_string()
+            ),
+            CredentialReminder::Expired { expires_at } => tr!(
+                "credential-expired",
+                name = label,
+                date = expires_at.format("%Y-%m-%d").to_string()
+            ),
+        }
+    }
+}
+
+/// Expiry takes precedence over age; a period of 0 days turns the check off.
+/// Rust-internal credentials renew themselves and are never due for rotation.
+fn reminder_for(
+    metadata: &CredentialMetadata,
+    policy: CredentialPolicy,
+    now: DateTime<Utc>,
+    rotation_days: u32,
+    expiry_warning_days: u32,
+) -> Option<CredentialReminder> {
+    if let Some(expires_at) = metadata.expires_at {
+        if expires_at <= now {
+            return Some(CredentialReminder::Expired { expires_at });
+        }
+        if expires_at - now <= ChronoDuration::days(expiry_warning_days.into()) {
+            return Some(CredentialReminder::ExpiringSoon { expires_at });
+        }
+    }
+
+    let changed_at = metadata.updated_at.or(metadata.created_at)?;
+    let age_days = (now - changed_at).num_days();
+    (policy != CredentialPolicy::InternalOnly
+        && rotation_days > 0
+        && age_days >= i64::from(rotation_days))
+    .then_some(CredentialReminder::RotationDue { age_days })
+}
+
+/// Name shown to the user: the target or pooled key name where there is one
+fn label(key: &str, config: &AppConfig) -> String {
+    if let Some(target) =
+        targets::target_id_from_key(key).and_then(|id| targets::find_target(&config.auth, id))
+    {
+        return target.name.clone();
+    }
+    if let Some(claude_key) =
+        key_pool::key_id_from_key(key).and_then(|id| key_pool::find_key(&config.auth, id))
+    {
+        return claude_key.name.clone();
+    }
+    match key {
+        "target_password" => tr!("credential-label-target-password"),
+        "proxy_password" => tr!("credential-label-proxy-password"),
+        _ if key == google_oauth::TOKEN_KEY => tr!("credential-label-google-token"),
+        _ if key == session_vault::VAULT_KEY => tr!("credential-label-session-vault-key"),
+        _ => [
+            LlmProvider::Anthropic,
+            LlmProvider::OpenaiCompatible,
+            LlmProvider::Ollama,
+        ]
+        .into_iter()
+        .find(|provider| provider.secret_key() == key)
+        .map_or_else(|| key.to_string(), |provider| provider.name().to_string()),
+    }
+}
+
+/// Every credential the app knows of for `config`, with its history
+fn list(config: &AppConfig, now: DateTime<Utc>) -> Vec<CredentialInfo> {
+    let mut metadata = all_metadata();
+    let advanced = &config.advanced;
+
+    secure_storage::known_secret_keys(config)
+        .into_iter()
+        .filter_map(|key| {
+            let policy = policy(&key)?;
+            let present = secure_storage::has_credential(&key);
+            let metadata = metadata.remove(&key).unwrap_or_default();
+            let reminder = present
+                .then(|| {
+                    reminder_for(
+                        &metadata,
+                        policy,
+                        now,
+                        advanced.credential_rotation_days,
+                        advanced.credential_expiry_warning_days,
+                    )
+                })
+                .flatten();
+            Some(CredentialInfo {
+                label: label(&key, config),
+                key,
+                policy,
+                present,
+                metadata,
+                reminder,
+            })
+        })
+        .collect()
+}
+
+/// Remind about credentials that newly need attention: an event for the UI
+/// and a tray tooltip that stays until nothing is due any more
+fn notify_reminders<R: Runtime>(app: &AppHandle<R>, credentials: &[CredentialInfo]) {
+    let due: Vec<&CredentialInfo> = credentials
+        .iter()
+        .filter(|c| c.reminder.is_some())
+        .collect();
+    let messages: Vec<String> = due.iter().filter_map(|c| c.reminder_message()).collect();
+    tray_v2::set_attention(
+        app,
+        (!messages.is_empty())
+            .then(|| messages.join("\n"))
+            .as_deref(),
+    );
+
+    let mut reminded = REMINDED.lock().unwrap();
+    // Credentials that were rotated may remind again once they age
+    reminded.retain(|key| due.iter().any(|c| &c.key == key));
+    for credential in due {
+        if reminded.contains(&credential.key) {
+            continue;
+        }
+        if let Some(message) = credential.reminder_message() {
+            info!("{}", message);
+        }
+        if let Err(e) = app.emit(REMINDER_EVENT, credential) {
+            warn!("Failed to emit credential reminder: {}", e);
+        }
+        reminded.push(credential.key.clone());
+    }
+}
+
+/// Check credential ages and expiry now and then; the first check runs at startup
+pub fn spawn_reminder_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_INTERVAL);
+        loop {
+            interval.tick().await;
+            let credentials = list(&config::load_settings(), Utc::now());
+            notify_reminders(&app, &credentials);
+        }
+    });
+}
+
+// ============= Tauri Commands =============
+
+/// What the UI may know about a credential: never the value itself
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CredentialStatus {
+    pub key: String,
+    pub policy: CredentialPolicy,
+    pub present: bool,
+    /// Only for `MaskedRead` credentials
+    pub hint: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[tauri::command]
+pub fn get_credential_status(key: String) -> Result<CredentialStatus, String> {
+    let policy = require_policy(&key)?;
+    let value = secure_storage::get_credential(&key).ok();
+
+    Ok(CredentialStatus {
+        policy,
+        present: value.is_some(),
+        hint: value
+            .as_deref()
+            .filter(|_| policy == CredentialPolicy::MaskedRead)
+            .and_then(mask),
+        updated_at: metadata_for(&key).and_then(|m| m.updated_at),
+        key,
+    })
+}
+
+/// A known credential and its history, without the value
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CredentialInfo {
+    pub key: String,
+    pub label: String,
+    pub policy: CredentialPolicy,
+    pub present: bool,
+    #[serde(flatten)]
+    pub metadata: CredentialMetadata,
+    pub reminder: Option<CredentialReminder>,
+}
+
+impl CredentialInfo {
+    fn reminder_message(&self) -> Option<String> {
+        self.reminder.as_ref().map(|r| r.message(&self.label))
+    }
+}
+
+#[tauri::command]
+pub fn list_credentials() -> Vec<CredentialInfo> {
+    list(&config::load_settings(), Utc::now())
+}
+
+/// Record when a credential stops working; `None` clears it
+#[tauri::command]
+pub fn set_credential_expiry(key: String, expires_at: Option<DateTime<Utc>>) -> Result<(), String> {
+    if !require_policy(&key)?.writable_from_ui() {
+        return Err(tr!("credential-internal", key = key));
+    }
+    if !secure_storage::has_credential(&key) {
+        return Err(tr!("secret-not-found", key = key));
+    }
+    update_metadata(|metadata| {
+        metadata.entry(key).or_default().expires_at = expires_at;
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn store_secure_credential(key: String, value: String) -> Result<(), String> {
+    if !require_policy(&key)?.writable_from_ui() {
+        return Err(tr!("credential-internal", key = key));
+    }
+    secure_storage::store_credential_from(&key, &value, CredentialSource::Settings)?;
+    api_gateway::reset_key_health(&key);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_secure_credential(key: String) -> Result<(), String> {
+    if !require_policy(&key)?.writable_from_ui() {
+        return Err(tr!("credential-internal", key = key));
+    }
+    secure_storage::delete_credential(&key)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policies_for_known_and_unknown_keys() {
+        assert_eq!(policy("claude_api_key"), Some(CredentialPolicy::MaskedRead));
+        assert_eq!(
+            policy("claude_api_key:team-a"),
+            Some(CredentialPolicy::MaskedRead)
+        );
+        assert_eq!(
+            policy("target_secret:crm"),
+            Some(CredentialPolicy::WriteOnly)
+        );
+        assert_eq!(policy("proxy_password"), Some(CredentialPolicy::WriteOnly));
+        assert_eq!(
+            policy("google_oauth_token"),
+            Some(CredentialPolicy::InternalOnly)
+        );
+        assert_eq!(
+            policy("session_vault_key"),
+            Some(CredentialPolicy::InternalOnly)
+        );
+
+        assert_eq!(policy("target_secret:../x"), None);
+        assert_eq!(policy("anything_else"), None);
+        assert!(get_credential_status("anything_else".to_string()).is_err());
+        assert!(store_secure_credential("anything_else".to_string(), "x".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_status_never_returns_value() {
+        let key = "claude_api_key:registry-status";
+        store_secure_credential(key.to_string(), "sk-ant-api03-secretAb3d".to_string()).unwrap();
+
+        let status = get_credential_status(key.to_string()).unwrap();
+        assert!(status.present);
+        assert_eq!(status.hint.as_deref(), Some("sk-…Ab3d"));
+        assert!(status.updated_at.is_some());
+
+        let write_only = "target_secret:registry-status";
+        store_secure_credential(write_only.to_string(), "hunter2-long-password".to_string())
+            .unwrap();
+        let status = get_credential_status(write_only.to_string()).unwrap();
+        assert!(status.present);
+        assert_eq!(status.hint, None);
+
+        delete_secure_credential(key.to_string()).unwrap();
+        delete_secure_credential(write_only.to_string()).unwrap();
+        let status = get_credential_status(key.to_string()).unwrap();
+        assert!(!status.present);
+        assert_eq!(status.updated_at, None);
+    }
+
+    #[test]
+    fn test_internal_credentials_not_writable_from_ui() {
+        let key = google_oauth::TOKEN_KEY.to_string();
+        assert!(store_secure_credential(key.clone(), "{}".to_string()).is_err());
+        assert!(delete_secure_credential(key.clone()).is_err());
+
+        // Presence is still reported
+        secure_storage::store_credential(&key, "{\"access_token\":\"t\"}").unwrap();
+        let status = get_credential_status(key.clone()).unwrap();
+        assert!(status.present);
+        assert_eq!(status.hint, None);
+        secure_storage::delete_credential(&key).unwrap();
+    }
+
+    #[test]
+    fn test_tauri_command_store_and_delete() {
+        let key = "claude_api_key:test-tauri-store".to_string();
+        store_secure_credential(key.clone(), "test_value".to_string()).unwrap();
+        assert_eq!(secure_storage::get_credential(&key).unwrap(), "test_value");
+
+        delete_secure_credential(key.clone()).unwrap();
+        assert!(!secure_storage::has_credential(&key));
+        assert!(!get_credential_status(key).unwrap().present);
+    }
+
+    #[test]
+    fn test_metadata_tracks_updates_and_use() {
+        let key = "claude_api_key:registry-metadata";
+        secure_storage::store_credential_from(key, "sk-ant-first", CredentialSource::Import)
+            .unwrap();
+        let first = metadata_for(key).unwrap();
+        assert_eq!(first.source, Some(CredentialSource::Import));
+        assert_eq!(first.created_at, first.updated_at);
+        assert_eq!(first.last_used_at, None);
+
+        record_used(key);
+        assert!(metadata_for(key).unwrap().last_used_at.is_some());
+
+        // Expiry belongs to the old value; saving the same value again changes nothing
+        set_credential_expiry(key.to_string(), Some(Utc::now())).unwrap();
+        secure_storage::store_credential(key, "sk-ant-first").unwrap();
+        assert!(metadata_for(key).unwrap().expires_at.is_some());
+
+        secure_storage::store_credential(key, "sk-ant-second").unwrap();
+        let second = metadata_for(key).unwrap();
+        assert_eq!(second.created_at, first.created_at);
+        assert_eq!(second.source, Some(CredentialSource::Settings));
+        assert_eq!(second.expires_at, None);
+
+        secure_storage::delete_credential(key).unwrap();
+        assert_eq!(metadata_for(key), None);
+        assert!(set_credential_expiry(key.to_string(), None).is_err());
+    }
+
+    #[test]
+    fn test_reminders() {
+        let now = Utc::now();
+        let days = ChronoDuration::days;
+        let metadata = |updated: i64, expires: Option<i64>| CredentialMetadata {
+            updated_at: Some(now - days(updated)),
+            expires_at: expires.map(|d| now + days(d)),
+            ..Default::default()
+        };
+        let masked = CredentialPolicy::MaskedRead;
+
+        assert_eq!(reminder_for(&metadata(10, None), masked, now, 90, 7), None);
+        assert_eq!(
+            reminder_for(&metadata(120, None), masked, now, 90, 7),
+            Some(CredentialReminder::RotationDue { age_days: 120 })
+        );
+        // Turned off, or renewed by the app itself
+        assert_eq!(reminder_for(&metadata(120, None), masked, now, 0, 7), None);
+        assert_eq!(
+            reminder_for(
+                &metadata(120, None),
+                CredentialPolicy::InternalOnly,
+                now,
+                90,
+                7
+            ),
+            None
+        );
+
+        assert!(matches!(
+            reminder_for(&metadata(10, Some(3)), masked, now, 90, 7),
+            Some(CredentialReminder::ExpiringSoon { .. })
+        ));
+        assert!(matches!(
+            reminder_for(&metadata(120, Some(-1)), masked, now, 90, 7),
+            Some(CredentialReminder::Expired { .. })
+        ));
+        assert_eq!(
+            reminder_for(&metadata(10, Some(30)), masked, now, 90, 7),
+            None
+        );
+
+        // No history, no guess
+        assert_eq!(
+            reminder_for(&CredentialMetadata::default(), masked, now, 90, 7),
+            None
+        );
+    }
+
+    #[test]
+    fn test_list_labels_known_credentials() {
+        let mut config = AppConfig::default();
+        config.auth.targets.push(targets::TargetDefinition {
+            id: "registry-list".to_string(),
+            name: "CRM".to_string(),
+            entry_url: "https://crm.example.com".to_string(),
+            auth_type: targets::TargetAuthType::Cookie,
+            username: None,
+            login_url: None,
+            selectors: Default::default(),
+            secret: None,
+        });
+        let key = targets::secret_key("registry-list");
+        secure_storage::store_credential(&key, "hunter2").unwrap();
+
+        let credentials = list(&config, Utc::now());
+        let target = credentials.iter().find(|c| c.key == key).unwrap();
+        assert_eq!(target.label, "CRM");
+        assert!(target.present);
+        assert_eq!(target.policy, CredentialPolicy::WriteOnly);
+        assert!(target.metadata.updated_at.is_some());
+        assert!(credentials.iter().any(|c| c.key == google_oauth::TOKEN_KEY));
+
+        secure_storage::delete_credential(&key).unwrap();
+    }
+
+    #[test]
+    fn test_mask_hides_short_values() {
+        assert_eq!(mask("short"), None);
+        assert_eq!(mask("sk-ant-1234567890"), Some("sk-…7890".to_string()));
+    }
+}
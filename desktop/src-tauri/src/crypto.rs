@@ -0,0 +1,240 @@
+use crate::i18n::tr;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+
+const KDF_NAME: &str = "argon2id";
+const CIPHER_NAME: &str = "xchacha20poly1305";
+const SALT_LEN: usize = 16;
+pub const KEY_LEN: usize = 32;
+
+/// Upper bounds for parameters read from imported files, so a crafted bundle
+/// cannot make key derivation allocate gigabytes or run for minutes
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+/// Passphrase-encrypted payload; every binary field is base64 encoded so the
+/// blob can be embedded in TOML or JSON documents
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EncryptedBlob {
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+    pub cipher: String,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: &KdfParams) -> Result<[u8; KEY_LEN], String> {
+    if params.memory_kib > MAX_MEMORY_KIB
+        || params.iterations > MAX_ITERATIONS
+        || params.parallelism > MAX_PARALLELISM
+    {
+        return Err(tr!("crypto-kdf-limits"));
+    }
+
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(KEY_LEN),
+    )
+    .map_err(|e| tr!("crypto-kdf-invalid", error = e))?;
+
+    let mut key = [0u8; KEY_LEN];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| tr!("crypto-kdf-failed", error = e))?;
+    Ok(key)
+}
+
+/// Encrypt with a key derived from a user passphrase (Argon2id + XChaCha20-Poly1305)
+pub fn encrypt_with_passphrase(plaintext: &[u8], passphrase: &str) -> Result<EncryptedBlob, String> {
+    if passphrase.is_empty() {
+        return Err(tr!("crypto-passphrase-empty"));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+
+    let kdf_params = KdfParams::default();
+    let key = derive_key(passphrase, &salt, &kdf_params)?;
+
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| tr!("crypto-encrypt-failed"))?;
+
+    Ok(EncryptedBlob {
+        kdf: KDF_NAME.to_string(),
+        kdf_params,
+        cipher: CIPHER_NAME.to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// Decrypt a blob produced by `encrypt_with_passphrase`
+pub fn decrypt_with_passphrase(blob: &EncryptedBlob, passphrase: &str) -> Result<Vec<u8>, String> {
+    if blob.kdf != KDF_NAME || blob.cipher != CIPHER_NAME {
+        let scheme = format!("{}/{}", blob.kdf, blob.cipher);
+        return Err(tr!("crypto-unsupported-scheme", scheme = scheme));
+    }
+
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|_| tr!("crypto-corrupted", field = field))
+    };
+    let salt = decode("salt", &blob.salt)?;
+    let nonce = decode("nonce", &blob.nonce)?;
+    let ciphertext = decode("ciphertext", &blob.ciphertext)?;
+
+    if nonce.len() != 24 {
+        return Err(tr!("crypto-corrupted", field = "nonce"));
+    }
+
+    let key = derive_key(passphrase, &salt, &blob.kdf_params)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+
+    // AEAD failure means either a wrong passphrase or tampered data; don't guess which
+    cipher
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| tr!("crypto-wrong-passphrase"))
+}
+
+/// Payload encrypted with a random key kept elsewhere, e.g. in the secret store
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SealedBlob {
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+pub fn generate_key() -> [u8; KEY_LEN] {
+    let mut key = [0u8; KEY_LEN];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Encrypt with a raw key (XChaCha20-Poly1305); no key derivation, so it is cheap
+pub fn encrypt_with_key(plaintext: &[u8], key: &[u8; KEY_LEN]) -> Result<SealedBlob, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| tr!("crypto-encrypt-failed"))?;
+
+    Ok(SealedBlob {
+        cipher: CIPHER_NAME.to_string(),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// Decrypt a blob produced by `encrypt_with_key`
+pub fn decrypt_with_key(blob: &SealedBlob, key: &[u8; KEY_LEN]) -> Result<Vec<u8>, String> {
+    if blob.cipher != CIPHER_NAME {
+        return Err(tr!("crypto-unsupported-scheme", scheme = blob.cipher));
+    }
+    let nonce = BASE64
+        .decode(&blob.nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == 24)
+        .ok_or_else(|| tr!("crypto-corrupted", field = "nonce"))?;
+    let ciphertext = BASE64
+        .decode(&blob.ciphertext)
+        .map_err(|_| tr!("crypto-corrupted", field = "ciphertext"))?;
+
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| tr!("crypto-wrong-key"))
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let blob = encrypt_with_passphrase(b"top secret", "correct horse").unwrap();
+        assert_eq!(blob.kdf, "argon2id");
+
+        let plaintext = decrypt_with_passphrase(&blob, "correct horse").unwrap();
+        assert_eq!(plaintext, b"top secret");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_fails() {
+        let blob = encrypt_with_passphrase(b"top secret", "correct horse").unwrap();
+        assert!(decrypt_with_passphrase(&blob, "battery staple").is_err());
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails() {
+        let mut blob = encrypt_with_passphrase(b"top secret", "pass").unwrap();
+        let mut bytes = BASE64.decode(&blob.ciphertext).unwrap();
+        bytes[0] ^= 0xff;
+        blob.ciphertext = BASE64.encode(bytes);
+
+        assert!(decrypt_with_passphrase(&blob, "pass").is_err());
+    }
+
+    #[test]
+    fn test_empty_passphrase_rejected() {
+        assert!(encrypt_with_passphrase(b"data", "").is_err());
+    }
+
+    #[test]
+    fn test_excessive_kdf_params_rejected() {
+        let mut blob = encrypt_with_passphrase(b"data", "pass").unwrap();
+        blob.kdf_params.memory_kib = 4 * 1024 * 1024;
+        assert!(decrypt_with_passphrase(&blob, "pass").is_err());
+
+        let mut blob = encrypt_with_passphrase(b"data", "pass").unwrap();
+        blob.kdf_params.iterations = u32::MAX;
+        assert!(decrypt_with_passphrase(&blob, "pass").is_err());
+    }
+
+    #[test]
+    fn test_key_encryption_roundtrip() {
+        let key = generate_key();
+        let blob = encrypt_with_key(b"cookies", &key).unwrap();
+        assert_eq!(decrypt_with_key(&blob, &key).unwrap(), b"cookies");
+        assert!(decrypt_with_key(&blob, &generate_key()).is_err());
+    }
+
+    #[test]
+    fn test_each_encryption_uses_fresh_salt_and_nonce() {
+        let a = encrypt_with_passphrase(b"data", "pass").unwrap();
+        let b = encrypt_with_passphrase(b"data", "pass").unwrap();
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.nonce, b.nonce);
+    }
+}
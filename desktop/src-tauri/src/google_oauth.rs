@@ -0,0 +1,714 @@
+use crate::config::{self, AppConfig};
+use crate::credential_registry::CredentialSource;
+use crate::i18n::tr;
+use crate::secure_storage::{self, SecretStore};
+use crate::http_client;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chacha20poly1305::aead::{rand_core::RngCore, OsRng};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{info, warn};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Keychain entry holding the serialized `StoredToken`
+pub(crate) const TOKEN_KEY: &str = "google_oauth_token";
+
+const SCOPES: &[&str] = &[
+    "https://www.googleapis.com/auth/documents",
+    "https://www.googleapis.com/auth/drive",
+];
+
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Refresh this long before the access token actually expires
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+// ============= Client Credentials =============
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClientSecrets {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientSecretsFile {
+    installed: Option<ClientSecrets>,
+    web: Option<ClientSecrets>,
+}
+
+/// Parse a client credentials JSON downloaded from Google Cloud Console
+pub fn read_client_secrets(path: &Path) -> Result<ClientSecrets, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| tr!("google-credentials-read-failed", error = e))?;
+    let file: ClientSecretsFile = serde_json::from_str(&content)
+        .map_err(|e| tr!("google-credentials-malformed", error = e))?;
+    file.installed
+        .or(file.web)
+        .ok_or_else(|| tr!("google-credentials-missing-section"))
+}
+
+fn client_secrets_for(config: &AppConfig) -> Result<ClientSecrets, String> {
+    match config.auth.google_credentials_path {
+        Some(ref path) if !path.as_os_str().is_empty() => read_client_secrets(path),
+        _ => Err(tr!("google-credentials-not-configured")),
+    }
+}
+
+// ============= Token Storage =============
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StoredToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: DateTime<Utc>,
+    pub scope: Option<String>,
+}
+
+impl StoredToken {
+    fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - ChronoDuration::seconds(REFRESH_MARGIN_SECS) <= now
+    }
+}
+
+fn load_token() -> Option<StoredToken> {
+    load_token_from(&*secure_storage::active_store())
+}
+
+fn load_token_from(store: &dyn SecretStore) -> Option<StoredToken> {
+    let raw = store.get(TOKEN_KEY).ok()??;
+    match serde_json::from_str(&raw) {
+        Ok(token) => Some(token),
+        Err(e) => {
+            warn!("Stored Google token is unreadable: {}", e);
+            None
+        }
+    }
+}
+
+fn store_token(token: &StoredToken) -> Result<(), String> {
+    store_token_in(&*secure_storage::active_store(), token)
+}
+
+fn store_token_in(store: &dyn SecretStore, token: &StoredToken) -> Result<(), String> {
+    let raw = serde_json::to_string(token)
+        .map_err(|e| tr!("google-token-serialize-failed", error = e))?;
+    secure_storage::store_credential_in(store, TOKEN_KEY, &raw, CredentialSource::App)
+}
+
+/// Store the token and read it back, for callers that drop their own copy afterwards
+fn store_token_verified(token: &StoredToken) -> Result<(), String> {
+    store_token(token)?;
+    match load_token() {
+        Some(ref stored) if stored == token => Ok(()),
+        _ => Err(tr!("google-token-verify-failed")),
+    }
+}
+
+// ============= Token Endpoint =============
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+async fn request_token(
+    client: &reqwest::Client,
+    token_url: &str,
+    params: &[(&str, &str)],
+) -> Result<TokenResponse, String> {
+    let response = client
+        .post(token_url)
+        .form(params)
+        .send()
+        .await
+        .map_err(|e| tr!("google-connect-failed", error = e))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| tr!("google-response-read-failed", error = e))?;
+
+    if !status.is_success() {
+        let detail = serde_json::from_str::<TokenErrorResponse>(&body)
+            .map(|e| match e.error_description {
+                Some(description) => format!("{}: {}", e.error, description),
+                None => e.error,
+            })
+            .unwrap_or_else(|_| status.to_string());
+        return Err(tr!("google-auth-failed", detail = detail));
+    }
+
+    serde_json::from_str(&body).map_err(|e| tr!("google-response-malformed", error = e))
+}
+
+fn token_from_response(
+    response: TokenResponse,
+    previous_refresh_token: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<StoredToken, String> {
+    // Refresh responses usually omit the refresh token; keep the one we have
+    let refresh_token = response
+        .refresh_token
+        .or_else(|| previous_refresh_token.map(str::to_string))
+        .ok_or_else(|| tr!("google-no-refresh-token"))?;
+
+    Ok(StoredToken {
+        access_token: response.access_token,
+        refresh_token,
+        expires_at: now + ChronoDuration::seconds(response.expires_in.unwrap_or(3600)),
+        scope: response.scope,
+    })
+}
+
+async fn refresh_token(
+    client: &reqwest::Client,
+    token_url: &str,
+    secrets: &ClientSecrets,
+    token: &StoredToken,
+) -> Result<StoredToken, String> {
+    let params = [
+        ("client_id", secrets.client_id.as_str()),
+        (
+            "client_secret",
+            secrets.client_secret.as_deref().unwrap_or(""),
+        ),
+        ("grant_type", "refresh_token"),
+        ("refresh_token", token.refresh_token.as_str()),
+    ];
+    let response = request_token(client, token_url, &params).await?;
+    token_from_response(response, Some(&token.refresh_token), Utc::now())
+}
+
+// ============= Authorization Flow =============
+
+fn random_url_safe(bytes: usize) -> String {
+    let mut buffer = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buffer);
+    URL_SAFE_NO_PAD.encode(buffer)
+}
+
+/// PKCE verifier and its S256 challenge
+fn pkce_pair() -> (String, String) {
+    let verifier = random_url_safe(32);
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+fn consent_url(
+    auth_url: &str,
+    client_id: &str,
+    redirect_uri: &str,
+    challenge: &str,
+    state: &str,
+) -> Result<Url, String> {
+    let mut url = Url::parse(auth_url).map_err(|e| tr!("google-auth-url-invalid", error = e))?;
+    url.query_pairs_mut()
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", redirect_uri)
+        .append_pair("response_type", "code")
+        .append_pair("scope", &SCOPES.join(" "))
+        .append_pair("access_type", "offline")
+        .append_pair("prompt", "consent")
+        .append_pair("code_challenge", challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("state", state);
+    Ok(url)
+}
+
+/// Open a URL in the user's default browser
+fn open_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("rundll32")
+        .args(["url.dll,FileProtocolHandler", url])
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(url).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = std::process::Command::new("xdg-open").arg(url).spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| tr!("google-open-browser-failed", error = e))
+}
+
+fn callback_page(success: bool) -> String {
+    let (title, body) = if success {
+        (
+            tr!("google-callback-success-title"),
+            tr!("google-callback-success-body"),
+        )
+    } else {
+        (
+            tr!("google-callback-failure-title"),
+            tr!("google-callback-failure-body"),
+        )
+    };
+    format!(
+        "<html><body><h2>{}</h2><p>{}</p></body></html>",
+        title, body
+    )
+}
+
+async fn respond(stream: &mut tokio::net::TcpStream, body: &str) {
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Wait on the loopback listener for Google's redirect and return the authorization code.
+/// Requests that don't carry our `state` (favicon, stray probes) are ignored.
+async fn wait_for_code(listener: &TcpListener, expected_state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| tr!("google-callback-wait-failed", error = e))?;
+
+        let mut buffer = [0u8; 8192];
+        let n = stream.read(&mut buffer).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buffer[..n]);
+
+        // Request line: GET /?code=...&state=... HTTP/1.1
+        let target = request.split_whitespace().nth(1).unwrap_or("/");
+        let url = match Url::parse(&format!("http://127.0.0.1{}", target)) {
+            Ok(url) => url,
+            Err(_) => continue,
+        };
+
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        if param("state").as_deref() != Some(expected_state) {
+            respond(&mut stream, &callback_page(false)).await;
+            continue;
+        }
+
+        if let Some(error) = param("error") {
+            respond(&mut stream, &callback_page(false)).await;
+            return Err(tr!("google-consent-denied", error = error));
+        }
+
+        match param("code") {
+            Some(code) => {
+                respond(&mut stream, &callback_page(true)).await;
+                return Ok(code);
+            }
+            None => {
+                respond(&mut stream, &callback_page(false)).await;
+                return Err(tr!("google-callback-missing-code"));
+            }
+        }
+    }
+}
+
+async fn authorize(
+    config: &AppConfig,
+    open: impl FnOnce(&str) -> Result<(), String>,
+) -> Result<StoredToken, String> {
+    let secrets = client_secrets_for(config)?;
+    let endpoints = &config.advanced.endpoints;
+
+    // Loopback redirect on an ephemeral port, as Google recommends for desktop apps
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| tr!("google-callback-server-failed", error = e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| tr!("google-callback-server-failed", error = e))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}", port);
+
+    let (verifier, challenge) = pkce_pair();
+    let state = random_url_safe(16);
+    let url = consent_url(
+        &endpoints.google_auth_url,
+        &secrets.client_id,
+        &redirect_uri,
+        &challenge,
+        &state,
+    )?;
+
+    info!(
+        "Opening Google consent page, waiting for callback on port {}",
+        port
+    );
+    open(url.as_str())?;
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| tr!("google-auth-timeout"))??;
+
+    let client = http_client::build_client(&config.advanced, TOKEN_REQUEST_TIMEOUT)?;
+    let params = [
+        ("client_id", secrets.client_id.as_str()),
+        (
+            "client_secret",
+            secrets.client_secret.as_deref().unwrap_or(""),
+        ),
+        ("code", code.as_str()),
+        ("code_verifier", verifier.as_str()),
+        ("grant_type", "authorization_code"),
+        ("redirect_uri", redirect_uri.as_str()),
+    ];
+    let response = request_token(&client, &endpoints.google_token_url, &params).await?;
+    token_from_response(response, None, Utc::now())
+}
+
+/// A valid access token, refreshed and re-stored when it is about to expire
+pub async fn access_token(config: &AppConfig) -> Result<String, String> {
+    access_token_in(secure_storage::active_store(), config).await
+}
+
+/// Like `access_token`, with the token kept in `store`
+pub async fn access_token_in(
+    store: Arc<dyn SecretStore>,
+    config: &AppConfig,
+) -> Result<String, String> {
+    let token = load_token_from(&*store).ok_or_else(|| tr!("google-not-authorized"))?;
+    if !token.needs_refresh(Utc::now()) {
+        return Ok(token.access_token);
+    }
+
+    let secrets = client_secrets_for(config)?;
+    let client = http_client::build_client(&config.advanced, TOKEN_REQUEST_TIMEOUT)?;
+    let refreshed = refresh_token(
+        &client,
+        &config.advanced.endpoints.google_token_url,
+        &secrets,
+        &token,
+    )
+    .await?;
+    store_token_in(&*store, &refreshed)?;
+    info!("Google access token refreshed");
+    Ok(refreshed.access_token)
+}
+
+// ============= Token File Migration =============
+
+/// Token file as written by googleapis (Node) or google-auth (Python)
+#[derive(Debug, Deserialize)]
+struct LegacyTokenFile {
+    #[serde(alias = "token")]
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    /// googleapis: milliseconds since the epoch
+    expiry_date: Option<i64>,
+    /// google-auth: RFC 3339 timestamp
+    expiry: Option<String>,
+    scope: Option<String>,
+}
+
+fn token_from_file(content: &str) -> Result<StoredToken, String> {
+    let file: LegacyTokenFile =
+        serde_json::from_str(content).map_err(|e| tr!("google-token-file-malformed", error = e))?;
+    let refresh_token = file
+        .refresh_token
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| tr!("google-token-file-no-refresh"))?;
+
+    // Unknown expiry means "refresh on first use"
+    let expires_at = file
+        .expiry_date
+        .and_then(DateTime::from_timestamp_millis)
+        .or_else(|| {
+            file.expiry
+                .as_deref()
+                .and_then(|e| DateTime::parse_from_rfc3339(e).ok())
+                .map(|e| e.with_timezone(&Utc))
+        })
+        .unwrap_or(DateTime::UNIX_EPOCH);
+
+    Ok(StoredToken {
+        access_token: file.access_token.unwrap_or_default(),
+        refresh_token,
+        expires_at,
+        scope: file.scope,
+    })
+}
+
+/// Move a plaintext token file into the keychain, clear `google_token_path`
+/// and delete the file. Returns true when a file was migrated; a missing file
+/// leaves the config as it is.
+pub fn migrate_token_file() -> Result<bool, String> {
+    let mut settings = config::load_settings();
+    let path = match settings.auth.google_token_path {
+        Some(ref path) if !path.as_os_str().is_empty() && path.exists() => path.clone(),
+        _ => return Ok(false),
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| tr!("google-token-file-read-failed", error = e))?;
+    let token = token_from_file(&content)?;
+    store_token_verified(&token)?;
+
+    settings.auth.google_token_path = None;
+    config::save_config(settings)?;
+
+    // Only delete once the keychain holds the token and the config no longer needs the file
+    std::fs::remove_file(&path).map_err(|e| tr!("google-token-file-delete-failed", error = e))?;
+    info!("Google token migrated from {} to keychain", path.display());
+    Ok(true)
+}
+
+// ============= Tauri Commands =============
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GoogleAuthStatus {
+    pub authorized: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub scope: Option<String>,
+}
+
+fn status_of(token: Option<StoredToken>) -> GoogleAuthStatus {
+    GoogleAuthStatus {
+        authorized: token.is_some(),
+        expires_at: token.as_ref().map(|t| t.expires_at),
+        scope: token.and_then(|t| t.scope),
+    }
+}
+
+/// Run the installed-app consent flow in the user's browser and store the token.
+/// Accepts unsaved settings so a freshly selected credentials file can be used.
+#[tauri::command]
+pub async fn authorize_google(config: Option<AppConfig>) -> Result<GoogleAuthStatus, String> {
+    let config = config.unwrap_or_else(config::load_settings);
+    let token = authorize(&config, open_browser).await?;
+    store_token(&token)?;
+    info!("Google account authorized");
+    Ok(status_of(Some(token)))
+}
+
+#[tauri::command]
+pub fn get_google_auth_status() -> GoogleAuthStatus {
+    status_of(load_token())
+}
+
+#[tauri::command]
+pub fn revoke_google_authorization() -> Result<(), String> {
+    secure_storage::delete_credential(TOKEN_KEY)?;
+    info!("Google authorization removed");
+    Ok(())
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::test_support::stub_server;
+
+    fn write_secrets(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-oauth-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("credentials.json");
+        std::fs::write(
+            &path,
+            r#"{"installed":{"client_id":"client-123","client_secret":"shh","redirect_uris":["http://localhost"]}}"#,
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_client_secrets() {
+        let path = write_secrets("secrets");
+        let secrets = read_client_secrets(&path).unwrap();
+        assert_eq!(secrets.client_id, "client-123");
+        assert_eq!(secrets.client_secret.as_deref(), Some("shh"));
+
+        std::fs::write(&path, r#"{"other":{}}"#).unwrap();
+        assert!(read_client_secrets(&path).is_err());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_pkce_challenge_is_s256_of_verifier() {
+        let (verifier, challenge) = pkce_pair();
+        assert_eq!(verifier.len(), 43);
+        assert_eq!(
+            challenge,
+            URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+        );
+        assert_ne!(pkce_pair().0, verifier);
+    }
+
+    #[test]
+    fn test_consent_url_parameters() {
+        let url = consent_url(
+            "https://accounts.example.com/auth",
+            "client-123",
+            "http://127.0.0.1:5555",
+            "challenge",
+            "state-1",
+        )
+        .unwrap();
+        let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["client_id"], "client-123");
+        assert_eq!(query["redirect_uri"], "http://127.0.0.1:5555");
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["access_type"], "offline");
+        assert_eq!(query["state"], "state-1");
+    }
+
+    #[test]
+    fn test_needs_refresh() {
+        let now = Utc::now();
+        let mut token = StoredToken {
+            access_token: "a".to_string(),
+            refresh_token: "r".to_string(),
+            expires_at: now + ChronoDuration::seconds(30),
+            scope: None,
+        };
+        assert!(token.needs_refresh(now));
+        token.expires_at = now + ChronoDuration::hours(1);
+        assert!(!token.needs_refresh(now));
+    }
+
+    #[test]
+    fn test_token_from_node_and_python_files() {
+        let node = token_from_file(
+            r#"{"access_token":"ya29","refresh_token":"1//r","expiry_date":1700000000000,"scope":"docs"}"#,
+        )
+        .unwrap();
+        assert_eq!(node.refresh_token, "1//r");
+        assert_eq!(node.expires_at.timestamp(), 1_700_000_000);
+
+        let python = token_from_file(
+            r#"{"token":"ya29","refresh_token":"1//p","expiry":"2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(python.access_token, "ya29");
+        assert_eq!(python.expires_at.timestamp(), 1_704_067_200);
+
+        assert!(token_from_file(r#"{"access_token":"ya29"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_existing_refresh_token() {
+        let (base, requests) = stub_server(
+            "200 OK",
+            r#"{"access_token":"new-access","expires_in":3599,"token_type":"Bearer"}"#,
+        );
+        let client = reqwest::Client::new();
+        let secrets = ClientSecrets {
+            client_id: "client-123".to_string(),
+            client_secret: Some("shh".to_string()),
+        };
+        let token = StoredToken {
+            access_token: "old".to_string(),
+            refresh_token: "1//keep".to_string(),
+            expires_at: Utc::now(),
+            scope: None,
+        };
+
+        let refreshed = refresh_token(&client, &format!("{}/token", base), &secrets, &token)
+            .await
+            .unwrap();
+        assert_eq!(refreshed.access_token, "new-access");
+        assert_eq!(refreshed.refresh_token, "1//keep");
+        assert!(!refreshed.needs_refresh(Utc::now()));
+
+        let request = requests.recv().unwrap();
+        assert!(request.contains("grant_type=refresh_token"));
+        assert!(request.contains("refresh_token=1%2F%2Fkeep"));
+    }
+
+    #[tokio::test]
+    async fn test_token_error_is_reported() {
+        let (base, _requests) = stub_server(
+            "400 Bad Request",
+            r#"{"error":"invalid_grant","error_description":"Token has been expired or revoked."}"#,
+        );
+        let result = request_token(&reqwest::Client::new(), &base, &[("grant_type", "x")]).await;
+        assert!(result.unwrap_err().contains("invalid_grant"));
+    }
+
+    #[tokio::test]
+    async fn test_full_authorization_flow_with_loopback_callback() {
+        let (token_base, token_requests) = stub_server(
+            "200 OK",
+            r#"{"access_token":"ya29.fresh","refresh_token":"1//fresh","expires_in":3600}"#,
+        );
+        let mut config = AppConfig::default();
+        config.auth.google_credentials_path = Some(write_secrets("flow"));
+        config.advanced.endpoints.google_auth_url = "https://accounts.example.com/auth".to_string();
+        config.advanced.endpoints.google_token_url = format!("{}/token", token_base);
+
+        // Stand-in for the browser: follow the consent URL straight to the redirect
+        let token = authorize(&config, |url| {
+            let url = Url::parse(url).unwrap();
+            let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+            let callback = format!(
+                "{}/?code=auth-code&state={}",
+                query["redirect_uri"], query["state"]
+            );
+            tokio::spawn(async move {
+                let stray = callback.replace("state=", "state=wrong");
+                let _ = reqwest::get(&stray).await;
+                reqwest::get(&callback).await.unwrap();
+            });
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(token.access_token, "ya29.fresh");
+        assert_eq!(token.refresh_token, "1//fresh");
+
+        let request = token_requests.recv().unwrap();
+        assert!(request.contains("code=auth-code"));
+        assert!(request.contains("code_verifier="));
+        assert!(request.contains("grant_type=authorization_code"));
+
+        let _ = std::fs::remove_dir_all(
+            config
+                .auth
+                .google_credentials_path
+                .unwrap()
+                .parent()
+                .unwrap(),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_denied_consent_is_an_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let _ = reqwest::get(format!(
+                "http://127.0.0.1:{}/?error=access_denied&state=s",
+                port
+            ))
+            .await;
+        });
+
+        let result = wait_for_code(&listener, "s").await;
+        assert!(result.unwrap_err().contains("access_denied"));
+    }
+}
@@ -0,0 +1,256 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use log::{info, warn};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Interface languages with a message catalog. Other codes fall back to `ZhTw`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    ZhTw,
+    En,
+}
+
+impl Language {
+    const ALL: [Language; 2] = [Language::ZhTw, Language::En];
+
+    /// Map a `BasicSettings::language` code onto a catalog
+    pub fn from_code(code: &str) -> Self {
+        if code.to_ascii_lowercase().starts_with("en") {
+            Language::En
+        } else {
+            Language::ZhTw
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            Language::ZhTw => "zh-TW",
+            Language::En => "en",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::ZhTw => include_str!("../locales/zh-TW/main.ftl"),
+            Language::En => include_str!("../locales/en/main.ftl"),
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Language::ZhTw => 0,
+            Language::En => 1,
+        }
+    }
+}
+
+type Bundle = FluentBundle<FluentResource>;
+type ChangeListener = Box<dyn Fn(Language) + Send + Sync>;
+
+static BUNDLES: OnceLock<Vec<Bundle>> = OnceLock::new();
+#[cfg(not(test))]
+static CURRENT: std::sync::RwLock<Language> = std::sync::RwLock::new(Language::ZhTw);
+static LISTENER: OnceLock<ChangeListener> = OnceLock::new();
+
+// ============= Catalogs =============
+
+fn build_bundle(language: Language) -> Bundle {
+    let langid: LanguageIdentifier = language.tag().parse().expect("valid language tag");
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // Messages end up in plain dialogs and error strings, not bidi-aware UI
+    bundle.set_use_isolating(false);
+
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            warn!(
+                "Message catalog {} has errors: {:?}",
+                language.tag(),
+                errors
+            );
+            resource
+        },
+    );
+    if let Err(errors) = bundle.add_resource(resource) {
+        warn!(
+            "Duplicate messages in catalog {}: {:?}",
+            language.tag(),
+            errors
+        );
+    }
+    bundle
+}
+
+fn bundles() -> &'static [Bundle] {
+    BUNDLES.get_or_init(|| Language::ALL.iter().map(|&l| build_bundle(l)).collect())
+}
+
+fn format(language: Language, id: &str, args: &[(&str, String)]) -> Option<String> {
+    let bundle = &bundles()[language.index()];
+    let pattern = bundle.get_message(id)?.value()?;
+
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+    if !errors.is_empty() {
+        warn!("Formatting message '{}' failed: {:?}", id, errors);
+    }
+    Some(text.into_owned())
+}
+
+/// Message `id` in `language`, falling back to zh-TW and finally to the id itself
+pub fn message_in(language: Language, id: &str, args: &[(&str, String)]) -> String {
+    format(language, id, args)
+        .or_else(|| format(Language::ZhTw, id, args))
+        .unwrap_or_else(|| {
+            warn!("Missing message '{}'", id);
+            id.to_string()
+        })
+}
+
+/// Message `id` in the current interface language
+pub fn message(id: &str, args: &[(&str, String)]) -> String {
+    message_in(current(), id, args)
+}
+
+/// Localized message: `tr!("id")` or `tr!("id", name = value, ...)`, where each
+/// value is anything `Display`
+macro_rules! tr {
+    ($id:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::message($id, &[$((stringify!($name), $value.to_string())),*])
+    };
+}
+pub(crate) use tr;
+
+// ============= Current Language =============
+
+#[cfg(not(test))]
+pub fn current() -> Language {
+    *CURRENT.read().unwrap()
+}
+
+/// Store `language`, returning the previous one
+#[cfg(not(test))]
+fn replace_current(language: Language) -> Language {
+    std::mem::replace(&mut *CURRENT.write().unwrap(), language)
+}
+
+// Tests run in parallel, so each test thread keeps its own language; one test
+// switching it cannot change the messages another test asserts on
+#[cfg(test)]
+thread_local! {
+    static CURRENT: std::cell::Cell<Language> = const { std::cell::Cell::new(Language::ZhTw) };
+}
+
+#[cfg(test)]
+pub fn current() -> Language {
+    CURRENT.with(|current| current.get())
+}
+
+#[cfg(test)]
+fn replace_current(language: Language) -> Language {
+    CURRENT.with(|current| current.replace(language))
+}
+
+/// Switch the interface language, notifying the listener when it actually changes
+pub fn set_language(code: &str) {
+    let language = Language::from_code(code);
+    if replace_current(language) == language {
+        return;
+    }
+
+    info!("Interface language set to {}", language.tag());
+    if let Some(listener) = LISTENER.get() {
+        listener(language);
+    }
+}
+
+/// Register the callback that refreshes native UI (tray menu) after a language change
+pub fn on_language_changed(listener: impl Fn(Language) + Send + Sync + 'static) {
+    if LISTENER.set(Box::new(listener)).is_err() {
+        warn!("Language change listener already registered");
+    }
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn message_ids(language: Language) -> BTreeSet<String> {
+        if let Err((_, errors)) = FluentResource::try_new(language.source().to_string()) {
+            panic!("{} catalog has syntax errors: {:?}", language.tag(), errors);
+        }
+        // Message definitions are the only unindented lines that are not comments
+        language
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_catalogs_define_same_messages() {
+        let zh = message_ids(Language::ZhTw);
+        let en = message_ids(Language::En);
+        assert!(!zh.is_empty());
+        assert_eq!(
+            zh.symmetric_difference(&en).collect::<Vec<_>>(),
+            Vec::<&String>::new()
+        );
+    }
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("zh-TW"), Language::ZhTw);
+        assert_eq!(Language::from_code("en"), Language::En);
+        assert_eq!(Language::from_code("en-US"), Language::En);
+        // No Simplified Chinese catalog yet
+        assert_eq!(Language::from_code("zh-CN"), Language::ZhTw);
+    }
+
+    #[test]
+    fn test_message_with_arguments() {
+        let args = [("error", "disk full".to_string())];
+        assert_eq!(
+            message_in(Language::En, "config-save-failed", &args),
+            "Failed to save settings: disk full"
+        );
+        assert_eq!(
+            message_in(Language::ZhTw, "config-save-failed", &args),
+            "保存配置失敗: disk full"
+        );
+    }
+
+    #[test]
+    fn test_multiline_message_keeps_line_breaks() {
+        let args = [("version", "2.0.0".to_string())];
+        let text = message_in(Language::En, "tray-about-message", &args);
+        assert!(text.starts_with("AutoDoc Agent v2.0.0\n"));
+        assert!(text.contains("\n\n"));
+    }
+
+    #[test]
+    fn test_language_switch_stays_on_test_thread() {
+        set_language("en");
+        assert_eq!(tr!("sidecar-not-running"), "Backend is not running");
+
+        let other = std::thread::spawn(current).join().unwrap();
+        assert_eq!(other, Language::ZhTw);
+    }
+
+    #[test]
+    fn test_unknown_message_falls_back_to_id() {
+        assert_eq!(
+            message_in(Language::En, "no-such-message", &[]),
+            "no-such-message"
+        );
+    }
+}
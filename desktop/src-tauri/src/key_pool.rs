@@ -0,0 +1,539 @@
+use crate::api_gateway::{self, TokenBucket};
+use crate::config::{AppConfig, AuthSettings};
+use crate::i18n::tr;
+use crate::llm_provider::{self, LlmProvider};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Keychain key prefix for pooled Claude keys, e.g. `claude_api_key:team-a`
+const SECRET_KEY_PREFIX: &str = "claude_api_key:";
+const MAX_KEY_ID_LEN: usize = 64;
+/// How long a key rejected by upstream is skipped; storing the key again ends it early
+const AUTH_FAILURE_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// One named Claude API key of the pool
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClaudeKey {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Requests per minute this key may send
+    pub rate_limit: u32,
+    /// Higher priority keys are used first; equal priorities share the load
+    #[serde(default)]
+    pub priority: u32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Note: stored in OS keychain under `secret_key(id)`
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// ============= Keys & IDs =============
+
+/// Keychain key holding pooled key `id`
+pub fn secret_key(id: &str) -> String {
+    format!("{}{}", SECRET_KEY_PREFIX, id)
+}
+
+/// Pool key ID encoded in a keychain key, if it is a pooled key
+pub fn key_id_from_key(key: &str) -> Option<&str> {
+    key.strip_prefix(SECRET_KEY_PREFIX)
+        .filter(|id| is_valid_id(id))
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_KEY_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Give every key without an ID the first free `key-N`
+pub fn assign_ids(keys: &mut [ClaudeKey]) {
+    let mut taken: Vec<String> = keys
+        .iter()
+        .filter(|k| !k.id.is_empty())
+        .map(|k| k.id.clone())
+        .collect();
+
+    for key in keys.iter_mut().filter(|k| k.id.is_empty()) {
+        let id = (1..)
+            .map(|n| format!("key-{}", n))
+            .find(|id| !taken.contains(id))
+            .unwrap_or_default();
+        taken.push(id.clone());
+        key.id = id;
+    }
+}
+
+/// Validate the pool; returns (dotted field, message) pairs
+pub fn validate_keys(keys: &[ClaudeKey]) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+
+    for (index, key) in keys.iter().enumerate() {
+        let field = |name: &str| format!("auth.claude_keys[{}].{}", index, name);
+
+        if !key.id.is_empty() {
+            if !is_valid_id(&key.id) {
+                problems.push((field("id"), tr!("key-pool-id-invalid", id = key.id.clone())));
+            } else if seen.contains(&key.id.as_str()) {
+                problems.push((
+                    field("id"),
+                    tr!("key-pool-id-duplicate", id = key.id.clone()),
+                ));
+            }
+            seen.push(&key.id);
+        }
+        if key.name.trim().is_empty() {
+            problems.push((field("name"), tr!("key-pool-name-empty")));
+        }
+        if key.rate_limit == 0 {
+            problems.push((field("rate_limit"), tr!("key-pool-rate-limit-invalid")));
+        }
+        if let Some(secret) = key.secret.as_deref().filter(|s| !s.is_empty()) {
+            if let Err(e) = llm_provider::validate_key(LlmProvider::Anthropic, secret) {
+                problems.push((field("secret"), e));
+            }
+        }
+    }
+
+    problems
+}
+
+pub fn find_key<'a>(auth: &'a AuthSettings, id: &str) -> Option<&'a ClaudeKey> {
+    auth.claude_keys.iter().find(|k| k.id == id)
+}
+
+// ============= Runtime Pool =============
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Health {
+    Healthy,
+    CoolingDown(Instant),
+    /// Rejected by upstream; skipped until then, without waiting for it
+    Invalid(Instant),
+}
+
+/// What happened to a request sent with a key
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyOutcome {
+    Success,
+    /// 429; the key is skipped for the given time
+    RateLimited(Duration),
+    /// 401 or 403; the key is skipped for `AUTH_FAILURE_COOLDOWN` or until stored again
+    AuthFailed(String),
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum KeyHealth {
+    Healthy,
+    CoolingDown { until: DateTime<Utc> },
+    Invalid { until: DateTime<Utc> },
+}
+
+/// Health and usage of a key since the app started, for the UI
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct KeyStatus {
+    pub id: String,
+    pub name: String,
+    pub priority: u32,
+    pub rate_limit: u32,
+    pub health: KeyHealth,
+    pub requests: u64,
+    pub rate_limited: u64,
+    pub auth_failures: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub last_used: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+struct Member {
+    id: String,
+    name: String,
+    /// Keychain entry the key is read from on every request
+    secret_key: String,
+    priority: u32,
+    rate_limit: u32,
+    bucket: TokenBucket,
+    health: Health,
+    /// Lease sequence number of the last use, 0 when never used
+    last_lease: u64,
+    status: KeyStatus,
+}
+
+impl Member {
+    fn new(id: &str, name: &str, secret_key: String, priority: u32, rate_limit: u32) -> Self {
+        Member {
+            id: id.to_string(),
+            name: name.to_string(),
+            secret_key,
+            priority,
+            rate_limit,
+            bucket: TokenBucket::new(rate_limit, Instant::now()),
+            health: Health::Healthy,
+            last_lease: 0,
+            status: KeyStatus {
+                id: id.to_string(),
+                name: name.to_string(),
+                priority,
+                rate_limit,
+                health: KeyHealth::Healthy,
+                requests: 0,
+                rate_limited: 0,
+                auth_failures: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                last_used: None,
+                last_error: None,
+            },
+        }
+    }
+
+    fn available_at(&self, now: Instant) -> Option<Instant> {
+        match self.health {
+            Health::Invalid(until) if until > now => None,
+            Health::CoolingDown(until) if until > now => Some(until),
+            _ => Some(now),
+        }
+    }
+}
+
+/// A key picked for one request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lease {
+    pub id: String,
+    pub secret_key: String,
+}
+
+/// Keys of the selected provider. Without pooled keys this is the provider's single key.
+pub struct KeyPool {
+    members: Vec<Member>,
+    /// `advanced.api_rate_limit`, shared by all keys on top of their own limits
+    overall: TokenBucket,
+    /// Number of leases handed out, orders members by last use
+    leases: u64,
+}
+
+impl KeyPool {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let provider = config.auth.llm_provider;
+        let pooled: Vec<&ClaudeKey> = config
+            .auth
+            .claude_keys
+            .iter()
+            .filter(|k| k.enabled)
+            .collect();
+
+        let members = if provider == LlmProvider::Anthropic && !pooled.is_empty() {
+            pooled
+                .into_iter()
+                .map(|k| Member::new(&k.id, &k.name, secret_key(&k.id), k.priority, k.rate_limit))
+                .collect()
+        } else {
+            vec![Member::new(
+                "default",
+                provider.name(),
+                provider.secret_key().to_string(),
+                0,
+                config.advanced.api_rate_limit,
+            )]
+        };
+        KeyPool {
+            members,
+            overall: TokenBucket::new(config.advanced.api_rate_limit, Instant::now()),
+            leases: 0,
+        }
+    }
+
+    /// Rebuild from new settings, keeping the statistics of keys that still exist.
+    /// Health is reset since a saved key may have been replaced.
+    pub fn reconfigure(&mut self, config: &AppConfig) {
+        let now = Instant::now();
+        let mut rebuilt = KeyPool::from_config(config);
+        for member in &mut rebuilt.members {
+            if let Some(previous) = self.members.iter_mut().find(|m| m.id == member.id) {
+                // Saving settings must not hand out a fresh burst
+                std::mem::swap(&mut member.bucket, &mut previous.bucket);
+                member.bucket.set_rate(member.rate_limit, now);
+                member.last_lease = previous.last_lease;
+                member.status = KeyStatus {
+                    name: member.name.clone(),
+                    priority: member.priority,
+                    rate_limit: member.rate_limit,
+                    health: KeyHealth::Healthy,
+                    ..previous.status.clone()
+                };
+            }
+        }
+        std::mem::swap(&mut rebuilt.overall, &mut self.overall);
+        rebuilt
+            .overall
+            .set_rate(config.advanced.api_rate_limit, now);
+        rebuilt.leases = self.leases;
+        *self = rebuilt;
+    }
+
+    /// Pick a key other than those in `skip`, or report how long until one may be ready;
+    /// `None` when no such key is usable at all
+    pub fn lease(&mut self, now: Instant, skip: &[String]) -> Result<Lease, Option<Duration>> {
+        let mut order: Vec<usize> = (0..self.members.len())
+            .filter(|&i| !skip.contains(&self.members[i].id))
+            .collect();
+        // Highest priority first, least recently used first within a priority
+        order.sort_by_key(|&i| {
+            let member = &self.members[i];
+            (std::cmp::Reverse(member.priority), member.last_lease)
+        });
+
+        let mut wait: Option<Duration> = None;
+        for i in order {
+            let member = &mut self.members[i];
+            let Some(available_at) = member.available_at(now) else {
+                continue;
+            };
+            let delay = if available_at > now {
+                available_at - now
+            } else if let Err(delay) = self.overall.check(now) {
+                delay
+            } else {
+                match member.bucket.try_take(now) {
+                    Ok(()) => {
+                        let _ = self.overall.try_take(now);
+                        self.leases += 1;
+                        member.last_lease = self.leases;
+                        return Ok(Lease {
+                            id: member.id.clone(),
+                            secret_key: member.secret_key.clone(),
+                        });
+                    }
+                    Err(delay) => delay,
+                }
+            };
+            wait = Some(wait.map_or(delay, |w| w.min(delay)));
+        }
+        Err(wait)
+    }
+
+    /// Whether a usable key outside `tried` could take over
+    pub fn has_untried(&self, tried: &[String], now: Instant) -> bool {
+        self.members
+            .iter()
+            .any(|m| !tried.contains(&m.id) && m.available_at(now).is_some())
+    }
+
+    pub fn report(&mut self, id: &str, outcome: KeyOutcome, now: Instant) {
+        let Some(member) = self.members.iter_mut().find(|m| m.id == id) else {
+            return;
+        };
+        member.status.requests += 1;
+        member.status.last_used = Some(Utc::now());
+        match outcome {
+            KeyOutcome::Success => member.health = Health::Healthy,
+            KeyOutcome::RateLimited(cooldown) => {
+                member.status.rate_limited += 1;
+                member.health = Health::CoolingDown(now + cooldown);
+            }
+            KeyOutcome::AuthFailed(error) => {
+                member.status.auth_failures += 1;
+                member.status.last_error = Some(error);
+                member.health = Health::Invalid(now + AUTH_FAILURE_COOLDOWN);
+            }
+        }
+    }
+
+    /// The key stored under `secret_key` was replaced; give it another chance right away
+    pub fn reset_health(&mut self, secret_key: &str) {
+        for member in self.members.iter_mut().filter(|m| m.secret_key == secret_key) {
+            member.health = Health::Healthy;
+        }
+    }
+
+    pub fn add_usage(&mut self, id: &str, input_tokens: u64, output_tokens: u64) {
+        if let Some(member) = self.members.iter_mut().find(|m| m.id == id) {
+            member.status.input_tokens += input_tokens;
+            member.status.output_tokens += output_tokens;
+        }
+    }
+
+    pub fn statuses(&self, now: Instant) -> Vec<KeyStatus> {
+        self.members
+            .iter()
+            .map(|member| KeyStatus {
+                health: match member.health {
+                    Health::Invalid(until) if until > now => KeyHealth::Invalid {
+                        until: wall_clock(until, now),
+                    },
+                    Health::CoolingDown(until) if until > now => KeyHealth::CoolingDown {
+                        until: wall_clock(until, now),
+                    },
+                    _ => KeyHealth::Healthy,
+                },
+                ..member.status.clone()
+            })
+            .collect()
+    }
+}
+
+fn wall_clock(until: Instant, now: Instant) -> DateTime<Utc> {
+    Utc::now() + chrono::Duration::from_std(until - now).unwrap_or_default()
+}
+
+// ============= Tauri Commands =============
+
+/// Health and usage of the keys the gateway is using
+#[tauri::command]
+pub fn get_claude_key_pool_status() -> Vec<KeyStatus> {
+    api_gateway::key_pool_status()
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: &str, priority: u32, rate_limit: u32) -> ClaudeKey {
+        ClaudeKey {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            rate_limit,
+            priority,
+            enabled: true,
+            secret: None,
+        }
+    }
+
+    fn pool(keys: Vec<ClaudeKey>) -> KeyPool {
+        let mut config = AppConfig::default();
+        config.auth.claude_keys = keys;
+        KeyPool::from_config(&config)
+    }
+
+    #[test]
+    fn test_single_key_without_pool() {
+        let mut config = AppConfig::default();
+        config.auth.claude_keys = vec![ClaudeKey {
+            enabled: false,
+            ..key("off", 0, 10)
+        }];
+        let mut pool = KeyPool::from_config(&config);
+        assert_eq!(pool.statuses(Instant::now()).len(), 1);
+        assert_eq!(
+            pool.lease(Instant::now(), &[]).unwrap().secret_key,
+            "claude_api_key"
+        );
+    }
+
+    #[test]
+    fn test_lease_prefers_priority_and_spreads_load() {
+        let mut pool = pool(vec![key("low", 0, 60), key("a", 5, 60), key("b", 5, 60)]);
+        let now = Instant::now();
+
+        let leased: Vec<String> = (0..4).map(|_| pool.lease(now, &[]).unwrap().id).collect();
+        assert_eq!(leased, vec!["a", "b", "a", "b"]);
+        assert_eq!(pool.lease(now, &[]).unwrap().secret_key, "claude_api_key:a");
+
+        // Rate limited keys cool down and the others take over
+        pool.report("a", KeyOutcome::RateLimited(Duration::from_secs(5)), now);
+        pool.report("b", KeyOutcome::AuthFailed("401".to_string()), now);
+        assert_eq!(pool.lease(now, &[]).unwrap().id, "low");
+        assert!(pool.has_untried(&["low".to_string()], now));
+        assert!(!pool.has_untried(&["low".to_string(), "a".to_string()], now));
+        assert_eq!(
+            pool.lease(now, &["low".to_string()]),
+            Err(Some(Duration::from_secs(5)))
+        );
+        assert_eq!(
+            pool.lease(now + Duration::from_secs(6), &[]).unwrap().id,
+            "a"
+        );
+
+        let statuses = pool.statuses(now);
+        let b = statuses.iter().find(|s| s.id == "b").unwrap();
+        assert!(matches!(b.health, KeyHealth::Invalid { .. }));
+        assert_eq!(b.auth_failures, 1);
+        assert!(matches!(
+            statuses.iter().find(|s| s.id == "a").unwrap().health,
+            KeyHealth::CoolingDown { .. }
+        ));
+    }
+
+    #[test]
+    fn test_lease_waits_for_per_key_rate_limit() {
+        let mut pool = pool(vec![key("a", 0, 1), key("b", 0, 1)]);
+        let now = Instant::now();
+        pool.lease(now, &[]).unwrap();
+        pool.lease(now, &[]).unwrap();
+
+        let wait = pool.lease(now, &[]).unwrap_err().unwrap();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(60));
+
+        pool.report("a", KeyOutcome::AuthFailed("401".to_string()), now);
+        pool.report("b", KeyOutcome::AuthFailed("401".to_string()), now);
+        assert_eq!(pool.lease(now, &[]), Err(None));
+        assert!(!pool.has_untried(&[], now));
+
+        let mut keys = vec![
+            ClaudeKey {
+                id: String::new(),
+                ..key("new", 0, 1)
+            },
+            key("key-1", 0, 0),
+        ];
+        assign_ids(&mut keys);
+        assert_eq!(keys[0].id, "key-2");
+        let fields: Vec<_> = validate_keys(&keys).into_iter().map(|(f, _)| f).collect();
+        assert_eq!(fields, vec!["auth.claude_keys[1].rate_limit"]);
+    }
+
+    #[test]
+    fn test_rejected_key_recovers_after_cooldown_or_when_stored_again() {
+        let mut pool = pool(Vec::new());
+        let now = Instant::now();
+        let lease = pool.lease(now, &[]).unwrap();
+        pool.report(&lease.id, KeyOutcome::AuthFailed("403".to_string()), now);
+
+        // The only key is skipped rather than waited for
+        assert_eq!(pool.lease(now, &[]), Err(None));
+        let later = now + AUTH_FAILURE_COOLDOWN + Duration::from_secs(1);
+        assert!(pool.has_untried(&[], later));
+        assert_eq!(pool.lease(later, &[]).unwrap().id, "default");
+
+        pool.report(&lease.id, KeyOutcome::AuthFailed("401".to_string()), later);
+        pool.reset_health("claude_api_key:other");
+        assert_eq!(pool.lease(later, &[]), Err(None));
+        pool.reset_health("claude_api_key");
+        assert_eq!(pool.lease(later, &[]).unwrap().id, "default");
+        assert_eq!(pool.statuses(later)[0].auth_failures, 2);
+    }
+
+    #[test]
+    fn test_global_rate_limit_caps_pooled_keys() {
+        let mut config = AppConfig::default();
+        config.auth.claude_keys = vec![key("a", 0, 60), key("b", 0, 60)];
+        config.advanced.api_rate_limit = 2;
+        let mut pool = KeyPool::from_config(&config);
+        let now = Instant::now();
+
+        assert_eq!(pool.lease(now, &[]).unwrap().id, "a");
+        assert_eq!(pool.lease(now, &[]).unwrap().id, "b");
+        // Both keys have tokens left, but the pool as a whole is out
+        let wait = pool.lease(now, &[]).unwrap_err().unwrap();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30));
+
+        // Raising the limit takes effect without a fresh burst for the used tokens
+        config.advanced.api_rate_limit = 120;
+        pool.reconfigure(&config);
+        assert!(pool.lease(now + Duration::from_secs(1), &[]).is_ok());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::i18n::tr;
+use crate::secure_storage;
+use log::{info, warn};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// `(table, field)` of secrets older versions wrote into the config file;
+/// each field name is also its key in the secret store
+const LEGACY_FIELDS: [(&str, &str); 3] = [
+    ("auth", "claude_api_key"),
+    ("auth", "target_password"),
+    ("advanced", "proxy_password"),
+];
+/// Copy of the unscrubbed config, kept only while the file is being rewritten
+const BACKUP_EXTENSION: &str = "toml.plaintext-backup";
+
+/// Result of the last startup migration, until the UI picks it up
+static REPORT: Mutex<Option<MigrationReport>> = Mutex::new(None);
+
+/// What happened to plaintext secrets found in the config file
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+pub struct MigrationReport {
+    /// Moved into the secret store
+    pub migrated: Vec<String>,
+    /// Removed from the file only: empty, or the secret store already held a newer value
+    pub discarded: Vec<String>,
+    /// Set when the migration failed; the file is left as it was
+    pub error: Option<String>,
+}
+
+/// Remove every legacy secret field from `table`, returning the string values found
+fn take_legacy_secrets(table: &mut toml::Table) -> Vec<(&'static str, String)> {
+    LEGACY_FIELDS
+        .iter()
+        .filter_map(|&(section, field)| {
+            let value = table
+                .get_mut(section)
+                .and_then(toml::Value::as_table_mut)?
+                .remove(field)?;
+            Some((field, value.as_str().unwrap_or_default().to_string()))
+        })
+        .collect()
+}
+
+/// Overwrite a file with zeros before removing it, so the plaintext does not linger
+/// in freed blocks. Best effort: copy-on-write filesystems and SSDs may keep old data.
+fn secure_delete(path: &Path) -> io::Result<()> {
+    let len = std::fs::metadata(path)?.len();
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    io::copy(&mut io::repeat(0).take(len), &mut file)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)
+}
+
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let temp = path.with_extension("toml.tmp");
+    std::fs::write(&temp, content).and_then(|_| std::fs::rename(&temp, path))
+}
+
+/// A backup left by an interrupted run still holds plaintext secrets.
+/// Restore it when the config was lost, otherwise just wipe it.
+fn recover_backup(path: &Path, backup: &Path) -> Result<(), String> {
+    if !backup.exists() {
+        return Ok(());
+    }
+    let config_readable = std::fs::read_to_string(path)
+        .ok()
+        .is_some_and(|content| content.parse::<toml::Table>().is_ok());
+    if config_readable {
+        secure_delete(backup)
+    } else {
+        warn!("Restoring config from {}", backup.display());
+        std::fs::rename(backup, path)
+    }
+    .map_err(|e| tr!("legacy-secrets-backup-failed", error = e))
+}
+
+/// Move plaintext secrets out of the config file at `path` into the secret store.
+/// `None` when the file holds none.
+pub fn migrate_file(path: &Path) -> Result<Option<MigrationReport>, String> {
+    let backup = path.with_extension(BACKUP_EXTENSION);
+    recover_backup(path, &backup)?;
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(tr!("legacy-secrets-read-failed", error = e)),
+    };
+    let mut table = content
+        .parse::<toml::Table>()
+        .map_err(|e| tr!("legacy-secrets-read-failed", error = e))?;
+    let found = take_legacy_secrets(&mut table);
+    if found.is_empty() {
+        return Ok(None);
+    }
+
+    // Nothing is removed from the file until every secret is safely stored
+    let mut report = MigrationReport::default();
+    for (key, value) in found {
+        if secure_storage::has_credential(key) {
+            report.discarded.push(key.to_string());
+        } else if secure_storage::migrate_credential_to_keychain(key, Some(value.clone()))? {
+            if secure_storage::get_credential(key).ok().as_ref() != Some(&value) {
+                return Err(tr!("secret-migration-verify-failed", key = key));
+            }
+            report.migrated.push(key.to_string());
+        } else {
+            report.discarded.push(key.to_string());
+        }
+    }
+
+    let scrubbed = toml::to_string_pretty(&table)
+        .map_err(|e| tr!("common-serialize-config-failed", error = e))?;
+    std::fs::copy(path, &backup)
+        .and_then(|_| secure_storage::restrict_permissions(&backup))
+        .map_err(|e| tr!("legacy-secrets-backup-failed", error = e))?;
+    write_atomically(path, &scrubbed).map_err(|e| tr!("legacy-secrets-write-failed", error = e))?;
+    secure_delete(&backup).map_err(|e| tr!("legacy-secrets-backup-failed", error = e))?;
+
+    Ok(Some(report))
+}
+
+fn config_file_path() -> Result<PathBuf, String> {
+    confy::get_configuration_file_path("autodoc-agent", "config")
+        .map_err(|e| tr!("config-path-unavailable", error = e))
+}
+
+/// Run once at startup, after the secret store was chosen
+pub fn migrate_at_startup() {
+    let report = match config_file_path().and_then(|path| migrate_file(&path)) {
+        Ok(None) => return,
+        Ok(Some(report)) => {
+            info!(
+                "Plaintext secrets removed from config: {} migrated, {} discarded",
+                report.migrated.len(),
+                report.discarded.len()
+            );
+            report
+        }
+        Err(e) => {
+            warn!("Plaintext secret migration failed: {}", e);
+            MigrationReport {
+                error: Some(e),
+                ..Default::default()
+            }
+        }
+    };
+    *REPORT.lock().unwrap() = Some(report);
+}
+
+// ============= Tauri Commands =============
+
+/// Outcome of the startup migration; only returned once so the UI reports it once
+#[tauri::command]
+pub fn take_legacy_secret_report() -> Option<MigrationReport> {
+    REPORT.lock().unwrap().take()
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEGACY_CONFIG: &str = r#"
+[auth]
+claude_model = "claude-sonnet-4-20250514"
+target_password = "hunter2"
+
+[advanced]
+log_level = "debug"
+proxy_password = ""
+"#;
+
+    fn fixture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-legacy-secrets-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("config.toml")
+    }
+
+    #[test]
+    fn test_take_legacy_secrets() {
+        let mut table: toml::Table = LEGACY_CONFIG.parse().unwrap();
+        let found = take_legacy_secrets(&mut table);
+
+        assert_eq!(
+            found,
+            vec![
+                ("target_password", "hunter2".to_string()),
+                ("proxy_password", String::new())
+            ]
+        );
+        assert!(!table["auth"]
+            .as_table()
+            .unwrap()
+            .contains_key("target_password"));
+        assert_eq!(table["advanced"]["log_level"].as_str(), Some("debug"));
+        assert!(take_legacy_secrets(&mut table).is_empty());
+    }
+
+    #[test]
+    fn test_migrate_file_moves_and_scrubs() {
+        let path = fixture("migrate");
+        std::fs::write(&path, LEGACY_CONFIG).unwrap();
+
+        let report = migrate_file(&path).unwrap().unwrap();
+        assert_eq!(report.migrated, vec!["target_password"]);
+        assert_eq!(report.discarded, vec!["proxy_password"]);
+        assert_eq!(
+            secure_storage::get_credential("target_password").unwrap(),
+            "hunter2"
+        );
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("hunter2"));
+        assert!(!content.contains("proxy_password"));
+        assert!(content.contains("claude-sonnet-4-20250514"));
+        assert!(!path.with_extension(BACKUP_EXTENSION).exists());
+
+        // A value already in the store is newer than the leftover plaintext
+        std::fs::write(&path, LEGACY_CONFIG.replace("hunter2", "older")).unwrap();
+        let report = migrate_file(&path).unwrap().unwrap();
+        assert!(report.migrated.is_empty());
+        assert_eq!(
+            secure_storage::get_credential("target_password").unwrap(),
+            "hunter2"
+        );
+
+        // Clean files are left alone
+        assert_eq!(migrate_file(&path).unwrap(), None);
+        secure_storage::delete_credential("target_password").unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_backup_is_recovered() {
+        let path = fixture("recover");
+        let backup = path.with_extension(BACKUP_EXTENSION);
+
+        // Config lost mid-rewrite: the backup takes its place
+        std::fs::write(&backup, "[auth]\nclaude_model = \"x\"\n").unwrap();
+        std::fs::write(&path, "[auth\n").unwrap();
+        assert_eq!(migrate_file(&path).unwrap(), None);
+        assert!(!backup.exists());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("claude_model"));
+
+        // Config intact: the stale backup is wiped
+        std::fs::write(&backup, "target_password = \"hunter2\"").unwrap();
+        assert_eq!(migrate_file(&path).unwrap(), None);
+        assert!(!backup.exists());
+    }
+}
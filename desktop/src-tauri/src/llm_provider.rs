@@ -0,0 +1,213 @@
+use crate::config::{AppConfig, ValidationIssue};
+use crate::i18n::tr;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+/// The LLM service explorations run against
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProvider {
+    /// Claude through the Anthropic API; uses `claude_*` settings
+    #[default]
+    Anthropic,
+    /// Any service speaking the OpenAI chat completions API, e.g. on-prem gateways
+    OpenaiCompatible,
+    /// Local Ollama-style server; a key is only needed behind an authenticating proxy
+    Ollama,
+}
+
+impl LlmProvider {
+    pub fn name(self) -> &'static str {
+        match self {
+            LlmProvider::Anthropic => "Claude API",
+            LlmProvider::OpenaiCompatible => "OpenAI-compatible API",
+            LlmProvider::Ollama => "Ollama",
+        }
+    }
+
+    /// Value of `AUTODOC_LLM_PROVIDER` for the backend
+    pub fn id(self) -> &'static str {
+        match self {
+            LlmProvider::Anthropic => "anthropic",
+            LlmProvider::OpenaiCompatible => "openai_compatible",
+            LlmProvider::Ollama => "ollama",
+        }
+    }
+
+    /// Keychain entry holding the provider's API key
+    pub fn secret_key(self) -> &'static str {
+        match self {
+            LlmProvider::Anthropic => "claude_api_key",
+            LlmProvider::OpenaiCompatible => "openai_compatible_api_key",
+            LlmProvider::Ollama => "ollama_api_key",
+        }
+    }
+
+    pub fn requires_key(self) -> bool {
+        self != LlmProvider::Ollama
+    }
+}
+
+/// Base URL and model of a provider other than Anthropic
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProviderEndpoint {
+    /// API root the request paths are appended to, e.g. `https://llm.corp/v1`
+    pub base_url: String,
+    pub model: String,
+    // Note: stored in OS keychain under `LlmProvider::secret_key`
+    #[serde(default, skip_serializing)]
+    pub api_key: String,
+}
+
+pub fn default_openai_compatible() -> ProviderEndpoint {
+    ProviderEndpoint {
+        base_url: "https://api.openai.com/v1".to_string(),
+        model: "gpt-4o".to_string(),
+        api_key: String::new(),
+    }
+}
+
+pub fn default_ollama() -> ProviderEndpoint {
+    ProviderEndpoint {
+        base_url: "http://localhost:11434".to_string(),
+        model: "llama3.2-vision".to_string(),
+        api_key: String::new(),
+    }
+}
+
+/// Resolved settings of the selected provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveProvider {
+    pub provider: LlmProvider,
+    pub base_url: String,
+    pub model: String,
+    pub api_key: String,
+}
+
+/// Base URL `provider` is reached at under `config`, without a trailing slash
+pub fn base_url(config: &AppConfig, provider: LlmProvider) -> String {
+    let base_url = match provider {
+        LlmProvider::Anthropic => &config.advanced.endpoints.claude_api_base,
+        LlmProvider::OpenaiCompatible => &config.auth.openai_compatible.base_url,
+        LlmProvider::Ollama => &config.auth.ollama.base_url,
+    };
+    base_url.trim_end_matches('/').to_string()
+}
+
+pub fn active(config: &AppConfig) -> ActiveProvider {
+    let provider = config.auth.llm_provider;
+    let (model, api_key) = match provider {
+        LlmProvider::Anthropic => (&config.auth.claude_model, &config.auth.claude_api_key),
+        LlmProvider::OpenaiCompatible => (
+            &config.auth.openai_compatible.model,
+            &config.auth.openai_compatible.api_key,
+        ),
+        LlmProvider::Ollama => (&config.auth.ollama.model, &config.auth.ollama.api_key),
+    };
+    ActiveProvider {
+        provider,
+        base_url: base_url(config, provider),
+        model: model.clone(),
+        api_key: api_key.clone(),
+    }
+}
+
+/// Where the API key of `provider` is kept while the config is in memory
+pub fn api_key_mut(config: &mut AppConfig, provider: LlmProvider) -> &mut String {
+    match provider {
+        LlmProvider::Anthropic => &mut config.auth.claude_api_key,
+        LlmProvider::OpenaiCompatible => &mut config.auth.openai_compatible.api_key,
+        LlmProvider::Ollama => &mut config.auth.ollama.api_key,
+    }
+}
+
+// ============= Validation =============
+
+/// Problems with the selected provider's API key
+pub fn validate_key(provider: LlmProvider, key: &str) -> Result<(), String> {
+    match provider {
+        LlmProvider::Anthropic if key.is_empty() => Err(tr!("config-api-key-empty")),
+        LlmProvider::Anthropic if !key.starts_with("sk-") => Err(tr!("config-api-key-malformed")),
+        _ if key.is_empty() && provider.requires_key() => {
+            Err(tr!("llm-key-empty", provider = provider.name()))
+        }
+        _ if key.chars().any(char::is_whitespace) => {
+            Err(tr!("llm-key-malformed", provider = provider.name()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Problems with the selected provider's base URL and model
+pub fn validate_settings(config: &AppConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let (field, endpoint) = match config.auth.llm_provider {
+        // Claude settings are covered by the endpoint and model defaults
+        LlmProvider::Anthropic => return issues,
+        LlmProvider::OpenaiCompatible => ("auth.openai_compatible", &config.auth.openai_compatible),
+        LlmProvider::Ollama => ("auth.ollama", &config.auth.ollama),
+    };
+
+    let url_ok = Url::parse(&endpoint.base_url)
+        .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some());
+    if !url_ok {
+        issues.push(ValidationIssue::new(
+            &format!("{}.base_url", field),
+            tr!("llm-base-url-invalid", url = endpoint.base_url.clone()),
+        ));
+    }
+    if endpoint.model.trim().is_empty() {
+        issues.push(ValidationIssue::new(
+            &format!("{}.model", field),
+            tr!("llm-model-empty"),
+        ));
+    }
+    issues
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_rules_per_provider() {
+        assert!(validate_key(LlmProvider::Anthropic, "sk-ant-api03-x").is_ok());
+        assert!(validate_key(LlmProvider::Anthropic, "").is_err());
+        assert!(validate_key(LlmProvider::Anthropic, "corp-token").is_err());
+
+        // On-prem gateways issue keys in any format
+        assert!(validate_key(LlmProvider::OpenaiCompatible, "corp-token").is_ok());
+        assert!(validate_key(LlmProvider::OpenaiCompatible, "").is_err());
+        assert!(validate_key(LlmProvider::OpenaiCompatible, "two words").is_err());
+
+        assert!(validate_key(LlmProvider::Ollama, "").is_ok());
+    }
+
+    #[test]
+    fn test_active_provider_and_settings_validation() {
+        let mut config = AppConfig::default();
+        config.auth.claude_api_key = "sk-ant-test".to_string();
+        let claude = active(&config);
+        assert_eq!(claude.provider, LlmProvider::Anthropic);
+        assert_eq!(claude.base_url, "https://api.anthropic.com");
+        assert_eq!(claude.api_key, "sk-ant-test");
+        assert!(validate_settings(&config).is_empty());
+
+        config.auth.llm_provider = LlmProvider::Ollama;
+        config.auth.ollama.base_url = "http://gpu-box:11434/".to_string();
+        let ollama = active(&config);
+        assert_eq!(ollama.base_url, "http://gpu-box:11434");
+        assert_eq!(ollama.model, "llama3.2-vision");
+        assert_eq!(ollama.api_key, "");
+
+        config.auth.ollama.base_url = "gpu-box:11434".to_string();
+        config.auth.ollama.model = " ".to_string();
+        let fields: Vec<_> = validate_settings(&config)
+            .into_iter()
+            .map(|issue| issue.field)
+            .collect();
+        assert_eq!(fields, vec!["auth.ollama.base_url", "auth.ollama.model"]);
+    }
+}
@@ -1,16 +1,41 @@
 // Prevents additional console window on Windows in release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api_gateway;
+mod autostart;
+mod backup;
 mod config;
+mod config_bundle;
+mod config_history;
+mod connectivity;
+mod credential_registry;
+mod crypto;
+mod google_oauth;
+mod http_client;
+mod i18n;
+mod key_pool;
+mod legacy_secrets;
+mod llm_provider;
+mod path_guard;
+mod retention;
+mod snapshot_store;
+mod snapshots;
+mod storage_move;
+mod storage_usage;
+mod targets;
+mod telemetry;
+mod usage_ledger;
 mod sidecar;
 mod secure_storage;
+mod session_vault;
 mod tray_v2;
 mod updater;
+mod window_state;
 
 use tray_v2 as tray;
 
-use log::info;
-use tauri::Manager;
+use log::{info, warn};
+use tauri::{Emitter, Manager, RunEvent, WindowEvent};
 
 fn main() {
     env_logger::init();
@@ -24,17 +49,66 @@ fn main() {
         .setup(|app| {
             info!("Application setup...");
 
+            // Messages follow the saved interface language from the start
+            let settings = config::load_settings();
+            let language = settings.basic.language.clone();
+            i18n::set_language(&language);
+
+            // Keychain, or the encrypted file vault where there is none
+            secure_storage::init(settings.advanced.secret_backend);
+
+            // Move secrets older versions left in the config file into the secret store
+            legacy_secrets::migrate_at_startup();
+
+            // Only recorded when enable_telemetry is on
+            telemetry::record_app_start(&language);
+
             // Initialize tray icon
             tray::create_tray(app.handle())?;
 
+            // Relabel the tray when the language is changed in settings
+            let handle = app.handle().clone();
+            i18n::on_language_changed(move |_| {
+                if let Err(e) = tray::refresh_menu(&handle) {
+                    warn!("Failed to refresh tray menu: {}", e);
+                }
+            });
+
+            // Local Claude API gateway; the backend gets its address and a session token
+            match tauri::async_runtime::block_on(api_gateway::start(&settings)) {
+                Ok(endpoint) => {
+                    app.manage(endpoint);
+                }
+                Err(e) => warn!("Failed to start API gateway: {}", e),
+            }
+
+            // Tell the UI when Claude spending passes a budget
+            let handle = app.handle().clone();
+            usage_ledger::on_budget_exceeded(move |status| {
+                if let Err(e) = handle.emit(usage_ledger::BUDGET_EVENT, status) {
+                    warn!("Failed to emit budget warning: {}", e);
+                }
+            });
+
             // 初始化 Backend Process
             let backend = sidecar::BackendProcess::new();
             app.manage(backend);
 
+            // Move a plaintext Google token file into the keychain
+            match google_oauth::migrate_token_file() {
+                Ok(true) => info!("Google token file migrated to keychain"),
+                Ok(false) => {}
+                Err(e) => warn!("Google token migration failed: {}", e),
+            }
+
             // 載入或創建配置
             match config::load_config() {
                 Ok(cfg) => {
                     info!("配置載入成功");
+                    // Re-register in case the binary moved since the last save
+                    if let Err(e) = autostart::sync(cfg.basic.auto_start) {
+                        warn!("Failed to sync autostart registration: {}", e);
+                    }
                     app.manage(cfg);
                 }
                 Err(e) => {
@@ -45,12 +119,29 @@ fn main() {
                 }
             }
 
+            // Daily cleanup of expired snapshots and screenshots when auto_cleanup is on
+            retention::spawn_background_job();
+
+            // Warn when a storage volume runs low on free space
+            storage_usage::spawn_low_space_monitor(app.handle().clone());
+
+            // Remind about credentials due for rotation or about to expire
+            credential_registry::spawn_reminder_monitor(app.handle().clone());
+
             // Note: Backend is now started manually via the UI to ensure proper path resolution
             // The backend requires AppHandle for path resolution, which is not available here
             info!("Backend will be started on demand via UI");
 
+            // The window is created hidden; show it unless starting in the tray
+            window_state::apply_startup_visibility(app.handle());
+
             Ok(())
         })
+        .on_window_event(|window, event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                window_state::handle_close_requested(window, api);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // Config commands
             config::load_config,
@@ -58,25 +149,86 @@ fn main() {
             config::validate_config,
             config::get_default_config,
             config::reset_config,
+            path_guard::check_storage_paths,
+            targets::list_targets,
+            autostart::get_autostart_status,
+            // Storage retention commands
+            retention::preview_retention,
+            retention::run_retention,
+            retention::get_retention_history,
+            // Storage usage commands
+            storage_usage::get_storage_usage,
+            snapshot_store::recompress_snapshots,
+            // Storage relocation commands
+            storage_move::move_storage,
+            storage_move::get_pending_storage_move,
+            storage_move::cancel_storage_move,
+            // Telemetry commands
+            telemetry::get_telemetry_events,
+            telemetry::export_telemetry,
+            telemetry::purge_telemetry,
+            telemetry::record_exploration,
+            telemetry::record_error,
+            // Usage ledger commands
+            usage_ledger::get_usage_report,
+            usage_ledger::export_usage_csv,
+            usage_ledger::get_usage_budgets,
+            usage_ledger::save_usage_budget,
+            usage_ledger::delete_usage_budget,
+            // API key pool commands
+            key_pool::get_claude_key_pool_status,
+            // Backup commands
+            backup::create_backup,
+            backup::inspect_backup,
+            backup::restore_backup,
+            // Config history commands
+            config_history::list_config_history,
+            config_history::diff_config_versions,
+            config_history::restore_config_version,
+            // Config bundle commands
+            config_bundle::export_config,
+            config_bundle::import_config,
             // Secure storage commands
-            secure_storage::store_secure_credential,
-            secure_storage::get_secure_credential,
-            secure_storage::delete_secure_credential,
-            secure_storage::has_secure_credential,
+            credential_registry::store_secure_credential,
+            credential_registry::delete_secure_credential,
+            credential_registry::get_credential_status,
+            credential_registry::list_credentials,
+            credential_registry::set_credential_expiry,
+            secure_storage::get_secret_store_diagnostics,
+            secure_storage::unlock_secret_store,
+            secure_storage::migrate_secret_store,
+            legacy_secrets::take_legacy_secret_report,
+            // Session vault commands
+            session_vault::list_sessions,
+            session_vault::invalidate_session,
+            session_vault::export_session,
+            session_vault::import_session,
             // Sidecar commands
             sidecar::start_backend,
             sidecar::stop_backend,
             sidecar::restart_backend,
             sidecar::check_backend_health,
             sidecar::get_backend_status,
+            // HTTP client commands
+            http_client::test_proxy,
+            connectivity::test_connections,
+            // Google OAuth commands
+            google_oauth::authorize_google,
+            google_oauth::get_google_auth_status,
+            google_oauth::revoke_google_authorization,
             // Updater commands
             updater::check_for_updates,
             updater::install_update,
             updater::get_app_version,
             updater::download_update_progress,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                window_state::stop_services(app);
+            }
+        });
 
     info!("Application terminated");
 }
@@ -0,0 +1,447 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+const WRITE_PROBE_PREFIX: &str = ".autodoc-write-probe";
+
+// ============= Allowed Roots =============
+
+/// Built-in roots used when `storage.allowed_roots` is empty.
+/// The home directory itself is deliberately not a root; only `~/AutoDoc` is.
+pub fn default_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = [
+        dirs::document_dir(),
+        dirs::data_dir(),
+        dirs::config_dir(),
+        dirs::download_dir(),
+        dirs::desktop_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join("AutoDoc"));
+    }
+    roots
+}
+
+/// Roots that storage paths must live under. Only `storage.allowed_roots` of the
+/// saved config file counts: settings sent by the UI or read from an imported file
+/// are the input being checked, so they never get to widen the roots themselves.
+pub fn storage_roots() -> Vec<PathBuf> {
+    let configured = config::load_settings().storage.allowed_roots;
+    if configured.is_empty() {
+        default_roots()
+    } else {
+        configured
+    }
+}
+
+// ============= Validation =============
+
+/// Remove `.` components and refuse `..`, without touching the filesystem
+fn normalize(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err(tr!("path-not-absolute", path = path.display()));
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(tr!("path-parent-component", path = path.display()));
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    Ok(normalized)
+}
+
+/// Find the allowed root containing `path` and return (canonical root, relative remainder)
+fn split_at_root(path: &Path, roots: &[PathBuf]) -> Option<(PathBuf, PathBuf)> {
+    roots.iter().find_map(|root| {
+        // Roots themselves may legitimately be symlinks (e.g. /var on macOS),
+        // so accept the path in either the configured or the resolved form
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.clone());
+        [root, &canonical_root].iter().find_map(|candidate| {
+            path.strip_prefix(candidate)
+                .ok()
+                .map(|relative| (canonical_root.clone(), relative.to_path_buf()))
+        })
+    })
+}
+
+/// Validate that `path` lies under one of `roots` and that no existing component
+/// below the root is a symlink. Returns the resolved absolute path.
+pub fn validate_within(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let normalized = normalize(path)?;
+
+    let (root, relative) = split_at_root(&normalized, roots)
+        .ok_or_else(|| tr!("path-outside-roots", path = normalized.display()))?;
+
+    let resolved = root.join(&relative);
+
+    let mut current = root;
+    for component in relative.components() {
+        current.push(component.as_os_str());
+        match std::fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(tr!("path-symlink", path = current.display()));
+            }
+            Ok(_) => {}
+            // Nothing below a missing component can be a symlink yet
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+            Err(e) => return Err(tr!("path-validation-failed", error = e)),
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Create a directory one component at a time, re-validating after each step so a
+/// symlink swapped in between validation and creation is detected
+pub fn create_dir_secure(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let target = validate_within(path, roots)?;
+    let (root, relative) = split_at_root(&target, roots)
+        .ok_or_else(|| tr!("path-outside-roots", path = target.display()))?;
+
+    let mut current = root;
+    for component in relative.components() {
+        current.push(component.as_os_str());
+        match std::fs::create_dir(&current) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(tr!("path-create-dir-failed", path = current.display(), error = e)),
+        }
+
+        let metadata = std::fs::symlink_metadata(&current)
+            .map_err(|e| tr!("path-validation-failed", error = e))?;
+        if metadata.file_type().is_symlink() || !metadata.is_dir() {
+            return Err(tr!("path-symlink-or-not-dir", path = current.display()));
+        }
+    }
+
+    Ok(current)
+}
+
+// ============= Capacity Checks =============
+
+/// Closest ancestor of `path` (or the path itself) that exists on disk
+fn nearest_existing(path: &Path) -> Option<PathBuf> {
+    path.ancestors().find(|p| p.exists()).map(Path::to_path_buf)
+}
+
+/// Write and delete a probe file in `dir` (or its nearest existing ancestor)
+pub fn probe_writable(dir: &Path) -> Result<(), String> {
+    let existing = nearest_existing(dir).ok_or_else(|| tr!("path-not-found", path = dir.display()))?;
+    let probe = existing.join(format!("{}-{}", WRITE_PROBE_PREFIX, std::process::id()));
+
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .and_then(|mut file| file.write_all(b"ok").and_then(|_| file.sync_all()));
+    let _ = std::fs::remove_file(&probe);
+
+    result.map_err(|e| tr!("path-not-writable", path = existing.display(), error = e))
+}
+
+/// Free bytes on the volume holding `path`
+pub fn free_space(path: &Path) -> Result<u64, String> {
+    let existing = nearest_existing(path).ok_or_else(|| tr!("path-not-found", path = path.display()))?;
+    fs4::available_space(&existing).map_err(|e| tr!("path-free-space-unavailable", error = e))
+}
+
+// ============= Directory Walking =============
+
+/// Every regular file below `dir` with its metadata. Symlinks are never followed,
+/// so a link planted inside storage cannot pull outside files into a scan or cleanup.
+pub fn walk_files(dir: &Path) -> Vec<(PathBuf, std::fs::Metadata)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let metadata = match std::fs::symlink_metadata(entry.path()) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                files.push((entry.path(), metadata));
+            }
+        }
+    }
+    files
+}
+
+// ============= Storage Path Reports =============
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PathCheckReport {
+    pub field: String,
+    pub path: PathBuf,
+    pub resolved: Option<PathBuf>,
+    pub exists: bool,
+    pub writable: bool,
+    pub free_bytes: Option<u64>,
+    pub ok: bool,
+    pub problems: Vec<String>,
+}
+
+/// Storage paths paired with their config field and the directory that will be written to
+fn storage_targets(storage: &StorageSettings) -> Vec<(&'static str, PathBuf, PathBuf)> {
+    let database_dir = storage
+        .database_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| storage.database_path.clone());
+
+    vec![
+        (
+            "storage.snapshot_storage_path",
+            storage.snapshot_storage_path.clone(),
+            storage.snapshot_storage_path.clone(),
+        ),
+        (
+            "storage.screenshot_storage_path",
+            storage.screenshot_storage_path.clone(),
+            storage.screenshot_storage_path.clone(),
+        ),
+        ("storage.database_path", storage.database_path.clone(), database_dir),
+    ]
+}
+
+/// Run every check against every storage path without creating anything.
+/// A path outside the allowed roots is only reported; nothing is probed there.
+pub fn check_storage(storage: &StorageSettings, roots: &[PathBuf]) -> Vec<PathCheckReport> {
+    let min_free_bytes = storage.min_free_space_mb.saturating_mul(1024 * 1024);
+
+    storage_targets(storage)
+        .into_iter()
+        .map(|(field, path, dir)| {
+            let mut problems = Vec::new();
+
+            let resolved = match validate_within(&path, roots) {
+                Ok(resolved) => Some(resolved),
+                Err(e) => {
+                    return PathCheckReport {
+                        field: field.to_string(),
+                        exists: path.exists(),
+                        path,
+                        resolved: None,
+                        writable: false,
+                        free_bytes: None,
+                        ok: false,
+                        problems: vec![e],
+                    };
+                }
+            };
+
+            let writable = match probe_writable(&dir) {
+                Ok(()) => true,
+                Err(e) => {
+                    problems.push(e);
+                    false
+                }
+            };
+
+            let free_bytes = match free_space(&dir) {
+                Ok(bytes) => {
+                    if bytes < min_free_bytes {
+                        problems.push(tr!(
+                            "path-low-free-space",
+                            available = bytes / (1024 * 1024),
+                            required = storage.min_free_space_mb
+                        ));
+                    }
+                    Some(bytes)
+                }
+                Err(e) => {
+                    problems.push(e);
+                    None
+                }
+            };
+
+            PathCheckReport {
+                field: field.to_string(),
+                exists: path.exists(),
+                path,
+                resolved,
+                writable,
+                free_bytes,
+                ok: problems.is_empty(),
+                problems,
+            }
+        })
+        .collect()
+}
+
+// ============= Tauri Commands =============
+
+/// Dry-run check of the given (or saved) storage settings; nothing is created or changed
+#[tauri::command]
+pub fn check_storage_paths(storage: Option<StorageSettings>) -> Vec<PathCheckReport> {
+    let storage = storage.unwrap_or_else(|| config::load_settings().storage);
+    check_storage(&storage, &storage_roots())
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("autodoc-guard-test-{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        root.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn test_path_under_root_allowed() {
+        let root = test_root("allowed");
+        let result = validate_within(&root.join("snapshots").join("new"), std::slice::from_ref(&root));
+        assert_eq!(result.unwrap(), root.join("snapshots").join("new"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_path_outside_roots_rejected() {
+        let root = test_root("outside");
+        assert!(validate_within(Path::new("/etc/autodoc"), std::slice::from_ref(&root)).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_relative_and_parent_components_rejected() {
+        let root = test_root("relative");
+        assert!(validate_within(Path::new("AutoDoc/snapshots"), std::slice::from_ref(&root)).is_err());
+        assert!(validate_within(&root.join("..").join("escape"), std::slice::from_ref(&root)).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_current_dir_components_removed() {
+        let root = test_root("curdir");
+        let result = validate_within(&root.join(".").join("data"), std::slice::from_ref(&root)).unwrap();
+        assert_eq!(result, root.join("data"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_component_rejected() {
+        use std::os::unix::fs::symlink;
+
+        let root = test_root("symlink");
+        let elsewhere = test_root("symlink-target");
+        symlink(&elsewhere, root.join("link")).unwrap();
+
+        assert!(validate_within(&root.join("link"), std::slice::from_ref(&root)).is_err());
+        assert!(validate_within(&root.join("link").join("child"), std::slice::from_ref(&root)).is_err());
+        assert!(create_dir_secure(&root.join("link").join("child"), std::slice::from_ref(&root)).is_err());
+        assert!(!elsewhere.join("child").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+        let _ = std::fs::remove_dir_all(&elsewhere);
+    }
+
+    #[test]
+    fn test_create_dir_secure_creates_nested_dirs() {
+        let root = test_root("create");
+        let target = root.join("a").join("b").join("c");
+
+        let created = create_dir_secure(&target, std::slice::from_ref(&root)).unwrap();
+        assert_eq!(created, target);
+        assert!(target.is_dir());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_write_probe_leaves_no_files() {
+        let root = test_root("probe");
+        assert!(probe_writable(&root.join("not-yet-created")).is_ok());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_check_storage_reports_per_path() {
+        let root = test_root("report");
+        let mut storage = config::AppConfig::default().storage;
+        storage.allowed_roots = vec![root.clone()];
+        storage.snapshot_storage_path = root.join("snapshots");
+        storage.screenshot_storage_path = PathBuf::from("/etc/autodoc-screenshots");
+        storage.database_path = root.join("autodoc.db");
+        storage.min_free_space_mb = 0;
+
+        let reports = check_storage(&storage, &storage.allowed_roots);
+        assert_eq!(reports.len(), 3);
+        assert!(reports[0].ok, "{:?}", reports[0].problems);
+        assert!(!reports[1].ok);
+        assert!(reports[2].ok, "{:?}", reports[2].problems);
+        // Dry run: nothing was created
+        assert!(!root.join("snapshots").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rejected_path_not_probed() {
+        let root = test_root("rejected-root");
+        let outside = test_root("rejected-outside");
+        // A probe here would fail to create its file and add a problem
+        let probe = outside.join(format!("{}-{}", WRITE_PROBE_PREFIX, std::process::id()));
+        std::fs::create_dir(&probe).unwrap();
+
+        let mut storage = config::AppConfig::default().storage;
+        storage.snapshot_storage_path = outside.clone();
+        storage.screenshot_storage_path = root.join("screenshots");
+        storage.database_path = root.join("autodoc.db");
+        storage.min_free_space_mb = 0;
+
+        let reports = check_storage(&storage, std::slice::from_ref(&root));
+        assert!(!reports[0].ok);
+        assert_eq!(reports[0].problems.len(), 1, "{:?}", reports[0].problems);
+        assert!(!reports[0].writable);
+        assert_eq!(reports[0].free_bytes, None);
+        assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn test_free_space_minimum_enforced() {
+        let root = test_root("space");
+        let mut storage = config::AppConfig::default().storage;
+        storage.allowed_roots = vec![root.clone()];
+        storage.snapshot_storage_path = root.join("snapshots");
+        storage.screenshot_storage_path = root.join("screenshots");
+        storage.database_path = root.join("autodoc.db");
+        storage.min_free_space_mb = u64::MAX / (1024 * 1024);
+
+        let reports = check_storage(&storage, &storage.allowed_roots);
+        assert!(reports.iter().all(|r| !r.ok));
+        assert!(reports[0].problems.iter().any(|p| p.contains("可用空間不足")));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,604 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use crate::{path_guard, snapshots};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+const RUN_LOG_FILE_NAME: &str = "retention_runs.jsonl";
+const MAX_LOGGED_RUNS: usize = 50;
+/// Subdirectory of `snapshot_storage_path` that archived items are moved into
+const ARCHIVE_DIR_NAME: &str = "archive";
+/// First background run shortly after launch, then once a day
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(10 * 60);
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Serializes runs so the daily job and a manual run never race on the same files
+static RUN_LOCK: Mutex<()> = Mutex::new(());
+
+/// What happens to data older than the retention window
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    #[default]
+    Delete,
+    /// Move into `<snapshot_storage_path>/archive` for manual offloading
+    Archive,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionItemKind {
+    Snapshot,
+    Screenshot,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RetentionItem {
+    pub kind: RetentionItemKind,
+    pub path: PathBuf,
+    pub snapshot_id: Option<String>,
+    pub project_id: Option<String>,
+    /// Snapshot creation time, or file modification time for screenshots
+    pub timestamp: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// Outcome of one run; dry runs list the items without touching them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RetentionReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub dry_run: bool,
+    pub action: RetentionAction,
+    pub retention_days: u32,
+    pub items: Vec<RetentionItem>,
+    /// Expired snapshots kept because they are their project's latest version
+    pub kept_latest: Vec<String>,
+    pub processed: usize,
+    pub freed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+struct RetentionPlan {
+    items: Vec<RetentionItem>,
+    kept_latest: Vec<String>,
+}
+
+// ============= Planning =============
+
+fn to_utc(time: SystemTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from(time)
+}
+
+fn directory_size(dir: &Path) -> u64 {
+    path_guard::walk_files(dir)
+        .iter()
+        .map(|(_, metadata)| metadata.len())
+        .sum()
+}
+
+/// Find everything older than the retention window. Each project's latest snapshot,
+/// and screenshots filed under its ID, are always kept.
+fn plan(
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+    now: DateTime<Utc>,
+) -> Result<RetentionPlan, String> {
+    let mut plan = RetentionPlan {
+        items: Vec::new(),
+        kept_latest: Vec::new(),
+    };
+    // 0 means keep forever
+    if storage.retention_days == 0 {
+        return Ok(plan);
+    }
+
+    let snapshot_root = path_guard::validate_within(&storage.snapshot_storage_path, roots)?;
+    let screenshot_root = path_guard::validate_within(&storage.screenshot_storage_path, roots)?;
+    let archive_root = snapshot_root.join(ARCHIVE_DIR_NAME);
+    let cutoff = now - ChronoDuration::days(i64::from(storage.retention_days));
+
+    let catalog = snapshots::list_snapshots(&snapshot_root);
+    let protected: HashSet<String> = snapshots::latest_per_project(&catalog)
+        .into_values()
+        .collect();
+
+    for snapshot in &catalog {
+        let manifest = &snapshot.manifest;
+        if manifest.created_at >= cutoff {
+            continue;
+        }
+        if protected.contains(&manifest.id) {
+            plan.kept_latest.push(manifest.id.clone());
+            continue;
+        }
+        plan.items.push(RetentionItem {
+            kind: RetentionItemKind::Snapshot,
+            path: snapshot.dir.clone(),
+            snapshot_id: Some(manifest.id.clone()),
+            project_id: Some(manifest.project_id.clone()),
+            timestamp: manifest.created_at,
+            size_bytes: directory_size(&snapshot.dir),
+        });
+    }
+
+    for (path, metadata) in path_guard::walk_files(&screenshot_root) {
+        // Screenshot and snapshot paths may share a tree; never rescan archived or snapshot data
+        if path.starts_with(&archive_root)
+            || path.starts_with(snapshots::snapshots_dir(&snapshot_root))
+        {
+            continue;
+        }
+        let modified = match metadata.modified() {
+            Ok(modified) => to_utc(modified),
+            Err(_) => continue,
+        };
+        if modified >= cutoff {
+            continue;
+        }
+
+        let relative = path.strip_prefix(&screenshot_root).unwrap_or(&path);
+        let owner = relative
+            .components()
+            .filter_map(|c| c.as_os_str().to_str())
+            .find(|name| catalog.iter().any(|s| s.manifest.id == *name));
+        if owner.is_some_and(|id| protected.contains(id)) {
+            continue;
+        }
+
+        plan.items.push(RetentionItem {
+            kind: RetentionItemKind::Screenshot,
+            path: path.clone(),
+            snapshot_id: owner.map(str::to_string),
+            project_id: owner.and_then(|id| {
+                catalog
+                    .iter()
+                    .find(|s| s.manifest.id == id)
+                    .map(|s| s.manifest.project_id.clone())
+            }),
+            timestamp: modified,
+            size_bytes: metadata.len(),
+        });
+    }
+
+    Ok(plan)
+}
+
+// ============= Execution =============
+
+/// Move a file or directory, falling back to copy + delete across volumes
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if from.is_dir() {
+        for (file, _) in path_guard::walk_files(from) {
+            let relative = file.strip_prefix(from).unwrap_or(&file);
+            let destination = to.join(relative);
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&file, &destination)?;
+        }
+        std::fs::remove_dir_all(from)
+    } else {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)
+    }
+}
+
+fn archive_destination(
+    item: &RetentionItem,
+    archive_root: &Path,
+    screenshot_root: &Path,
+) -> PathBuf {
+    match item.kind {
+        RetentionItemKind::Snapshot => archive_root
+            .join("snapshots")
+            .join(item.path.file_name().unwrap_or_default()),
+        RetentionItemKind::Screenshot => archive_root.join("screenshots").join(
+            item.path
+                .strip_prefix(screenshot_root)
+                .unwrap_or(&item.path),
+        ),
+    }
+}
+
+fn apply(
+    item: &RetentionItem,
+    action: RetentionAction,
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+) -> Result<(), String> {
+    // Re-check right before touching the item in case the tree changed since planning
+    let path = path_guard::validate_within(&item.path, roots)?;
+
+    let result = match action {
+        RetentionAction::Delete => match item.kind {
+            RetentionItemKind::Snapshot => std::fs::remove_dir_all(&path),
+            RetentionItemKind::Screenshot => std::fs::remove_file(&path),
+        },
+        RetentionAction::Archive => {
+            let snapshot_root = path_guard::validate_within(&storage.snapshot_storage_path, roots)?;
+            let screenshot_root =
+                path_guard::validate_within(&storage.screenshot_storage_path, roots)?;
+            let destination = archive_destination(
+                item,
+                &snapshot_root.join(ARCHIVE_DIR_NAME),
+                &screenshot_root,
+            );
+            move_path(&path, &destination)
+        }
+    };
+    result.map_err(|e| {
+        tr!(
+            "retention-item-failed",
+            path = item.path.display(),
+            error = e
+        )
+    })
+}
+
+fn run_with(
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+    dry_run: bool,
+    now: DateTime<Utc>,
+) -> Result<RetentionReport, String> {
+    let plan = plan(storage, roots, now)?;
+    let mut report = RetentionReport {
+        started_at: now,
+        finished_at: now,
+        dry_run,
+        action: storage.retention_action,
+        retention_days: storage.retention_days,
+        items: Vec::new(),
+        kept_latest: plan.kept_latest,
+        processed: 0,
+        freed_bytes: 0,
+        errors: Vec::new(),
+    };
+
+    for item in plan.items {
+        if !dry_run {
+            match apply(&item, storage.retention_action, storage, roots) {
+                Ok(()) => {
+                    report.processed += 1;
+                    report.freed_bytes += item.size_bytes;
+                }
+                Err(e) => {
+                    warn!("Retention: {}", e);
+                    report.errors.push(e);
+                }
+            }
+        }
+        report.items.push(item);
+    }
+
+    report.finished_at = Utc::now();
+    Ok(report)
+}
+
+// ============= Run Log =============
+
+fn run_log_path() -> Result<PathBuf, String> {
+    Ok(config::config_dir()?.join(RUN_LOG_FILE_NAME))
+}
+
+fn read_run_log(path: &Path) -> Vec<RetentionReport> {
+    std::fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append a run, keeping only the most recent `MAX_LOGGED_RUNS`
+fn append_run_log(path: &Path, report: &RetentionReport) -> Result<(), String> {
+    let mut runs = read_run_log(path);
+    runs.push(report.clone());
+    let skip = runs.len().saturating_sub(MAX_LOGGED_RUNS);
+
+    let mut content = String::new();
+    for run in runs.iter().skip(skip) {
+        let line =
+            serde_json::to_string(run).map_err(|e| tr!("retention-log-write-failed", error = e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    std::fs::write(path, content).map_err(|e| tr!("retention-log-write-failed", error = e))
+}
+
+/// Keep retention from running while the guard is held, e.g. during a storage move
+pub fn lock() -> Result<MutexGuard<'static, ()>, String> {
+    RUN_LOCK
+        .try_lock()
+        .map_err(|_| tr!("retention-already-running"))
+}
+
+/// Plan and (unless `dry_run`) apply retention for `storage`, then log the run
+pub fn run(storage: &StorageSettings, dry_run: bool) -> Result<RetentionReport, String> {
+    let _guard = lock()?;
+
+    let roots = path_guard::storage_roots();
+    let report = run_with(storage, &roots, dry_run, Utc::now())?;
+
+    info!(
+        "Retention run ({}, {:?}, {} days): {} item(s), {} processed, {} bytes freed, {} error(s)",
+        if dry_run { "dry run" } else { "applied" },
+        report.action,
+        report.retention_days,
+        report.items.len(),
+        report.processed,
+        report.freed_bytes,
+        report.errors.len()
+    );
+    match run_log_path() {
+        Ok(path) => {
+            if let Err(e) = append_run_log(&path, &report) {
+                warn!("Failed to record retention run: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to record retention run: {}", e),
+    }
+
+    Ok(report)
+}
+
+/// Daily background job; reads the settings on every tick so changes apply without restart
+pub fn spawn_background_job() {
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        let mut interval = tokio::time::interval(RUN_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let storage = config::load_settings().storage;
+            if !storage.auto_cleanup || storage.retention_days == 0 {
+                continue;
+            }
+            match tokio::task::spawn_blocking(move || run(&storage, false)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => warn!("Scheduled retention run failed: {}", e),
+                Err(e) => warn!("Scheduled retention run panicked: {}", e),
+            }
+        }
+    });
+}
+
+// ============= Tauri Commands =============
+
+/// Report what retention would remove, using `storage` or the saved settings
+#[tauri::command]
+pub async fn preview_retention(
+    storage: Option<StorageSettings>,
+) -> Result<RetentionReport, String> {
+    let storage = storage.unwrap_or_else(|| config::load_settings().storage);
+    tokio::task::spawn_blocking(move || run(&storage, true))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Apply retention now with the saved settings, even when `auto_cleanup` is off
+#[tauri::command]
+pub async fn run_retention() -> Result<RetentionReport, String> {
+    let storage = config::load_settings().storage;
+    tokio::task::spawn_blocking(move || run(&storage, false))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Most recent runs first
+#[tauri::command]
+pub fn get_retention_history(limit: Option<usize>) -> Result<Vec<RetentionReport>, String> {
+    let mut runs = read_run_log(&run_log_path()?);
+    runs.reverse();
+    runs.truncate(limit.unwrap_or(MAX_LOGGED_RUNS));
+    Ok(runs)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshots::test_support::write_snapshot;
+
+    struct Fixture {
+        root: PathBuf,
+        storage: StorageSettings,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("autodoc-retention-test-{}", name));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(&root).unwrap();
+            let root = root.canonicalize().unwrap();
+
+            let mut storage = config::AppConfig::default().storage;
+            storage.snapshot_storage_path = root.join("snapshots");
+            storage.screenshot_storage_path = root.join("screenshots");
+            storage.retention_days = 30;
+            storage.allowed_roots = vec![root.clone()];
+            Fixture { root, storage }
+        }
+
+        fn roots(&self) -> Vec<PathBuf> {
+            self.storage.allowed_roots.clone()
+        }
+
+        fn screenshot(&self, relative: &str, age_days: u64) -> PathBuf {
+            let path = self.storage.screenshot_storage_path.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"png").unwrap();
+            let mtime = SystemTime::now() - Duration::from_secs(age_days * 24 * 60 * 60);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+            path
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn seed(fixture: &Fixture) {
+        let now = Utc::now();
+        let base = &fixture.storage.snapshot_storage_path;
+        write_snapshot(
+            base,
+            "old",
+            "proj-1",
+            (1, 0, 0),
+            now - ChronoDuration::days(90),
+        );
+        write_snapshot(
+            base,
+            "recent",
+            "proj-1",
+            (1, 1, 0),
+            now - ChronoDuration::days(1),
+        );
+        // Only snapshot of its project: expired but still the latest version
+        write_snapshot(
+            base,
+            "only",
+            "proj-2",
+            (1, 0, 0),
+            now - ChronoDuration::days(90),
+        );
+
+        fixture.screenshot("old/page.png", 90);
+        fixture.screenshot("only/page.png", 90);
+        fixture.screenshot("loose.png", 90);
+        fixture.screenshot("fresh.png", 1);
+    }
+
+    fn paths(report: &RetentionReport) -> Vec<String> {
+        let mut paths: Vec<String> = report
+            .items
+            .iter()
+            .map(|item| {
+                item.path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_dry_run_reports_without_removing() {
+        let fixture = Fixture::new("dry-run");
+        seed(&fixture);
+
+        let report = run_with(&fixture.storage, &fixture.roots(), true, Utc::now()).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(paths(&report), vec!["loose.png", "old", "page.png"]);
+        assert_eq!(report.kept_latest, vec!["only".to_string()]);
+        assert_eq!(report.processed, 0);
+
+        let screenshot = report
+            .items
+            .iter()
+            .find(|item| item.kind == RetentionItemKind::Screenshot && item.snapshot_id.is_some())
+            .unwrap();
+        assert_eq!(screenshot.project_id.as_deref(), Some("proj-1"));
+
+        for item in &report.items {
+            assert!(item.path.exists());
+        }
+    }
+
+    #[test]
+    fn test_delete_keeps_latest_and_recent() {
+        let fixture = Fixture::new("delete");
+        seed(&fixture);
+
+        let report = run_with(&fixture.storage, &fixture.roots(), false, Utc::now()).unwrap();
+        assert_eq!(report.processed, 3);
+        assert!(report.errors.is_empty());
+        assert!(report.freed_bytes > 0);
+
+        let snapshots = snapshots::snapshots_dir(&fixture.storage.snapshot_storage_path);
+        let screenshots = &fixture.storage.screenshot_storage_path;
+        assert!(!snapshots.join("old").exists());
+        assert!(snapshots.join("recent").exists());
+        assert!(snapshots.join("only").exists());
+        assert!(!screenshots.join("old/page.png").exists());
+        assert!(screenshots.join("only/page.png").exists());
+        assert!(!screenshots.join("loose.png").exists());
+        assert!(screenshots.join("fresh.png").exists());
+    }
+
+    #[test]
+    fn test_archive_moves_items() {
+        let mut fixture = Fixture::new("archive");
+        fixture.storage.retention_action = RetentionAction::Archive;
+        seed(&fixture);
+
+        let report = run_with(&fixture.storage, &fixture.roots(), false, Utc::now()).unwrap();
+        assert_eq!(report.processed, 3);
+
+        let archive = fixture.storage.snapshot_storage_path.join(ARCHIVE_DIR_NAME);
+        assert!(archive.join("snapshots/old/manifest.json").exists());
+        assert!(archive.join("screenshots/old/page.png").exists());
+        assert!(archive.join("screenshots/loose.png").exists());
+
+        // Archived data is out of the catalog and not picked up again
+        let again = run_with(&fixture.storage, &fixture.roots(), true, Utc::now()).unwrap();
+        assert!(again.items.is_empty());
+    }
+
+    #[test]
+    fn test_zero_retention_days_keeps_everything() {
+        let mut fixture = Fixture::new("forever");
+        fixture.storage.retention_days = 0;
+        seed(&fixture);
+
+        let report = run_with(&fixture.storage, &fixture.roots(), false, Utc::now()).unwrap();
+        assert!(report.items.is_empty());
+        assert_eq!(report.processed, 0);
+    }
+
+    #[test]
+    fn test_storage_outside_roots_is_refused() {
+        let mut fixture = Fixture::new("roots");
+        fixture.storage.allowed_roots = vec![fixture.root.join("elsewhere")];
+
+        assert!(run_with(&fixture.storage, &fixture.roots(), true, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_run_log_keeps_recent_runs() {
+        let fixture = Fixture::new("log");
+        let log = fixture.root.join(RUN_LOG_FILE_NAME);
+        let report = run_with(&fixture.storage, &fixture.roots(), true, Utc::now()).unwrap();
+
+        for _ in 0..MAX_LOGGED_RUNS + 5 {
+            append_run_log(&log, &report).unwrap();
+        }
+        let runs = read_run_log(&log);
+        assert_eq!(runs.len(), MAX_LOGGED_RUNS);
+        assert_eq!(runs[0], report);
+    }
+}
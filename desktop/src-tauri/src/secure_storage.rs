@@ -1,103 +1,592 @@
+use crate::credential_registry::{self, CredentialSource};
+use crate::crypto::{self, EncryptedBlob};
+use crate::i18n::tr;
+use crate::{config, config_bundle, google_oauth, session_vault};
 use keyring::Entry;
-use log::{error, info};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 
 const SERVICE_NAME: &str = "AutoDoc Agent";
+/// Throwaway entry written to find out whether the OS keychain works
+const PROBE_KEY: &str = "__autodoc_keyring_probe";
+const VAULT_FILE_NAME: &str = "secrets.vault";
+const VAULT_VERSION: u32 = 1;
+/// Unlocks the file vault at startup on machines without anyone to type the passphrase
+const VAULT_PASSPHRASE_ENV: &str = "AUTODOC_VAULT_PASSPHRASE";
+
+/// The store secrets currently go to; chosen on first use
+static STORE: RwLock<Option<Arc<dyn SecretStore>>> = RwLock::new(None);
+
+/// Where secrets are kept
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackend {
+    /// macOS Keychain, Windows Credential Manager or the Linux Secret Service
+    Keyring,
+    /// Passphrase-encrypted file next to the config
+    FileVault,
+    #[cfg(test)]
+    Memory,
+}
 
-/// Securely store a credential in the OS keychain
-pub fn store_credential(key: &str, value: &str) -> Result<(), String> {
-    match Entry::new(SERVICE_NAME, key) {
-        Ok(entry) => {
-            match entry.set_password(value) {
-                Ok(_) => {
-                    info!("Credential '{}' stored securely in keychain", key);
-                    Ok(())
-                }
-                Err(e) => {
-                    error!("Failed to store credential '{}': {}", key, e);
-                    Err(format!("Failed to store credential: {}", e))
-                }
-            }
+impl SecretBackend {
+    fn name(self) -> &'static str {
+        match self {
+            SecretBackend::Keyring => "keychain",
+            SecretBackend::FileVault => "file vault",
+            #[cfg(test)]
+            SecretBackend::Memory => "memory",
         }
-        Err(e) => {
+    }
+}
+
+/// `advanced.secret_backend`: which store to use
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackendPreference {
+    /// The OS keychain when it works, otherwise the file vault
+    #[default]
+    Auto,
+    Keyring,
+    FileVault,
+}
+
+/// Key-value storage for credentials
+pub trait SecretStore: Send + Sync {
+    fn backend(&self) -> SecretBackend;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    /// `None` when nothing is stored under `key`
+    fn get(&self, key: &str) -> Result<Option<String>, String>;
+    /// Deleting a missing key is not an error
+    fn delete(&self, key: &str) -> Result<(), String>;
+
+    /// Stores that need a passphrase before use
+    fn is_locked(&self) -> bool {
+        false
+    }
+
+    fn unlock(&self, _passphrase: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// ============= OS Keychain =============
+
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(key: &str) -> Result<Entry, String> {
+        Entry::new(SERVICE_NAME, key).map_err(|e| {
             error!("Failed to create keychain entry for '{}': {}", key, e);
-            Err(format!("Failed to create keychain entry: {}", e))
-        }
+            tr!("secret-entry-failed", error = e)
+        })
     }
 }
 
-/// Retrieve a credential from the OS keychain
-pub fn get_credential(key: &str) -> Result<String, String> {
-    match Entry::new(SERVICE_NAME, key) {
-        Ok(entry) => {
-            match entry.get_password() {
-                Ok(password) => {
-                    info!("Credential '{}' retrieved from keychain", key);
-                    Ok(password)
-                }
-                Err(_e) => {
-                    // Don't log the full error as it might contain sensitive info
-                    Err(format!("Credential not found or inaccessible: {}", key))
-                }
+impl SecretStore for KeyringStore {
+    fn backend(&self) -> SecretBackend {
+        SecretBackend::Keyring
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        Self::entry(key)?.set_password(value).map_err(|e| {
+            error!("Failed to store credential '{}': {}", key, e);
+            tr!("secret-store-failed", error = e)
+        })
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        match Self::entry(key)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            // Don't log the full error as it might contain sensitive info
+            Err(_) => Err(tr!("secret-not-found", key = key)),
+        }
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        match Self::entry(key)?.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => {
+                error!("Failed to delete credential '{}': {}", key, e);
+                Err(tr!("secret-keychain-failed", error = e))
             }
         }
-        Err(e) => {
-            error!("Failed to access keychain for '{}': {}", key, e);
-            Err(format!("Failed to access keychain: {}", e))
+    }
+}
+
+/// Write, read back and remove a throwaway entry
+fn probe_keyring() -> Result<(), String> {
+    let entry = Entry::new(SERVICE_NAME, PROBE_KEY).map_err(|e| e.to_string())?;
+    entry.set_password("probe").map_err(|e| e.to_string())?;
+    let read = entry.get_password().map_err(|e| e.to_string());
+    let _ = entry.delete_password();
+    match read? {
+        value if value == "probe" => Ok(()),
+        _ => Err("keychain returned a different value".to_string()),
+    }
+}
+
+// ============= File Vault =============
+
+/// On-disk layout of the vault; the secrets map is one encrypted JSON object
+#[derive(Debug, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    secrets: EncryptedBlob,
+}
+
+struct UnlockedVault {
+    passphrase: String,
+    secrets: BTreeMap<String, String>,
+}
+
+/// Secrets encrypted with Argon2id and XChaCha20-Poly1305 under a passphrase.
+/// Decrypted once on unlock; every change rewrites the whole file.
+pub struct FileVaultStore {
+    path: PathBuf,
+    unlocked: Mutex<Option<UnlockedVault>>,
+}
+
+impl FileVaultStore {
+    pub fn new(path: PathBuf) -> Self {
+        FileVaultStore {
+            path,
+            unlocked: Mutex::new(None),
+        }
+    }
+
+    fn read(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>, String> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            // The first unlock creates the vault with this passphrase
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(tr!("secret-vault-read-failed", error = e)),
+        };
+        let file: VaultFile = serde_json::from_str(&content)
+            .map_err(|e| tr!("secret-vault-read-failed", error = e))?;
+        if file.version != VAULT_VERSION {
+            return Err(tr!("secret-vault-version", version = file.version));
+        }
+        let plaintext = crypto::decrypt_with_passphrase(&file.secrets, passphrase)
+            .map_err(|_| tr!("secret-vault-wrong-passphrase"))?;
+        serde_json::from_slice(&plaintext).map_err(|e| tr!("secret-vault-read-failed", error = e))
+    }
+
+    fn write(&self, vault: &UnlockedVault) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(&vault.secrets)
+            .map_err(|e| tr!("secret-vault-write-failed", error = e))?;
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            secrets: crypto::encrypt_with_passphrase(&plaintext, &vault.passphrase)?,
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| tr!("secret-vault-write-failed", error = e))?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| tr!("secret-vault-write-failed", error = e))?;
+        }
+        let temp = self.path.with_extension("vault.tmp");
+        std::fs::write(&temp, content)
+            .and_then(|_| restrict_permissions(&temp))
+            .and_then(|_| std::fs::rename(&temp, &self.path))
+            .map_err(|e| tr!("secret-vault-write-failed", error = e))
+    }
+
+    fn with_unlocked<T>(
+        &self,
+        f: impl FnOnce(&mut UnlockedVault) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut unlocked = self.unlocked.lock().unwrap();
+        match unlocked.as_mut() {
+            Some(vault) => f(vault),
+            None => Err(tr!("secret-vault-locked")),
         }
     }
 }
 
-/// Delete a credential from the OS keychain
-pub fn delete_credential(key: &str) -> Result<(), String> {
-    match Entry::new(SERVICE_NAME, key) {
-        Ok(entry) => {
-            match entry.delete_password() {
-                Ok(_) => {
-                    info!("Credential '{}' deleted from keychain", key);
-                    Ok(())
-                }
-                Err(_e) => {
-                    // It's okay if the credential doesn't exist
-                    info!("Credential '{}' was not in keychain", key);
-                    Ok(())
-                }
+impl SecretStore for FileVaultStore {
+    fn backend(&self) -> SecretBackend {
+        SecretBackend::FileVault
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.with_unlocked(|vault| {
+            let previous = vault.secrets.insert(key.to_string(), value.to_string());
+            self.write(vault).inspect_err(|_| {
+                // Keep memory and disk in agreement
+                match previous {
+                    Some(previous) => vault.secrets.insert(key.to_string(), previous),
+                    None => vault.secrets.remove(key),
+                };
+            })
+        })
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        self.with_unlocked(|vault| Ok(vault.secrets.get(key).cloned()))
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.with_unlocked(|vault| match vault.secrets.remove(key) {
+            Some(previous) => self.write(vault).inspect_err(|_| {
+                vault.secrets.insert(key.to_string(), previous);
+            }),
+            None => Ok(()),
+        })
+    }
+
+    fn is_locked(&self) -> bool {
+        self.unlocked.lock().unwrap().is_none()
+    }
+
+    fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        if passphrase.is_empty() {
+            return Err(tr!("secret-vault-passphrase-required"));
+        }
+        let secrets = Self::read(&self.path, passphrase)?;
+        *self.unlocked.lock().unwrap() = Some(UnlockedVault {
+            passphrase: passphrase.to_string(),
+            secrets,
+        });
+        Ok(())
+    }
+}
+
+/// Only the owner may read the vault
+#[cfg(unix)]
+pub(crate) fn restrict_permissions(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn restrict_permissions(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Vault file in the config directory
+fn vault_path() -> Result<PathBuf, String> {
+    Ok(config::config_dir()?.join(VAULT_FILE_NAME))
+}
+
+fn open_vault() -> Result<FileVaultStore, String> {
+    let vault = FileVaultStore::new(vault_path()?);
+    if let Ok(passphrase) = std::env::var(VAULT_PASSPHRASE_ENV) {
+        vault.unlock(&passphrase)?;
+    }
+    Ok(vault)
+}
+
+// ============= In-Memory Store =============
+
+/// Keeps tests away from the real keychain
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryStore {
+    secrets: Mutex<BTreeMap<String, String>>,
+}
+
+#[cfg(test)]
+impl SecretStore for MemoryStore {
+    fn backend(&self) -> SecretBackend {
+        SecretBackend::Memory
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, String> {
+        Ok(self.secrets.lock().unwrap().get(key).cloned())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), String> {
+        self.secrets.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+// ============= Backend Selection =============
+
+/// Open the store `preference` asks for; `Auto` falls back to the vault without a keychain
+fn select(preference: SecretBackendPreference) -> Arc<dyn SecretStore> {
+    let vault = || -> Arc<dyn SecretStore> {
+        match open_vault() {
+            Ok(vault) => Arc::new(vault),
+            Err(e) => {
+                warn!("File vault unavailable, using the keychain: {}", e);
+                Arc::new(KeyringStore)
             }
         }
-        Err(e) => {
-            error!("Failed to access keychain for deletion of '{}': {}", key, e);
-            Err(format!("Failed to access keychain: {}", e))
+    };
+
+    match preference {
+        SecretBackendPreference::Keyring => Arc::new(KeyringStore),
+        SecretBackendPreference::FileVault => vault(),
+        SecretBackendPreference::Auto => match probe_keyring() {
+            Ok(()) => Arc::new(KeyringStore),
+            Err(e) => {
+                warn!("OS keychain unavailable, using the file vault: {}", e);
+                vault()
+            }
+        },
+    }
+}
+
+/// Choose the store from the saved settings; called once at startup
+pub fn init(preference: SecretBackendPreference) {
+    let store = select(preference);
+    info!("Secrets are kept in the {}", store.backend().name());
+    *STORE.write().unwrap() = Some(store);
+}
+
+fn store() -> Arc<dyn SecretStore> {
+    if let Some(store) = STORE.read().unwrap().as_ref() {
+        return store.clone();
+    }
+    let mut slot = STORE.write().unwrap();
+    slot.get_or_insert_with(|| {
+        #[cfg(test)]
+        let store: Arc<dyn SecretStore> = Arc::new(MemoryStore::default());
+        #[cfg(not(test))]
+        let store = select(config::load_settings().advanced.secret_backend);
+        store
+    })
+    .clone()
+}
+
+/// The store credentials are currently kept in
+pub fn active_store() -> Arc<dyn SecretStore> {
+    store()
+}
+
+/// Copy every key in `keys` from one store to another, then remove them from the source.
+/// Nothing is removed unless every copy was read back intact.
+pub fn migrate_secrets(
+    from: &dyn SecretStore,
+    to: &dyn SecretStore,
+    keys: &[String],
+) -> Result<usize, String> {
+    let mut moved = Vec::new();
+    for key in keys {
+        let Some(value) = from.get(key)? else {
+            continue;
+        };
+        to.set(key, &value)?;
+        if to.get(key)?.as_deref() != Some(value.as_str()) {
+            return Err(tr!("secret-migration-verify-failed", key = key.clone()));
         }
+        moved.push(key);
     }
+    for key in &moved {
+        if let Err(e) = from.delete(key) {
+            warn!("Failed to remove migrated credential '{}': {}", key, e);
+        }
+    }
+    Ok(moved.len())
 }
 
-/// Check if a credential exists in the keychain
-pub fn has_credential(key: &str) -> bool {
-    match Entry::new(SERVICE_NAME, key) {
-        Ok(entry) => entry.get_password().is_ok(),
-        Err(_) => false,
+/// Every keychain entry the app writes for `config`
+pub(crate) fn known_secret_keys(config: &config::AppConfig) -> Vec<String> {
+    let mut keys = config_bundle::exportable_secret_keys(config);
+    keys.push(google_oauth::TOKEN_KEY.to_string());
+    keys.push(session_vault::VAULT_KEY.to_string());
+    keys
+}
+
+// ============= Diagnostics =============
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SecretStoreDiagnostics {
+    pub preference: SecretBackendPreference,
+    pub active: SecretBackend,
+    /// Whether the active store needs a passphrase before secrets can be used
+    pub locked: bool,
+    pub keyring_available: bool,
+    /// Raw error from the keychain probe
+    pub keyring_error: Option<String>,
+    /// Likely causes of a keychain failure, in plain words
+    pub reasons: Vec<String>,
+    pub vault_path: Option<PathBuf>,
+    pub vault_exists: bool,
+}
+
+/// Plain explanations for a failed keychain probe
+fn explain_keyring_error(error: &str) -> Vec<String> {
+    let mut reasons = Vec::new();
+    let lower = error.to_lowercase();
+
+    if cfg!(target_os = "linux") {
+        if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+            reasons.push(tr!("secret-diag-no-dbus"));
+        }
+        if lower.contains("org.freedesktop.secrets") || lower.contains("serviceunknown") {
+            reasons.push(tr!("secret-diag-no-secret-service"));
+        }
+    }
+    if lower.contains("locked") {
+        reasons.push(tr!("secret-diag-keyring-locked"));
+    }
+    if reasons.is_empty() {
+        reasons.push(tr!("secret-diag-keyring-error", error = error.to_string()));
     }
+    reasons
 }
 
-// Tauri commands
+// ============= Credential API =============
 
-#[tauri::command]
-pub fn store_secure_credential(key: String, value: String) -> Result<(), String> {
-    store_credential(&key, &value)
+/// Securely store a credential in the active secret store
+pub fn store_credential(key: &str, value: &str) -> Result<(), String> {
+    store_credential_from(key, value, CredentialSource::Settings)
+}
+
+/// Store a credential, recording where its value came from
+pub fn store_credential_from(
+    key: &str,
+    value: &str,
+    source: CredentialSource,
+) -> Result<(), String> {
+    store_credential_in(&*store(), key, value, source)
+}
+
+/// Store a credential in `store`, recording where its value came from
+pub fn store_credential_in(
+    store: &dyn SecretStore,
+    key: &str,
+    value: &str,
+    source: CredentialSource,
+) -> Result<(), String> {
+    // Saving the same value again is not a rotation
+    let unchanged = matches!(store.get(key), Ok(Some(ref current)) if current == value);
+    store.set(key, value)?;
+    if !unchanged {
+        credential_registry::record_updated(key, source);
+    }
+    info!(
+        "Credential '{}' stored securely in {}",
+        key,
+        store.backend().name()
+    );
+    Ok(())
 }
 
+/// Retrieve a credential from the active secret store
+pub fn get_credential(key: &str) -> Result<String, String> {
+    match store().get(key)? {
+        Some(value) => Ok(value),
+        None => Err(tr!("secret-not-found", key = key)),
+    }
+}
+
+/// Retrieve a credential, telling a missing one (`None`) apart from a store that
+/// cannot be read, e.g. a locked file vault
+pub fn find_credential(key: &str) -> Result<Option<String>, String> {
+    store().get(key)
+}
+
+/// Delete a credential; a missing credential is not an error
+pub fn delete_credential(key: &str) -> Result<(), String> {
+    let store = store();
+    store.delete(key)?;
+    credential_registry::forget(key);
+    info!(
+        "Credential '{}' deleted from {}",
+        key,
+        store.backend().name()
+    );
+    Ok(())
+}
+
+/// Check if a credential exists in the active secret store
+pub fn has_credential(key: &str) -> bool {
+    matches!(store().get(key), Ok(Some(_)))
+}
+
+// Tauri commands; reading and writing single credentials goes through `credential_registry`
+
+/// Which store is in use and, when the keychain is unusable, why
 #[tauri::command]
-pub fn get_secure_credential(key: String) -> Result<String, String> {
-    get_credential(&key)
+pub fn get_secret_store_diagnostics() -> SecretStoreDiagnostics {
+    let store = store();
+    let keyring_error = probe_keyring().err();
+    let vault_path = vault_path().ok();
+
+    SecretStoreDiagnostics {
+        preference: config::load_settings().advanced.secret_backend,
+        active: store.backend(),
+        locked: store.is_locked(),
+        keyring_available: keyring_error.is_none(),
+        reasons: keyring_error
+            .as_deref()
+            .map(explain_keyring_error)
+            .unwrap_or_default(),
+        keyring_error,
+        vault_exists: vault_path.as_deref().is_some_and(Path::exists),
+        vault_path,
+    }
 }
 
+/// Unlock the file vault for this session
 #[tauri::command]
-pub fn delete_secure_credential(key: String) -> Result<(), String> {
-    delete_credential(&key)
+pub fn unlock_secret_store(passphrase: String) -> Result<(), String> {
+    store().unlock(&passphrase)
 }
 
+/// Move every known secret into another store and make it the saved choice
 #[tauri::command]
-pub fn has_secure_credential(key: String) -> Result<bool, String> {
-    Ok(has_credential(&key))
+pub fn migrate_secret_store(
+    target: SecretBackendPreference,
+    passphrase: Option<String>,
+) -> Result<usize, String> {
+    let source = store();
+    if source.is_locked() {
+        return Err(tr!("secret-vault-locked"));
+    }
+
+    let destination: Arc<dyn SecretStore> = match target {
+        SecretBackendPreference::Keyring => {
+            probe_keyring().map_err(|e| tr!("secret-keychain-failed", error = e))?;
+            Arc::new(KeyringStore)
+        }
+        SecretBackendPreference::FileVault => {
+            let vault = FileVaultStore::new(vault_path()?);
+            vault.unlock(passphrase.as_deref().unwrap_or_default())?;
+            Arc::new(vault)
+        }
+        SecretBackendPreference::Auto => select(SecretBackendPreference::Auto),
+    };
+
+    let mut settings = config::load_settings();
+    let migrated = if destination.backend() == source.backend() {
+        0
+    } else {
+        migrate_secrets(
+            source.as_ref(),
+            destination.as_ref(),
+            &known_secret_keys(&settings),
+        )?
+    };
+    info!(
+        "Migrated {} credentials from {} to {}",
+        migrated,
+        source.backend().name(),
+        destination.backend().name()
+    );
+    *STORE.write().unwrap() = Some(destination);
+
+    settings.advanced.secret_backend = target;
+    config::save_config(settings)?;
+    Ok(migrated)
 }
 
 /// Migrate plaintext credentials to secure storage
@@ -105,7 +594,7 @@ pub fn migrate_credential_to_keychain(key: &str, plaintext_value: Option<String>
     if let Some(value) = plaintext_value {
         if !value.is_empty() {
             info!("Migrating credential '{}' to keychain", key);
-            store_credential(key, &value)?;
+            store_credential_from(key, &value, CredentialSource::Migration)?;
             return Ok(true); // Migration performed
         }
     }
@@ -289,78 +778,6 @@ mod tests {
         let _ = delete_credential(test_key);
     }
 
-    #[test]
-    fn test_tauri_command_store() {
-        let test_key = "test_tauri_store".to_string();
-        let test_value = "test_value".to_string();
-
-        // Test Tauri command wrapper
-        let result = store_secure_credential(test_key.clone(), test_value.clone());
-        assert!(result.is_ok());
-
-        // Verify via direct function
-        let retrieved = get_credential(&test_key).unwrap();
-        assert_eq!(retrieved, test_value);
-
-        // Cleanup
-        let _ = delete_credential(&test_key);
-    }
-
-    #[test]
-    fn test_tauri_command_get() {
-        let test_key = "test_tauri_get".to_string();
-        let test_value = "test_value".to_string();
-
-        // Store via direct function
-        let _ = store_credential(&test_key, &test_value);
-
-        // Test Tauri command wrapper
-        let result = get_secure_credential(test_key.clone());
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), test_value);
-
-        // Cleanup
-        let _ = delete_credential(&test_key);
-    }
-
-    #[test]
-    fn test_tauri_command_delete() {
-        let test_key = "test_tauri_delete".to_string();
-        let test_value = "test_value".to_string();
-
-        // Store credential
-        let _ = store_credential(&test_key, &test_value);
-
-        // Test Tauri command wrapper
-        let result = delete_secure_credential(test_key.clone());
-        assert!(result.is_ok());
-
-        // Verify deletion
-        assert!(!has_credential(&test_key));
-    }
-
-    #[test]
-    fn test_tauri_command_has() {
-        let test_key = "test_tauri_has".to_string();
-        let test_value = "test_value".to_string();
-
-        // Should not exist initially
-        let result = has_secure_credential(test_key.clone());
-        assert!(result.is_ok());
-        assert!(!result.unwrap());
-
-        // Store credential
-        let _ = store_credential(&test_key, &test_value);
-
-        // Should exist now
-        let result = has_secure_credential(test_key.clone());
-        assert!(result.is_ok());
-        assert!(result.unwrap());
-
-        // Cleanup
-        let _ = delete_credential(&test_key);
-    }
-
     #[test]
     fn test_migrate_credential_with_value() {
         let test_key = "test_migrate";
@@ -451,4 +868,94 @@ mod tests {
         // Cleanup
         let _ = delete_credential(test_key);
     }
+
+    fn vault_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-secure-storage-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_tests_use_memory_store() {
+        assert_eq!(store().backend(), SecretBackend::Memory);
+    }
+
+    #[test]
+    fn test_file_vault_roundtrip_and_lock() {
+        let dir = vault_dir("vault");
+        let path = dir.join(VAULT_FILE_NAME);
+
+        let vault = FileVaultStore::new(path.clone());
+        assert!(vault.is_locked());
+        assert!(vault.set("claude_api_key", "sk-ant-x").is_err());
+
+        vault.unlock("correct horse").unwrap();
+        vault.set("claude_api_key", "sk-ant-x").unwrap();
+        vault.set("proxy_password", "hunter2").unwrap();
+        vault.delete("proxy_password").unwrap();
+        vault.delete("never_stored").unwrap();
+
+        // Nothing readable on disk
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-ant-x"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let reopened = FileVaultStore::new(path.clone());
+        assert!(reopened.unlock("wrong").is_err());
+        assert!(reopened.is_locked());
+        reopened.unlock("correct horse").unwrap();
+        assert_eq!(
+            reopened.get("claude_api_key").unwrap().as_deref(),
+            Some("sk-ant-x")
+        );
+        assert_eq!(reopened.get("proxy_password").unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_migrate_secrets_between_stores() {
+        let dir = vault_dir("migrate");
+        let source = MemoryStore::default();
+        source.set("claude_api_key", "sk-ant-x").unwrap();
+        source.set("target_secret:crm", "token").unwrap();
+        source.set("unrelated", "stays").unwrap();
+
+        let vault = FileVaultStore::new(dir.join(VAULT_FILE_NAME));
+        vault.unlock("pass").unwrap();
+        let keys = vec![
+            "claude_api_key".to_string(),
+            "target_secret:crm".to_string(),
+            "proxy_password".to_string(),
+        ];
+        assert_eq!(migrate_secrets(&source, &vault, &keys).unwrap(), 2);
+
+        assert_eq!(
+            vault.get("target_secret:crm").unwrap().as_deref(),
+            Some("token")
+        );
+        assert_eq!(source.get("claude_api_key").unwrap(), None);
+        assert_eq!(source.get("unrelated").unwrap().as_deref(), Some("stays"));
+
+        // A locked destination leaves the source untouched
+        let locked = FileVaultStore::new(dir.join("other.vault"));
+        assert!(migrate_secrets(&source, &locked, &["unrelated".to_string()]).is_err());
+        assert_eq!(source.get("unrelated").unwrap().as_deref(), Some("stays"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_explain_keyring_error() {
+        let reasons =
+            explain_keyring_error("Platform secure storage failure: collection is locked");
+        assert!(reasons.contains(&tr!("secret-diag-keyring-locked")));
+        assert!(!explain_keyring_error("boom").is_empty());
+    }
 }
@@ -0,0 +1,576 @@
+use crate::config::{self, AppConfig};
+use crate::credential_registry::CredentialSource;
+use crate::crypto::{self, EncryptedBlob, SealedBlob, KEY_LEN};
+use crate::i18n::tr;
+use crate::{secure_storage, targets};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Secret store entry holding the key every saved session is encrypted with
+pub const VAULT_KEY: &str = "session_vault_key";
+const SESSIONS_DIR_NAME: &str = "sessions";
+const SESSION_FILE_EXTENSION: &str = "session";
+const SESSION_FILE_VERSION: u32 = 1;
+const EXPORT_FORMAT: &str = "autodoc-session";
+const EXPORT_VERSION: u32 = 1;
+
+/// Two first captures at once must not each create a key
+static KEY_LOCK: Mutex<()> = Mutex::new(());
+
+/// A cookie as reported by the DevTools protocol (`Network.getAllCookies`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+    /// Seconds since the epoch; -1 for a session cookie
+    #[serde(default = "default_cookie_expires")]
+    pub expires: f64,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub same_site: Option<String>,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+fn default_cookie_expires() -> f64 {
+    -1.0
+}
+
+impl SessionCookie {
+    /// `None` for session cookies, which live as long as the browser
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        (self.expires > 0.0).then(|| {
+            DateTime::from_timestamp(self.expires as i64, 0).unwrap_or(DateTime::<Utc>::MAX_UTC)
+        })
+    }
+}
+
+/// Login state of a target app captured from the Chrome MCP browser
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserSession {
+    /// Origin the local storage belongs to, e.g. `https://crm.example.com`
+    pub origin: String,
+    pub cookies: Vec<SessionCookie>,
+    #[serde(default)]
+    pub local_storage: BTreeMap<String, String>,
+}
+
+impl BrowserSession {
+    /// When the last persistent cookie expires; `None` with session cookies only
+    fn cookies_expire_at(&self) -> Option<DateTime<Utc>> {
+        self.cookies
+            .iter()
+            .filter_map(SessionCookie::expires_at)
+            .max()
+    }
+}
+
+/// On-disk layout; only the session itself is encrypted so listing needs no key
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionFile {
+    version: u32,
+    target_id: String,
+    captured_at: DateTime<Utc>,
+    cookies_expire_at: Option<DateTime<Utc>>,
+    session: SealedBlob,
+}
+
+impl SessionFile {
+    /// The earlier of the cookie expiry and the configured maximum age; 0 hours means no age limit
+    fn expires_at(&self, max_age_hours: u32) -> Option<DateTime<Utc>> {
+        let by_age = (max_age_hours > 0)
+            .then(|| self.captured_at + ChronoDuration::hours(max_age_hours.into()));
+        match (by_age, self.cookies_expire_at) {
+            (Some(age), Some(cookies)) => Some(age.min(cookies)),
+            (age, cookies) => age.or(cookies),
+        }
+    }
+}
+
+/// A saved session as shown in the UI, without cookies or storage values
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub target_id: String,
+    pub captured_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+}
+
+/// Portable copy of one session, encrypted with a passphrase instead of the machine's key
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionExport {
+    format: String,
+    version: u32,
+    target_id: String,
+    captured_at: DateTime<Utc>,
+    session: EncryptedBlob,
+}
+
+// ============= Vault Key =============
+
+/// The vault key, created on first capture; `None` before anything was saved.
+/// A store that cannot be read is an error rather than a missing key, so saved
+/// sessions are neither dropped nor orphaned by a new key.
+fn vault_key(create: bool) -> Result<Option<[u8; KEY_LEN]>, String> {
+    let _guard = KEY_LOCK.lock().unwrap();
+    if let Some(encoded) = secure_storage::find_credential(VAULT_KEY)? {
+        let key = BASE64
+            .decode(encoded)
+            .ok()
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .ok_or_else(|| tr!("session-key-invalid"))?;
+        return Ok(Some(key));
+    }
+    if !create {
+        return Ok(None);
+    }
+
+    let key = crypto::generate_key();
+    secure_storage::store_credential_from(VAULT_KEY, &BASE64.encode(key), CredentialSource::App)?;
+    info!("Created session vault key");
+    Ok(Some(key))
+}
+
+// ============= Session Files =============
+
+#[cfg(not(test))]
+fn sessions_dir() -> Result<PathBuf, String> {
+    Ok(config::config_dir()?.join(SESSIONS_DIR_NAME))
+}
+
+/// Tests never touch the user's config directory
+#[cfg(test)]
+fn sessions_dir() -> Result<PathBuf, String> {
+    Ok(std::env::temp_dir()
+        .join("autodoc-session-vault-test")
+        .join(SESSIONS_DIR_NAME))
+}
+
+/// Target IDs become file names, so only well-formed ones are accepted
+fn session_path(dir: &Path, target_id: &str) -> Result<PathBuf, String> {
+    if !targets::is_valid_id(target_id) {
+        return Err(tr!("session-target-invalid", target = target_id));
+    }
+    Ok(dir.join(format!("{}.{}", target_id, SESSION_FILE_EXTENSION)))
+}
+
+fn read_file(path: &Path) -> Result<Option<SessionFile>, String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(tr!("session-read-failed", error = e)),
+    };
+    let file: SessionFile =
+        serde_json::from_str(&content).map_err(|e| tr!("session-read-failed", error = e))?;
+    if file.version != SESSION_FILE_VERSION {
+        return Err(tr!("session-version-unsupported", version = file.version));
+    }
+    Ok(Some(file))
+}
+
+fn write_file(path: &Path, file: &SessionFile) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(file).map_err(|e| tr!("session-write-failed", error = e))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| tr!("session-write-failed", error = e))?;
+    }
+    let temp = path.with_extension("session.tmp");
+    std::fs::write(&temp, content)
+        .and_then(|_| secure_storage::restrict_permissions(&temp))
+        .and_then(|_| std::fs::rename(&temp, path))
+        .map_err(|e| tr!("session-write-failed", error = e))
+}
+
+fn remove_file(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(tr!("session-delete-failed", error = e)),
+    }
+}
+
+fn save_in(
+    dir: &Path,
+    target_id: &str,
+    session: &BrowserSession,
+    captured_at: DateTime<Utc>,
+) -> Result<(), String> {
+    let path = session_path(dir, target_id)?;
+    let key = vault_key(true)?.ok_or_else(|| tr!("session-key-invalid"))?;
+    let plaintext =
+        serde_json::to_vec(session).map_err(|e| tr!("session-write-failed", error = e))?;
+
+    write_file(
+        &path,
+        &SessionFile {
+            version: SESSION_FILE_VERSION,
+            target_id: target_id.to_string(),
+            captured_at,
+            cookies_expire_at: session.cookies_expire_at(),
+            session: crypto::encrypt_with_key(&plaintext, &key)?,
+        },
+    )?;
+    info!(
+        "Saved browser session for target '{}' ({} cookies)",
+        target_id,
+        session.cookies.len()
+    );
+    Ok(())
+}
+
+/// The saved session if it is still usable, without cookies that expired on their own.
+/// Expired or undecryptable sessions are removed.
+fn load_in(
+    dir: &Path,
+    target_id: &str,
+    max_age_hours: u32,
+    now: DateTime<Utc>,
+) -> Result<Option<(DateTime<Utc>, BrowserSession)>, String> {
+    let path = session_path(dir, target_id)?;
+    let Some(file) = read_file(&path)? else {
+        return Ok(None);
+    };
+
+    if file.expires_at(max_age_hours).is_some_and(|at| at <= now) {
+        info!("Browser session for target '{}' expired", target_id);
+        remove_file(&path)?;
+        return Ok(None);
+    }
+
+    let Some(key) = vault_key(false)? else {
+        warn!(
+            "Session vault key missing; dropping session for '{}'",
+            target_id
+        );
+        remove_file(&path)?;
+        return Ok(None);
+    };
+    let mut session: BrowserSession = match crypto::decrypt_with_key(&file.session, &key)
+        .and_then(|plaintext| serde_json::from_slice(&plaintext).map_err(|e| e.to_string()))
+    {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("Dropping unreadable session for '{}': {}", target_id, e);
+            remove_file(&path)?;
+            return Ok(None);
+        }
+    };
+
+    session
+        .cookies
+        .retain(|cookie| !matches!(cookie.expires_at(), Some(at) if at <= now));
+    Ok(Some((file.captured_at, session)))
+}
+
+fn list_in(
+    dir: &Path,
+    target_ids: &[String],
+    max_age_hours: u32,
+    now: DateTime<Utc>,
+) -> Vec<SessionSummary> {
+    target_ids
+        .iter()
+        .filter_map(|id| {
+            let file = session_path(dir, id).and_then(|path| read_file(&path));
+            match file {
+                Ok(file) => file,
+                Err(e) => {
+                    warn!("Skipping session for '{}': {}", id, e);
+                    None
+                }
+            }
+        })
+        .map(|file| {
+            let expires_at = file.expires_at(max_age_hours);
+            SessionSummary {
+                expired: expires_at.is_some_and(|at| at <= now),
+                expires_at,
+                captured_at: file.captured_at,
+                target_id: file.target_id,
+            }
+        })
+        .collect()
+}
+
+fn export_from(
+    dir: &Path,
+    target_id: &str,
+    max_age_hours: u32,
+    passphrase: &str,
+) -> Result<String, String> {
+    let (captured_at, session) = load_in(dir, target_id, max_age_hours, Utc::now())?
+        .ok_or_else(|| tr!("session-not-found", target = target_id))?;
+    let plaintext =
+        serde_json::to_vec(&session).map_err(|e| tr!("session-write-failed", error = e))?;
+
+    let export = SessionExport {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        target_id: target_id.to_string(),
+        captured_at,
+        session: crypto::encrypt_with_passphrase(&plaintext, passphrase)?,
+    };
+    serde_json::to_string_pretty(&export).map_err(|e| tr!("session-write-failed", error = e))
+}
+
+/// Store an exported session under `target_id`, or the target it was exported from
+fn import_into(
+    dir: &Path,
+    content: &str,
+    passphrase: &str,
+    target_id: Option<&str>,
+) -> Result<String, String> {
+    let export: SessionExport =
+        serde_json::from_str(content).map_err(|e| tr!("session-read-failed", error = e))?;
+    if export.format != EXPORT_FORMAT {
+        return Err(tr!("session-read-failed", error = export.format));
+    }
+    if export.version > EXPORT_VERSION {
+        return Err(tr!("session-version-unsupported", version = export.version));
+    }
+
+    let plaintext = crypto::decrypt_with_passphrase(&export.session, passphrase)?;
+    let session: BrowserSession =
+        serde_json::from_slice(&plaintext).map_err(|e| tr!("session-read-failed", error = e))?;
+    let target_id = target_id.unwrap_or(&export.target_id).to_string();
+    save_in(dir, &target_id, &session, export.captured_at)?;
+    Ok(target_id)
+}
+
+fn require_target(config: &AppConfig, target_id: &str) -> Result<(), String> {
+    targets::find_target(&config.auth, target_id)
+        .map(|_| ())
+        .ok_or_else(|| tr!("session-target-invalid", target = target_id))
+}
+
+// ============= Public API =============
+
+/// Save the session captured for a configured target
+pub fn capture(target_id: &str, session: &BrowserSession) -> Result<(), String> {
+    require_target(&config::load_settings(), target_id)?;
+    save_in(&sessions_dir()?, target_id, session, Utc::now())
+}
+
+/// Session to restore before exploring a target, if one is still valid
+pub fn restore(target_id: &str) -> Result<Option<BrowserSession>, String> {
+    let max_age = config::load_settings().auth.session_max_age_hours;
+    Ok(load_in(&sessions_dir()?, target_id, max_age, Utc::now())?.map(|(_, session)| session))
+}
+
+/// Forget a target's session, e.g. when the app logged it out
+pub fn invalidate(target_id: &str) -> Result<(), String> {
+    remove_file(&session_path(&sessions_dir()?, target_id)?)?;
+    info!("Invalidated browser session for target '{}'", target_id);
+    Ok(())
+}
+
+// ============= Tauri Commands =============
+
+#[tauri::command]
+pub fn list_sessions() -> Result<Vec<SessionSummary>, String> {
+    let settings = config::load_settings();
+    let ids: Vec<String> = settings.auth.targets.iter().map(|t| t.id.clone()).collect();
+    Ok(list_in(
+        &sessions_dir()?,
+        &ids,
+        settings.auth.session_max_age_hours,
+        Utc::now(),
+    ))
+}
+
+#[tauri::command]
+pub fn invalidate_session(target_id: String) -> Result<(), String> {
+    invalidate(&target_id)
+}
+
+#[tauri::command]
+pub fn export_session(target_id: String, path: PathBuf, passphrase: String) -> Result<(), String> {
+    let output = config::validate_path(&path)?;
+    if passphrase.is_empty() {
+        return Err(tr!("bundle-export-password-required"));
+    }
+    let max_age = config::load_settings().auth.session_max_age_hours;
+    let content = export_from(&sessions_dir()?, &target_id, max_age, &passphrase)?;
+    std::fs::write(&output, content).map_err(|e| tr!("session-write-failed", error = e))?;
+    info!("Exported browser session for target '{}'", target_id);
+    Ok(())
+}
+
+/// Returns the target the session was stored under
+#[tauri::command]
+pub fn import_session(
+    path: PathBuf,
+    passphrase: String,
+    target_id: Option<String>,
+) -> Result<String, String> {
+    let input = config::validate_path(&path)?;
+    let content =
+        std::fs::read_to_string(&input).map_err(|e| tr!("session-read-failed", error = e))?;
+    let settings = config::load_settings();
+    let dir = sessions_dir()?;
+
+    // Check the target before anything is written
+    let export: SessionExport =
+        serde_json::from_str(&content).map_err(|e| tr!("session-read-failed", error = e))?;
+    require_target(&settings, target_id.as_deref().unwrap_or(&export.target_id))?;
+
+    let target_id = import_into(&dir, &content, &passphrase, target_id.as_deref())?;
+    info!("Imported browser session for target '{}'", target_id);
+    Ok(target_id)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cookie(name: &str, expires: f64) -> SessionCookie {
+        SessionCookie {
+            name: name.to_string(),
+            value: format!("{}-value", name),
+            domain: "crm.example.com".to_string(),
+            path: "/".to_string(),
+            expires,
+            http_only: true,
+            secure: true,
+            same_site: Some("Lax".to_string()),
+        }
+    }
+
+    fn session(cookies: Vec<SessionCookie>) -> BrowserSession {
+        BrowserSession {
+            origin: "https://crm.example.com".to_string(),
+            cookies,
+            local_storage: BTreeMap::from([("token".to_string(), "jwt".to_string())]),
+        }
+    }
+
+    fn timestamp(at: DateTime<Utc>) -> f64 {
+        at.timestamp() as f64
+    }
+
+    #[test]
+    fn test_parses_devtools_cookies() {
+        let raw = r#"{
+            "origin": "https://crm.example.com",
+            "cookies": [{"name": "sid", "value": "abc", "domain": ".example.com",
+                         "path": "/", "expires": -1, "size": 6, "httpOnly": true,
+                         "secure": true, "session": true, "sameSite": "Strict"}],
+            "localStorage": {"theme": "dark"}
+        }"#;
+        let session: BrowserSession = serde_json::from_str(raw).unwrap();
+        assert!(session.cookies[0].http_only);
+        assert_eq!(session.cookies[0].expires_at(), None);
+        assert_eq!(session.local_storage["theme"], "dark");
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = sessions_dir().unwrap();
+        let now = Utc::now();
+        let saved = session(vec![
+            cookie("sid", -1.0),
+            cookie("remember", timestamp(now + ChronoDuration::days(30))),
+            cookie("stale", timestamp(now - ChronoDuration::hours(1))),
+        ]);
+        save_in(&dir, "vault-roundtrip", &saved, now).unwrap();
+
+        // Nothing readable on disk
+        let raw = std::fs::read_to_string(session_path(&dir, "vault-roundtrip").unwrap()).unwrap();
+        assert!(!raw.contains("sid-value"));
+        assert!(!raw.contains("jwt"));
+
+        let (captured_at, loaded) = load_in(&dir, "vault-roundtrip", 24, now).unwrap().unwrap();
+        assert_eq!(captured_at, now);
+        let names: Vec<&str> = loaded.cookies.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["sid", "remember"]);
+        assert_eq!(loaded.local_storage, saved.local_storage);
+
+        remove_file(&session_path(&dir, "vault-roundtrip").unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_expired_sessions_are_dropped() {
+        let dir = sessions_dir().unwrap();
+        let now = Utc::now();
+
+        // Too old for the configured maximum age
+        save_in(
+            &dir,
+            "vault-old",
+            &session(vec![cookie("sid", -1.0)]),
+            now - ChronoDuration::hours(25),
+        )
+        .unwrap();
+        let summaries = list_in(&dir, &["vault-old".to_string()], 24, now);
+        assert!(summaries[0].expired);
+        assert_eq!(load_in(&dir, "vault-old", 24, now).unwrap(), None);
+        assert!(!session_path(&dir, "vault-old").unwrap().exists());
+
+        // Every persistent cookie expired, whatever the age limit
+        let cookies = vec![cookie(
+            "remember",
+            timestamp(now - ChronoDuration::minutes(1)),
+        )];
+        save_in(
+            &dir,
+            "vault-cookies",
+            &session(cookies),
+            now - ChronoDuration::hours(2),
+        )
+        .unwrap();
+        assert_eq!(load_in(&dir, "vault-cookies", 0, now).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_target_ids_rejected() {
+        let dir = sessions_dir().unwrap();
+        assert!(session_path(&dir, "../escape").is_err());
+        assert!(save_in(&dir, "", &session(Vec::new()), Utc::now()).is_err());
+        assert!(invalidate("a/b").is_err());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let dir = sessions_dir().unwrap();
+        let now = Utc::now();
+        save_in(
+            &dir,
+            "vault-export",
+            &session(vec![cookie("sid", -1.0)]),
+            now,
+        )
+        .unwrap();
+
+        let exported = export_from(&dir, "vault-export", 24, "correct horse").unwrap();
+        assert!(!exported.contains("sid-value"));
+        assert!(import_into(&dir, &exported, "wrong", None).is_err());
+
+        let target = import_into(&dir, &exported, "correct horse", Some("vault-imported")).unwrap();
+        assert_eq!(target, "vault-imported");
+        let (captured_at, imported) = load_in(&dir, "vault-imported", 24, now).unwrap().unwrap();
+        assert_eq!(captured_at.timestamp(), now.timestamp());
+        assert_eq!(imported.cookies[0].value, "sid-value");
+
+        for id in ["vault-export", "vault-imported"] {
+            remove_file(&session_path(&dir, id).unwrap()).unwrap();
+        }
+    }
+}
@@ -1,37 +1,52 @@
-use log::{error, info};
+use log::{error, info, warn};
 use std::process::{Child, Command as StdCommand, Stdio};
 use std::sync::Mutex;
 use std::path::PathBuf;
-use tauri::{State, AppHandle, Manager};
+use std::time::Instant;
+use tauri::{State, AppHandle, Manager, Runtime};
+use crate::{config, http_client};
+use crate::api_gateway::GatewayEndpoint;
+use crate::i18n::tr;
+use crate::telemetry::{self, TelemetryEvent};
 
 pub struct BackendProcess {
     child: Mutex<Option<Child>>,
+    started_at: Mutex<Option<Instant>>,
+    /// Script and port of the last start, so a suspended backend can be resumed
+    launch: Mutex<Option<(PathBuf, u16)>>,
 }
 
 impl BackendProcess {
     pub fn new() -> Self {
         BackendProcess {
             child: Mutex::new(None),
+            started_at: Mutex::new(None),
+            launch: Mutex::new(None),
         }
     }
 
-    pub fn start(&self, backend_path: PathBuf, port: u16) -> Result<(), String> {
+    pub fn start(
+        &self,
+        backend_path: PathBuf,
+        port: u16,
+        env: &[(&'static str, String)],
+    ) -> Result<(), String> {
         info!("啟動 Node.js Backend Sidecar on port {}", port);
 
         // Validate port range (non-privileged ports only)
         if port < 1024 || port > 65535 {
-            return Err("Port must be between 1024 and 65535".to_string());
+            return Err(tr!("sidecar-port-range"));
         }
 
         // 檢查是否已經在運行
         let mut child_lock = self.child.lock().unwrap();
         if child_lock.is_some() {
-            return Err("Backend 已經在運行".to_string());
+            return Err(tr!("sidecar-already-running"));
         }
 
         // Verify backend file exists
         if !backend_path.exists() {
-            return Err(format!("Backend file not found: {:?}", backend_path));
+            return Err(tr!("sidecar-backend-missing", path = backend_path.display()));
         }
 
         // 啟動後端進程 (使用絕對路徑)
@@ -39,12 +54,15 @@ impl BackendProcess {
             .arg(&backend_path)
             .arg("--port")
             .arg(port.to_string())
+            .envs(env.iter().map(|(name, value)| (name, value)))
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .map_err(|e| format!("啟動後端失敗: {}", e))?;
+            .map_err(|e| tr!("sidecar-start-failed", error = e))?;
 
         *child_lock = Some(child);
+        *self.started_at.lock().unwrap() = Some(Instant::now());
+        *self.launch.lock().unwrap() = Some((backend_path, port));
 
         // 等待後端啟動
         std::thread::sleep(std::time::Duration::from_secs(2));
@@ -64,26 +82,82 @@ impl BackendProcess {
                 }
                 Err(e) => {
                     error!("停止後端失敗: {}", e);
-                    Err(format!("停止後端失敗: {}", e))
+                    Err(tr!("sidecar-stop-failed", error = e))
                 }
             }
         } else {
-            Err("Backend 未在運行".to_string())
+            Err(tr!("sidecar-not-running"))
         }
     }
 
-    pub fn restart(&self, backend_path: PathBuf, port: u16) -> Result<(), String> {
+    pub fn restart(
+        &self,
+        backend_path: PathBuf,
+        port: u16,
+        env: &[(&'static str, String)],
+    ) -> Result<(), String> {
         self.stop().ok(); // 嘗試停止，忽略錯誤
         std::thread::sleep(std::time::Duration::from_secs(1));
-        self.start(backend_path, port)
+        self.start(backend_path, port, env)
+    }
+
+    /// Stop the backend if it is running, returning how to start it again
+    pub fn suspend(&self) -> Result<Option<(PathBuf, u16)>, String> {
+        if !self.is_running() {
+            return Ok(None);
+        }
+        self.stop()?;
+        Ok(self.launch.lock().unwrap().clone())
     }
 
     pub fn is_running(&self) -> bool {
-        let child_lock = self.child.lock().unwrap();
+        let mut child_lock = self.child.lock().unwrap();
+
+        // A backend that exited on its own has crashed; reap it so it can be started again
+        if let Some(Ok(Some(status))) = child_lock.as_mut().map(|child| child.try_wait()) {
+            warn!("Backend exited unexpectedly: {}", status);
+            let uptime_secs = self
+                .started_at
+                .lock()
+                .unwrap()
+                .take()
+                .map(|started| started.elapsed().as_secs())
+                .unwrap_or(0);
+            telemetry::record(TelemetryEvent::BackendCrash {
+                exit_code: status.code(),
+                uptime_secs,
+            });
+            *child_lock = None;
+        }
+
         child_lock.is_some()
     }
 }
 
+/// The backend reaches the LLM through the local gateway and never sees the real key
+fn backend_env<R: Runtime>(app_handle: &AppHandle<R>) -> Vec<(&'static str, String)> {
+    match app_handle.try_state::<GatewayEndpoint>() {
+        Some(gateway) => gateway.backend_env(&config::load_settings()),
+        None => {
+            warn!("API gateway is not running; backend starts without Claude access");
+            Vec::new()
+        }
+    }
+}
+
+/// Start a backend stopped by `BackendProcess::suspend` again
+pub fn resume<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    backend: &BackendProcess,
+    (backend_path, port): (PathBuf, u16),
+) -> Result<(), String> {
+    backend.start(backend_path, port, &backend_env(app_handle))
+}
+
+fn record_start_failure(message: &str) {
+    telemetry::record_error("backend_start_failed".to_string(), Some(message.to_string()));
+}
+
 #[tauri::command]
 pub fn start_backend(
     app_handle: AppHandle,
@@ -98,7 +172,7 @@ pub fn start_backend(
     let backend_path = if cfg!(debug_assertions) {
         // Development mode: relative to current directory
         std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?
+            .map_err(|e| tr!("sidecar-current-dir-failed", error = e))?
             .join("backend")
             .join("dist")
             .join("index.js")
@@ -107,20 +181,22 @@ pub fn start_backend(
         app_handle
             .path()
             .resource_dir()
-            .map_err(|e| format!("Failed to get resource directory: {}", e))?
+            .map_err(|e| tr!("sidecar-resource-dir-failed", error = e))?
             .join("backend")
             .join("dist")
             .join("index.js")
     };
 
-    backend.start(backend_path, port)?;
-    Ok(format!("Backend 已在端口 {} 啟動", port))
+    backend
+        .start(backend_path, port, &backend_env(&app_handle))
+        .inspect_err(|e| record_start_failure(e))?;
+    Ok(tr!("sidecar-started", port = port))
 }
 
 #[tauri::command]
 pub fn stop_backend(backend: State<BackendProcess>) -> Result<String, String> {
     backend.stop()?;
-    Ok("Backend 已停止".to_string())
+    Ok(tr!("sidecar-stopped"))
 }
 
 #[tauri::command]
@@ -134,7 +210,7 @@ pub fn restart_backend(
     // Get absolute path (same logic as start_backend)
     let backend_path = if cfg!(debug_assertions) {
         std::env::current_dir()
-            .map_err(|e| format!("Failed to get current directory: {}", e))?
+            .map_err(|e| tr!("sidecar-current-dir-failed", error = e))?
             .join("backend")
             .join("dist")
             .join("index.js")
@@ -142,25 +218,27 @@ pub fn restart_backend(
         app_handle
             .path()
             .resource_dir()
-            .map_err(|e| format!("Failed to get resource directory: {}", e))?
+            .map_err(|e| tr!("sidecar-resource-dir-failed", error = e))?
             .join("backend")
             .join("dist")
             .join("index.js")
     };
 
-    backend.restart(backend_path, port)?;
-    Ok(format!("Backend 已在端口 {} 重啟", port))
+    backend
+        .restart(backend_path, port, &backend_env(&app_handle))
+        .inspect_err(|e| record_start_failure(e))?;
+    Ok(tr!("sidecar-restarted", port = port))
 }
 
 #[tauri::command]
 pub fn check_backend_health() -> Result<bool, String> {
     // 檢查後端是否正常運作
-    let client = reqwest::blocking::Client::new();
-    match client
-        .get("http://localhost:3000/health")
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-    {
+    let settings = config::load_settings();
+    let client = http_client::build_blocking_client(
+        &settings.advanced,
+        std::time::Duration::from_secs(5),
+    )?;
+    match client.get("http://localhost:3000/health").send() {
         Ok(response) => Ok(response.status().is_success()),
         Err(_) => Ok(false),
     }
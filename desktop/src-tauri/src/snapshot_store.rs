@@ -0,0 +1,371 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use crate::{path_guard, snapshots};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use log::{info, warn};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Compressed files start with this header: magic, format version and the
+/// uncompressed length, followed by a single zstd frame
+const MAGIC: &[u8; 4] = b"ADZS";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 8;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_LEVEL: i32 = 9;
+
+pub const ZSTD_SUFFIX: &str = ".zst";
+/// Suffix the backend uses for its own gzip output
+const GZIP_SUFFIX: &str = ".gz";
+const JSON_SUFFIX: &str = ".json";
+
+/// Event carrying `RecompressProgress` while a recompression runs
+pub const RECOMPRESS_PROGRESS_EVENT: &str = "snapshot-recompress-progress";
+
+static RECOMPRESS_LOCK: Mutex<()> = Mutex::new(());
+
+// ============= Encoding =============
+
+pub fn encode(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(HEADER_LEN + data.len() / 4);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    out.extend_from_slice(&zstd::encode_all(data, ZSTD_LEVEL)?);
+    Ok(out)
+}
+
+/// Decode any stored form: our zstd container, backend gzip or legacy plain JSON
+pub fn decode(data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.starts_with(MAGIC) {
+        if data.len() < HEADER_LEN || data[MAGIC.len()] != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unsupported compressed snapshot format",
+            ));
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&data[MAGIC.len() + 1..HEADER_LEN]);
+        let decoded = zstd::decode_all(&data[HEADER_LEN..])?;
+        if decoded.len() as u64 != u64::from_le_bytes(length) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "compressed snapshot is truncated",
+            ));
+        }
+        Ok(decoded)
+    } else if data.starts_with(&GZIP_MAGIC) {
+        let mut decoded = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut decoded)?;
+        Ok(decoded)
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+// ============= Files =============
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// On-disk variants of a logical file, in the order they are read
+fn variants(path: &Path) -> [PathBuf; 3] {
+    [
+        with_suffix(path, ZSTD_SUFFIX),
+        with_suffix(path, GZIP_SUFFIX),
+        path.to_path_buf(),
+    ]
+}
+
+/// Read the logical file `path` (e.g. `.../content.json`) from whichever variant exists
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    for candidate in variants(path) {
+        match std::fs::read(&candidate) {
+            Ok(data) => return decode(&data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::from(io::ErrorKind::NotFound))
+}
+
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    String::from_utf8(read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write the logical file `path`, compressed or not, and drop the other variants
+/// so a stale copy can never shadow the new content
+pub fn write(path: &Path, data: &[u8], compress: bool) -> io::Result<u64> {
+    let (target, content) = if compress {
+        (with_suffix(path, ZSTD_SUFFIX), encode(data)?)
+    } else {
+        (path.to_path_buf(), data.to_vec())
+    };
+
+    let temp = with_suffix(&target, ".tmp");
+    std::fs::write(&temp, &content)?;
+    std::fs::rename(&temp, &target)?;
+
+    for stale in variants(path).iter().filter(|v| **v != target) {
+        match std::fs::remove_file(stale) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(content.len() as u64)
+}
+
+// ============= Recompression =============
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct RecompressProgress {
+    pub processed: usize,
+    pub total: usize,
+    pub saved_bytes: i64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RecompressReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Mirrors `enable_compression`: compress when true, restore plain JSON when false
+    pub compress: bool,
+    pub files: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    /// Negative when restoring plain JSON
+    pub saved_bytes: i64,
+    pub errors: Vec<String>,
+}
+
+/// Logical JSON files under `dir` with the total size of their current variants.
+/// Files already in the desired form are left out.
+fn pending_files(dir: &Path, compress: bool) -> BTreeMap<PathBuf, u64> {
+    // Logical path -> (total size, has a zstd variant, number of variants)
+    let mut logical: BTreeMap<PathBuf, (u64, bool, usize)> = BTreeMap::new();
+    for (path, metadata) in path_guard::walk_files(dir) {
+        let name = path.to_string_lossy();
+        let (base, is_zstd) = if let Some(base) = name.strip_suffix(ZSTD_SUFFIX) {
+            (base, true)
+        } else if let Some(base) = name.strip_suffix(GZIP_SUFFIX) {
+            (base, false)
+        } else {
+            (name.as_ref(), false)
+        };
+        if !base.ends_with(JSON_SUFFIX) {
+            continue;
+        }
+
+        let entry = logical.entry(PathBuf::from(base)).or_default();
+        entry.0 += metadata.len();
+        entry.1 |= is_zstd;
+        entry.2 += 1;
+    }
+
+    logical
+        .into_iter()
+        .filter(|(_, (_, has_zstd, count))| {
+            if compress {
+                !(*has_zstd && *count == 1)
+            } else {
+                // Disabling compression only undoes our zstd files; backend gzip stays as is
+                *has_zstd
+            }
+        })
+        .map(|(path, (size, _, _))| (path, size))
+        .collect()
+}
+
+fn convert(path: &Path, compress: bool) -> io::Result<u64> {
+    let data = read(path)?;
+    if compress {
+        // Never drop the original unless the new file decodes back to the same bytes
+        if decode(&encode(&data)?)? != data {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "round trip mismatch",
+            ));
+        }
+    }
+    write(path, &data, compress)
+}
+
+pub fn recompress_with(
+    storage_root: &Path,
+    compress: bool,
+    mut on_progress: impl FnMut(&RecompressProgress),
+) -> RecompressReport {
+    let started_at = Utc::now();
+    let pending = pending_files(&snapshots::snapshots_dir(storage_root), compress);
+
+    let mut progress = RecompressProgress {
+        total: pending.len(),
+        ..Default::default()
+    };
+    let mut bytes_before = 0;
+    let mut bytes_after = 0;
+    let mut errors = Vec::new();
+
+    for (path, size) in pending {
+        match convert(&path, compress) {
+            Ok(written) => {
+                bytes_before += size;
+                bytes_after += written;
+                progress.saved_bytes += size as i64 - written as i64;
+            }
+            Err(e) => errors.push(tr!(
+                "store-recompress-item-failed",
+                path = path.display(),
+                error = e
+            )),
+        }
+        progress.processed += 1;
+        on_progress(&progress);
+    }
+
+    RecompressReport {
+        started_at,
+        finished_at: Utc::now(),
+        compress,
+        files: progress.processed - errors.len(),
+        bytes_before,
+        bytes_after,
+        saved_bytes: progress.saved_bytes,
+        errors,
+    }
+}
+
+/// Bring existing snapshot JSON in line with `enable_compression`
+pub fn recompress(
+    storage: &StorageSettings,
+    on_progress: impl FnMut(&RecompressProgress),
+) -> Result<RecompressReport, String> {
+    let _guard = RECOMPRESS_LOCK
+        .try_lock()
+        .map_err(|_| tr!("store-recompress-running"))?;
+
+    let roots = path_guard::storage_roots();
+    let storage_root = path_guard::validate_within(&storage.snapshot_storage_path, &roots)?;
+
+    let report = recompress_with(&storage_root, storage.enable_compression, on_progress);
+    info!(
+        "Snapshot recompression finished: {} files, {} bytes saved, {} errors",
+        report.files,
+        report.saved_bytes,
+        report.errors.len()
+    );
+    for error in &report.errors {
+        warn!("{}", error);
+    }
+    Ok(report)
+}
+
+// ============= Tauri Commands =============
+
+/// Recompress stored snapshots in the background, emitting progress events
+#[tauri::command]
+pub async fn recompress_snapshots<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<RecompressReport, String> {
+    let storage = config::load_settings().storage;
+    tokio::task::spawn_blocking(move || {
+        recompress(&storage, |progress| {
+            if let Err(e) = app.emit(RECOMPRESS_PROGRESS_EVENT, progress.clone()) {
+                warn!("Failed to emit recompression progress: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshots::test_support::write_snapshot;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-store-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_round_trip_and_legacy_reads() {
+        let dir = test_dir("roundtrip");
+        let path = dir.join("content.json");
+        let data = br#"{"pages":[1,2,3]}"#.repeat(100);
+
+        write(&path, &data, true).unwrap();
+        assert!(with_suffix(&path, ZSTD_SUFFIX).exists());
+        assert!(!path.exists());
+        assert_eq!(read(&path).unwrap(), data);
+
+        // Writing uncompressed replaces the zstd variant
+        write(&path, b"{}", false).unwrap();
+        assert!(!with_suffix(&path, ZSTD_SUFFIX).exists());
+        assert_eq!(read_to_string(&path).unwrap(), "{}");
+
+        // Backend gzip output is read transparently
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"[1]").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(with_suffix(&path, GZIP_SUFFIX), encoder.finish().unwrap()).unwrap();
+        assert_eq!(read(&path).unwrap(), b"[1]");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_frame() {
+        let mut encoded = encode(&b"x".repeat(1000)).unwrap();
+        // Claim a different uncompressed length
+        encoded[MAGIC.len() + 1] ^= 0xff;
+        assert!(decode(&encoded).is_err());
+        assert!(decode(&encoded[..HEADER_LEN - 1]).is_err());
+    }
+
+    #[test]
+    fn test_recompress_reports_savings_and_is_idempotent() {
+        let root = test_dir("recompress");
+        let dir = write_snapshot(&root, "snap-1", "proj-1", (1, 0, 0), Utc::now());
+        std::fs::write(dir.join("content.json"), b"{\"text\":\"aaaa\"}".repeat(500)).unwrap();
+        std::fs::write(dir.join("screenshots.bin"), b"not json").unwrap();
+
+        let mut events = 0;
+        let report = recompress_with(&root, true, |_| events += 1);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.files, 2);
+        assert_eq!(events, 2);
+        assert!(report.saved_bytes > 0);
+        assert!(dir.join("content.json.zst").exists());
+        assert!(dir.join("screenshots.bin").exists());
+
+        // Manifests stay readable through the catalog
+        assert_eq!(snapshots::list_snapshots(&root).len(), 1);
+
+        let again = recompress_with(&root, true, |_| {});
+        assert_eq!(again.files, 0);
+
+        let restored = recompress_with(&root, false, |_| {});
+        assert_eq!(restored.files, 2);
+        assert!(restored.saved_bytes < 0);
+        assert!(dir.join("content.json").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::i18n::tr;
+use crate::snapshot_store;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Subdirectory of `snapshot_storage_path` the backend writes snapshots into
+pub(crate) const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Semantic version as stored by the backend (`SemanticVersion` in snapshot_schema.ts)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl std::fmt::Display for SnapshotVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The parts of a snapshot manifest the desktop app needs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub id: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub version: SnapshotVersion,
+}
+
+/// A snapshot directory on disk together with its manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotEntry {
+    pub manifest: SnapshotManifest,
+    pub dir: PathBuf,
+}
+
+// ============= Catalog =============
+
+pub fn snapshots_dir(storage_root: &Path) -> PathBuf {
+    storage_root.join(SNAPSHOTS_DIR_NAME)
+}
+
+/// Read `manifest.json` in whichever form it is stored (zstd, backend gzip or plain)
+pub fn read_manifest(snapshot_dir: &Path) -> Result<SnapshotManifest, String> {
+    let path = snapshot_dir.join(MANIFEST_FILE_NAME);
+    let content = snapshot_store::read_to_string(&path).map_err(|e| {
+        tr!(
+            "snapshot-manifest-read-failed",
+            path = path.display(),
+            error = e
+        )
+    })?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        tr!(
+            "snapshot-manifest-malformed",
+            path = snapshot_dir.display(),
+            error = e
+        )
+    })
+}
+
+/// All readable snapshots under `storage_root`; unreadable ones are logged and skipped
+pub fn list_snapshots(storage_root: &Path) -> Vec<SnapshotEntry> {
+    let entries = match std::fs::read_dir(snapshots_dir(storage_root)) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut snapshots: Vec<SnapshotEntry> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .filter_map(|entry| match read_manifest(&entry.path()) {
+            Ok(manifest) => Some(SnapshotEntry {
+                manifest,
+                dir: entry.path(),
+            }),
+            Err(e) => {
+                warn!("Skipping snapshot {}: {}", entry.path().display(), e);
+                None
+            }
+        })
+        .collect();
+
+    snapshots.sort_by_key(|s| s.manifest.created_at);
+    snapshots
+}
+
+/// ID of each project's latest snapshot: highest version, newest on a tie
+pub fn latest_per_project(snapshots: &[SnapshotEntry]) -> HashMap<String, String> {
+    let mut latest: HashMap<String, &SnapshotManifest> = HashMap::new();
+    for snapshot in snapshots {
+        let manifest = &snapshot.manifest;
+        let newer = match latest.get(&manifest.project_id) {
+            Some(current) => {
+                (manifest.version, manifest.created_at) > (current.version, current.created_at)
+            }
+            None => true,
+        };
+        if newer {
+            latest.insert(manifest.project_id.clone(), manifest);
+        }
+    }
+
+    latest
+        .into_iter()
+        .map(|(project, manifest)| (project, manifest.id.clone()))
+        .collect()
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// Write a snapshot directory with a manifest as the backend would
+    pub fn write_snapshot(
+        storage_root: &Path,
+        id: &str,
+        project_id: &str,
+        version: (u32, u32, u32),
+        created_at: DateTime<Utc>,
+    ) -> PathBuf {
+        let dir = snapshots_dir(storage_root).join(id);
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = serde_json::json!({
+            "id": id,
+            "projectId": project_id,
+            "name": format!("Snapshot {}", id),
+            "createdAt": created_at.to_rfc3339(),
+            "createdBy": "test",
+            "version": { "major": version.0, "minor": version.1, "patch": version.2 },
+            "tags": [],
+        });
+        std::fs::write(dir.join(MANIFEST_FILE_NAME), manifest.to_string()).unwrap();
+        std::fs::write(dir.join("content.json"), "{}").unwrap();
+        dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::write_snapshot;
+    use super::*;
+    use chrono::Duration;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-snapshots-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_list_snapshots_reads_plain_and_gzip_manifests() {
+        let root = test_dir("list");
+        let now = Utc::now();
+        write_snapshot(
+            &root,
+            "snap-a",
+            "proj-1",
+            (1, 0, 0),
+            now - Duration::days(2),
+        );
+
+        // Compressed manifest only, as written with compression enabled
+        let dir = write_snapshot(&root, "snap-b", "proj-1", (1, 1, 0), now);
+        let plain = std::fs::read(dir.join(MANIFEST_FILE_NAME)).unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&plain).unwrap();
+        std::fs::write(dir.join("manifest.json.gz"), encoder.finish().unwrap()).unwrap();
+        std::fs::remove_file(dir.join(MANIFEST_FILE_NAME)).unwrap();
+
+        // Directories without a manifest are ignored
+        std::fs::create_dir_all(snapshots_dir(&root).join("broken")).unwrap();
+
+        let snapshots = list_snapshots(&root);
+        let ids: Vec<&str> = snapshots.iter().map(|s| s.manifest.id.as_str()).collect();
+        assert_eq!(ids, vec!["snap-a", "snap-b"]);
+        assert_eq!(snapshots[1].manifest.version.to_string(), "1.1.0");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_latest_per_project_prefers_highest_version() {
+        let root = test_dir("latest");
+        let now = Utc::now();
+        // A hotfix of an older line created later must not win over the newer version
+        write_snapshot(&root, "v2", "proj-1", (2, 0, 0), now - Duration::days(5));
+        write_snapshot(&root, "v1-hotfix", "proj-1", (1, 9, 1), now);
+        write_snapshot(
+            &root,
+            "other",
+            "proj-2",
+            (0, 1, 0),
+            now - Duration::days(30),
+        );
+
+        let latest = latest_per_project(&list_snapshots(&root));
+        assert_eq!(latest.get("proj-1").map(String::as_str), Some("v2"));
+        assert_eq!(latest.get("proj-2").map(String::as_str), Some("other"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
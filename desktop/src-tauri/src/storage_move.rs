@@ -0,0 +1,729 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use crate::{path_guard, retention, sidecar};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+/// Journal of the move in progress; its presence means a move can be resumed
+const JOURNAL_FILE_NAME: &str = "storage_move.json";
+/// Event carrying `MoveProgress` while files are copied
+pub const MOVE_PROGRESS_EVENT: &str = "storage-move-progress";
+const PART_SUFFIX: &str = ".part";
+/// Persist the journal after this many copied files
+const JOURNAL_FLUSH_EVERY: usize = 50;
+
+static MOVE_LOCK: Mutex<()> = Mutex::new(());
+static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Storage directories that can be relocated
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLocation {
+    Snapshots,
+    Screenshots,
+}
+
+impl StorageLocation {
+    pub fn field(self) -> &'static str {
+        match self {
+            StorageLocation::Snapshots => "storage.snapshot_storage_path",
+            StorageLocation::Screenshots => "storage.screenshot_storage_path",
+        }
+    }
+
+    fn path(self, storage: &StorageSettings) -> &Path {
+        match self {
+            StorageLocation::Snapshots => &storage.snapshot_storage_path,
+            StorageLocation::Screenshots => &storage.screenshot_storage_path,
+        }
+    }
+
+    fn set_path(self, storage: &mut StorageSettings, path: PathBuf) {
+        match self {
+            StorageLocation::Snapshots => storage.snapshot_storage_path = path,
+            StorageLocation::Screenshots => storage.screenshot_storage_path = path,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovePhase {
+    /// Files are being copied; the config still points at the source
+    Copying,
+    /// The config points at the target; only source cleanup remains
+    Switched,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MoveFile {
+    pub relative: PathBuf,
+    pub size: u64,
+    /// Set once the copy at the target has been verified
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MoveJob {
+    pub location: StorageLocation,
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub phase: MovePhase,
+    pub started_at: DateTime<Utc>,
+    pub files: Vec<MoveFile>,
+}
+
+impl MoveJob {
+    fn progress(&self) -> MoveProgress {
+        let done = self.files.iter().filter(|f| f.sha256.is_some());
+        MoveProgress {
+            location: self.location,
+            target: self.target.clone(),
+            phase: self.phase,
+            files_done: done.clone().count(),
+            files_total: self.files.len(),
+            bytes_done: done.map(|f| f.size).sum(),
+            bytes_total: self.files.iter().map(|f| f.size).sum(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MoveProgress {
+    pub location: StorageLocation,
+    pub target: PathBuf,
+    pub phase: MovePhase,
+    pub files_done: usize,
+    pub files_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MoveReport {
+    pub location: StorageLocation,
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub files: usize,
+    pub bytes: u64,
+    pub resumed: bool,
+    /// Source entries that could not be removed after the switch
+    pub cleanup_errors: Vec<String>,
+}
+
+// ============= Journal =============
+
+fn journal_path() -> Result<PathBuf, String> {
+    Ok(config::config_dir()?.join(JOURNAL_FILE_NAME))
+}
+
+fn load_journal(path: &Path) -> Result<Option<MoveJob>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| tr!("move-journal-invalid", error = e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(tr!("move-journal-invalid", error = e)),
+    }
+}
+
+fn save_journal(path: &Path, job: &MoveJob) -> Result<(), String> {
+    let content =
+        serde_json::to_string(job).map_err(|e| tr!("move-journal-write-failed", error = e))?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, content)
+        .and_then(|_| std::fs::rename(&temp, path))
+        .map_err(|e| tr!("move-journal-write-failed", error = e))
+}
+
+// ============= Planning =============
+
+fn plan(
+    location: StorageLocation,
+    storage: &StorageSettings,
+    target: &Path,
+    roots: &[PathBuf],
+) -> Result<MoveJob, String> {
+    let source = path_guard::validate_within(location.path(storage), roots)?;
+    if target.starts_with(&source) || source.starts_with(target) {
+        return Err(tr!(
+            "move-target-overlaps",
+            source = source.display(),
+            target = target.display()
+        ));
+    }
+    let target = path_guard::create_dir_secure(target, roots)?;
+    // Never merge into or overwrite existing data
+    let target_has_entries = std::fs::read_dir(&target)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if target_has_entries {
+        return Err(tr!("move-target-not-empty", path = target.display()));
+    }
+
+    let files = path_guard::walk_files(&source)
+        .into_iter()
+        .filter_map(|(path, metadata)| {
+            let relative = path.strip_prefix(&source).ok()?.to_path_buf();
+            Some(MoveFile {
+                relative,
+                size: metadata.len(),
+                sha256: None,
+            })
+        })
+        .collect();
+
+    Ok(MoveJob {
+        location,
+        source,
+        target,
+        phase: MovePhase::Copying,
+        started_at: Utc::now(),
+        files,
+    })
+}
+
+fn ensure_free_space(job: &MoveJob, min_free_space_mb: u64) -> Result<(), String> {
+    let remaining: u64 = job
+        .files
+        .iter()
+        .filter(|f| f.sha256.is_none())
+        .map(|f| f.size)
+        .sum();
+    let required = remaining + min_free_space_mb.saturating_mul(1024 * 1024);
+    let available = path_guard::free_space(&job.target)?;
+    if available < required {
+        return Err(tr!(
+            "move-insufficient-space",
+            path = job.target.display(),
+            required = required / (1024 * 1024),
+            available = available / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+// ============= Copying =============
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Copy through a `.part` file, hashing on the way, then re-read the result
+/// and compare before it is considered done
+fn copy_verified(source: &Path, target: &Path) -> io::Result<String> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut part = target.as_os_str().to_owned();
+    part.push(PART_SUFFIX);
+    let part = PathBuf::from(part);
+
+    let mut reader = File::open(source)?;
+    let mut writer = File::create(&part)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+    }
+    writer.sync_all()?;
+    drop(writer);
+    std::fs::rename(&part, target)?;
+
+    let expected = format!("{:x}", hasher.finalize());
+    if sha256_file(target)? != expected {
+        let _ = std::fs::remove_file(target);
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "checksum mismatch after copy",
+        ));
+    }
+    Ok(expected)
+}
+
+/// Copy every unverified file. Returns false when `keep_going` asked to stop;
+/// the journal then holds everything needed to resume.
+fn copy_files(
+    job: &mut MoveJob,
+    journal: &Path,
+    mut keep_going: impl FnMut(&MoveProgress) -> bool,
+) -> Result<bool, String> {
+    let mut since_flush = 0;
+    for index in 0..job.files.len() {
+        if job.files[index].sha256.is_some() {
+            continue;
+        }
+        if !keep_going(&job.progress()) {
+            save_journal(journal, job)?;
+            return Ok(false);
+        }
+
+        let relative = job.files[index].relative.clone();
+        let source = job.source.join(&relative);
+        let target = job.target.join(&relative);
+        let hash = copy_verified(&source, &target).map_err(|e| {
+            // Keep what was verified so far before failing
+            let _ = save_journal(journal, job);
+            tr!("move-copy-failed", path = source.display(), error = e)
+        })?;
+        job.files[index].sha256 = Some(hash);
+
+        since_flush += 1;
+        if since_flush >= JOURNAL_FLUSH_EVERY {
+            save_journal(journal, job)?;
+            since_flush = 0;
+        }
+    }
+
+    keep_going(&job.progress());
+    save_journal(journal, job)?;
+    Ok(true)
+}
+
+/// Bring the job in line with the source as it is now: files added or rewritten
+/// since they were copied are queued again and deleted ones are dropped.
+/// Returns true when every copied file still matches its source.
+fn rescan(job: &mut MoveJob) -> Result<bool, String> {
+    let mut current: BTreeMap<PathBuf, u64> = path_guard::walk_files(&job.source)
+        .into_iter()
+        .filter_map(|(path, metadata)| {
+            let relative = path.strip_prefix(&job.source).ok()?.to_path_buf();
+            Some((relative, metadata.len()))
+        })
+        .collect();
+    let mut unchanged = true;
+
+    let mut kept = Vec::with_capacity(job.files.len());
+    for mut file in std::mem::take(&mut job.files) {
+        let Some(size) = current.remove(&file.relative) else {
+            let _ = std::fs::remove_file(job.target.join(&file.relative));
+            unchanged = false;
+            continue;
+        };
+        if let Some(hash) = &file.sha256 {
+            let source = job.source.join(&file.relative);
+            let source_hash = sha256_file(&source)
+                .map_err(|e| tr!("move-copy-failed", path = source.display(), error = e))?;
+            if size != file.size || source_hash != *hash {
+                file.sha256 = None;
+                unchanged = false;
+            }
+        }
+        file.size = size;
+        kept.push(file);
+    }
+
+    for (relative, size) in current {
+        kept.push(MoveFile {
+            relative,
+            size,
+            sha256: None,
+        });
+        unchanged = false;
+    }
+    job.files = kept;
+    Ok(unchanged)
+}
+
+/// Remove the moved files from the source, then any directories left empty
+fn remove_source(job: &MoveJob) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    for file in &job.files {
+        let path = job.source.join(&file.relative);
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => errors.push(tr!("move-cleanup-failed", path = path.display(), error = e)),
+        }
+        dirs.extend(
+            path.ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(&job.source))
+                .map(Path::to_path_buf),
+        );
+    }
+    dirs.push(job.source.clone());
+
+    // Deepest first; directories that still hold other data stay
+    dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    dirs.dedup();
+    for dir in dirs {
+        let _ = std::fs::remove_dir(&dir);
+    }
+    errors
+}
+
+// ============= Move =============
+
+/// Start or resume moving `location` to `target`. `switch` points the config at
+/// the target and only runs once every file is copied and verified.
+fn run_move(
+    journal: &Path,
+    location: StorageLocation,
+    target: &Path,
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+    mut keep_going: impl FnMut(&MoveProgress) -> bool,
+    switch: impl FnOnce(&MoveJob) -> Result<(), String>,
+) -> Result<MoveReport, String> {
+    let requested_target = path_guard::validate_within(target, roots)?;
+
+    let (mut job, resumed) = match load_journal(journal)? {
+        Some(job) if job.location == location && job.target == requested_target => (job, true),
+        Some(job) => {
+            return Err(tr!(
+                "move-other-pending",
+                source = job.source.display(),
+                target = job.target.display()
+            ))
+        }
+        None => {
+            let job = plan(location, storage, &requested_target, roots)?;
+            ensure_free_space(&job, storage.min_free_space_mb)?;
+            save_journal(journal, &job)?;
+            (job, false)
+        }
+    };
+
+    if job.phase == MovePhase::Copying {
+        if resumed {
+            ensure_free_space(&job, storage.min_free_space_mb)?;
+        }
+        // Repeat until a rescan finds the source exactly as it was copied
+        loop {
+            if !copy_files(&mut job, journal, &mut keep_going)? {
+                return Err(tr!("move-interrupted"));
+            }
+            if rescan(&mut job)? {
+                break;
+            }
+            info!("Source changed during the move; copying the changes");
+        }
+
+        switch(&job)?;
+        job.phase = MovePhase::Switched;
+        save_journal(journal, &job)?;
+        info!("Switched {} to {}", location.field(), job.target.display());
+    }
+
+    let cleanup_errors = remove_source(&job);
+    std::fs::remove_file(journal).map_err(|e| tr!("move-journal-write-failed", error = e))?;
+
+    let progress = job.progress();
+    Ok(MoveReport {
+        location,
+        source: job.source,
+        target: job.target,
+        files: progress.files_total,
+        bytes: progress.bytes_total,
+        resumed,
+        cleanup_errors,
+    })
+}
+
+/// Point the saved config at the new directory
+fn switch_config(job: &MoveJob) -> Result<(), String> {
+    let mut settings = config::load_settings();
+    job.location
+        .set_path(&mut settings.storage, job.target.clone());
+    // Secrets are absent from the loaded settings, so the keychain is left untouched
+    config::save_config(settings)
+}
+
+/// Throw away an unfinished copy. After the switch only cleanup remains, so
+/// cancelling then just forgets the journal and keeps the source files.
+fn cancel(journal: &Path) -> Result<(), String> {
+    let Some(job) = load_journal(journal)? else {
+        return Ok(());
+    };
+    if job.phase == MovePhase::Copying {
+        let partial = MoveJob {
+            source: job.target.clone(),
+            ..job.clone()
+        };
+        for error in remove_source(&partial) {
+            warn!("{}", error);
+        }
+        for file in &job.files {
+            let mut part = job.target.join(&file.relative).into_os_string();
+            part.push(PART_SUFFIX);
+            let _ = std::fs::remove_file(PathBuf::from(part));
+        }
+    }
+    std::fs::remove_file(journal).map_err(|e| tr!("move-journal-write-failed", error = e))
+}
+
+// ============= Tauri Commands =============
+
+/// Move a storage directory to `target`, resuming an interrupted move to the same place
+#[tauri::command]
+pub async fn move_storage<R: Runtime>(
+    app: AppHandle<R>,
+    location: StorageLocation,
+    target: PathBuf,
+) -> Result<MoveReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let _guard = MOVE_LOCK
+            .try_lock()
+            .map_err(|_| tr!("move-already-running"))?;
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+
+        // Retention and the backend both write into storage; keep them away so
+        // the source cannot change behind the copy
+        let _retention = retention::lock()?;
+        let backend = app.try_state::<sidecar::BackendProcess>();
+        let suspended = match &backend {
+            Some(backend) => backend.suspend()?,
+            None => None,
+        };
+
+        let storage = config::load_settings().storage;
+        let result = run_move(
+            &journal_path()?,
+            location,
+            &target,
+            &storage,
+            &path_guard::storage_roots(),
+            |progress| {
+                if let Err(e) = app.emit(MOVE_PROGRESS_EVENT, progress.clone()) {
+                    warn!("Failed to emit move progress: {}", e);
+                }
+                !CANCEL_REQUESTED.load(Ordering::SeqCst)
+            },
+            switch_config,
+        );
+
+        if let (Some(backend), Some(launch)) = (backend, suspended) {
+            if let Err(e) = sidecar::resume(&app, &backend, launch) {
+                warn!("Failed to restart the backend after the storage move: {}", e);
+            }
+        }
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// The interrupted move waiting to be resumed, if any
+#[tauri::command]
+pub fn get_pending_storage_move() -> Result<Option<MoveProgress>, String> {
+    Ok(load_journal(&journal_path()?)?.map(|job| job.progress()))
+}
+
+/// Stop a running move, or discard the copied files of an interrupted one
+#[tauri::command]
+pub fn cancel_storage_move() -> Result<(), String> {
+    // A running move stops at the next file and keeps its journal
+    if MOVE_LOCK.try_lock().is_err() {
+        CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+        return Ok(());
+    }
+    cancel(&journal_path()?)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> (PathBuf, StorageSettings) {
+        let root = std::env::temp_dir().join(format!("autodoc-move-test-{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("old/snap-1/pages")).unwrap();
+        let root = root.canonicalize().unwrap();
+        std::fs::write(root.join("old/snap-1/manifest.json"), "{}").unwrap();
+        std::fs::write(root.join("old/snap-1/pages/a.json"), "[1]").unwrap();
+        std::fs::write(root.join("old/snap-1/pages/b.json"), "[2]").unwrap();
+
+        let mut storage = config::AppConfig::default().storage;
+        storage.snapshot_storage_path = root.join("old");
+        storage.allowed_roots = vec![root.clone()];
+        storage.min_free_space_mb = 0;
+        (root, storage)
+    }
+
+    #[test]
+    fn test_move_copies_verifies_and_switches() {
+        let (root, storage) = fixture("complete");
+        let journal = root.join(JOURNAL_FILE_NAME);
+        let mut switched = None;
+
+        let report = run_move(
+            &journal,
+            StorageLocation::Snapshots,
+            &root.join("new"),
+            &storage,
+            &storage.allowed_roots,
+            |_| true,
+            |job| {
+                // Everything is at the target before the config changes
+                assert!(job
+                    .files
+                    .iter()
+                    .all(|f| job.target.join(&f.relative).exists()));
+                switched = Some(job.target.clone());
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.files, 3);
+        assert!(!report.resumed);
+        assert_eq!(switched, Some(root.join("new")));
+        assert_eq!(
+            std::fs::read_to_string(root.join("new/snap-1/pages/b.json")).unwrap(),
+            "[2]"
+        );
+        assert!(!root.join("old").exists());
+        assert!(!journal.exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_move_picks_up_source_changes_before_switching() {
+        let (root, storage) = fixture("changes");
+        let journal = root.join(JOURNAL_FILE_NAME);
+
+        // Once everything is copied, rewrite a copied file and add a new one
+        let mut changed = false;
+        let report = run_move(
+            &journal,
+            StorageLocation::Snapshots,
+            &root.join("new"),
+            &storage,
+            &storage.allowed_roots,
+            |progress| {
+                if progress.files_done == progress.files_total && !changed {
+                    std::fs::write(root.join("old/snap-1/pages/a.json"), "[10]").unwrap();
+                    std::fs::write(root.join("old/snap-1/pages/c.json"), "[3]").unwrap();
+                    changed = true;
+                }
+                true
+            },
+            |_| Ok(()),
+        )
+        .unwrap();
+
+        assert_eq!(report.files, 4);
+        assert_eq!(
+            std::fs::read_to_string(root.join("new/snap-1/pages/a.json")).unwrap(),
+            "[10]"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("new/snap-1/pages/c.json")).unwrap(),
+            "[3]"
+        );
+        assert!(!root.join("old").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_interrupted_move_resumes() {
+        let (root, storage) = fixture("resume");
+        let journal = root.join(JOURNAL_FILE_NAME);
+
+        // Stop after the first file
+        let mut calls = 0;
+        let result = run_move(
+            &journal,
+            StorageLocation::Snapshots,
+            &root.join("new"),
+            &storage,
+            &storage.allowed_roots,
+            |_| {
+                calls += 1;
+                calls < 2
+            },
+            |_| panic!("must not switch an incomplete move"),
+        );
+        assert!(result.is_err());
+        let pending = load_journal(&journal).unwrap().unwrap();
+        assert_eq!(pending.progress().files_done, 1);
+        assert!(root.join("old/snap-1/manifest.json").exists());
+
+        // A different target is refused while this move is pending
+        let other = run_move(
+            &journal,
+            StorageLocation::Snapshots,
+            &root.join("elsewhere"),
+            &storage,
+            &storage.allowed_roots,
+            |_| true,
+            |_| Ok(()),
+        );
+        assert!(other.is_err());
+
+        let report = run_move(
+            &journal,
+            StorageLocation::Snapshots,
+            &root.join("new"),
+            &storage,
+            &storage.allowed_roots,
+            |_| true,
+            |_| Ok(()),
+        )
+        .unwrap();
+        assert!(report.resumed);
+        assert_eq!(report.files, 3);
+        assert!(root.join("new/snap-1/pages/a.json").exists());
+        assert!(!root.join("old").exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_move_refuses_unsafe_targets() {
+        let (root, mut storage) = fixture("refuse");
+        let journal = root.join(JOURNAL_FILE_NAME);
+        let attempt = |storage: &StorageSettings, target: &Path| {
+            run_move(
+                &journal,
+                StorageLocation::Snapshots,
+                target,
+                storage,
+                &storage.allowed_roots,
+                |_| true,
+                |_| Ok(()),
+            )
+        };
+
+        // Nested inside the source
+        assert!(attempt(&storage, &root.join("old/inner")).is_err());
+
+        // Existing data at the target
+        std::fs::create_dir_all(root.join("busy")).unwrap();
+        std::fs::write(root.join("busy/file"), "x").unwrap();
+        assert!(attempt(&storage, &root.join("busy")).is_err());
+
+        // Not enough free space once the margin is added
+        storage.min_free_space_mb = u64::MAX / (2 * 1024 * 1024);
+        assert!(attempt(&storage, &root.join("new")).is_err());
+        assert!(root.join("old/snap-1/manifest.json").exists());
+        assert!(!root.join("old/inner").exists());
+        assert!(!journal.exists());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,641 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use crate::{path_guard, snapshots};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Runtime};
+
+const CACHE_FILE_NAME: &str = "storage_usage_cache.json";
+/// Event emitted when a storage volume drops below `min_free_space_mb`
+pub const LOW_SPACE_EVENT: &str = "storage-low-space";
+const MONITOR_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// SQLite side files that belong to the database
+const DATABASE_SIDE_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+/// Scans share the on-disk cache, so only one runs at a time
+static SCAN_LOCK: Mutex<()> = Mutex::new(());
+/// Paths already reported as low on space, so the monitor warns once per episode
+static WARNED_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct UsageStats {
+    pub bytes: u64,
+    pub files: u64,
+    pub oldest: Option<DateTime<Utc>>,
+    pub newest: Option<DateTime<Utc>>,
+}
+
+impl UsageStats {
+    fn add_file(&mut self, size: u64, modified: Option<DateTime<Utc>>) {
+        self.bytes += size;
+        self.files += 1;
+        if let Some(modified) = modified {
+            self.oldest = Some(self.oldest.map_or(modified, |t| t.min(modified)));
+            self.newest = Some(self.newest.map_or(modified, |t| t.max(modified)));
+        }
+    }
+
+    fn merge(&mut self, other: &UsageStats) {
+        self.bytes += other.bytes;
+        self.files += other.files;
+        self.oldest = match (self.oldest, other.oldest) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.newest = match (self.newest, other.newest) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LocationUsage {
+    pub field: String,
+    pub path: PathBuf,
+    pub stats: UsageStats,
+    pub free_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SnapshotUsage {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    pub snapshot: UsageStats,
+    /// Screenshots filed under `<screenshot_storage_path>/<snapshot id>/`
+    pub screenshots: UsageStats,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectUsage {
+    pub project_id: String,
+    pub stats: UsageStats,
+    /// Newest version first
+    pub snapshots: Vec<SnapshotUsage>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LowSpaceWarning {
+    pub field: String,
+    pub path: PathBuf,
+    pub free_bytes: u64,
+    pub threshold_bytes: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StorageUsage {
+    pub scanned_at: DateTime<Utc>,
+    pub locations: Vec<LocationUsage>,
+    /// Largest project first
+    pub projects: Vec<ProjectUsage>,
+    pub unassigned_screenshots: UsageStats,
+    pub warnings: Vec<LowSpaceWarning>,
+    /// Directories read from disk vs. reused from the cache
+    pub scanned_dirs: usize,
+    pub cached_dirs: usize,
+}
+
+// ============= Incremental Scanner =============
+
+/// Direct contents of one directory. Adding, removing or renaming entries bumps
+/// the directory's mtime; rewriting a file in place does not, so each file's size
+/// and mtime are kept as well and checked before the entry is reused.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CachedDir {
+    modified: SystemTime,
+    files: UsageStats,
+    subdirs: Vec<String>,
+    #[serde(default)]
+    file_stamps: Vec<FileStamp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+struct FileStamp {
+    name: String,
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl CachedDir {
+    fn is_current(&self, dir: &Path, modified: SystemTime) -> bool {
+        self.modified == modified
+            && self.file_stamps.len() as u64 == self.files.files
+            && self.file_stamps.iter().all(|stamp| {
+                std::fs::symlink_metadata(dir.join(&stamp.name)).is_ok_and(|metadata| {
+                    metadata.is_file()
+                        && metadata.len() == stamp.size
+                        && metadata.modified().ok() == stamp.modified
+                })
+            })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct UsageCache {
+    dirs: HashMap<PathBuf, CachedDir>,
+}
+
+struct Scanner {
+    previous: HashMap<PathBuf, CachedDir>,
+    current: HashMap<PathBuf, CachedDir>,
+    scanned: usize,
+    cached: usize,
+}
+
+impl Scanner {
+    fn new(cache: UsageCache) -> Self {
+        Scanner {
+            previous: cache.dirs,
+            current: HashMap::new(),
+            scanned: 0,
+            cached: 0,
+        }
+    }
+
+    fn read_dir(&mut self, dir: &Path, modified: SystemTime) -> CachedDir {
+        if let Some(entry) = self.current.get(dir) {
+            return entry.clone();
+        }
+        if let Some(entry) = self
+            .previous
+            .get(dir)
+            .filter(|e| e.is_current(dir, modified))
+        {
+            self.cached += 1;
+            return entry.clone();
+        }
+
+        self.scanned += 1;
+        let mut entry = CachedDir {
+            modified,
+            files: UsageStats::default(),
+            subdirs: Vec::new(),
+            file_stamps: Vec::new(),
+        };
+        if let Ok(children) = std::fs::read_dir(dir) {
+            for child in children.flatten() {
+                // Symlinks are neither followed nor counted
+                let metadata = match std::fs::symlink_metadata(child.path()) {
+                    Ok(metadata) => metadata,
+                    Err(_) => continue,
+                };
+                if metadata.is_dir() {
+                    entry
+                        .subdirs
+                        .push(child.file_name().to_string_lossy().into_owned());
+                } else if metadata.is_file() {
+                    let modified = metadata.modified().ok();
+                    entry
+                        .files
+                        .add_file(metadata.len(), modified.map(DateTime::<Utc>::from));
+                    entry.file_stamps.push(FileStamp {
+                        name: child.file_name().to_string_lossy().into_owned(),
+                        size: metadata.len(),
+                        modified,
+                    });
+                }
+            }
+        }
+        entry
+    }
+
+    /// Total usage of `dir` and everything below it
+    fn scan(&mut self, dir: &Path) -> UsageStats {
+        let modified = match std::fs::symlink_metadata(dir) {
+            Ok(metadata) if metadata.is_dir() => {
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)
+            }
+            _ => return UsageStats::default(),
+        };
+
+        let entry = self.read_dir(dir, modified);
+        self.current.insert(dir.to_path_buf(), entry.clone());
+
+        let mut total = entry.files;
+        for name in &entry.subdirs {
+            total.merge(&self.scan(&dir.join(name)));
+        }
+        total
+    }
+
+    fn into_cache(self) -> UsageCache {
+        UsageCache { dirs: self.current }
+    }
+}
+
+fn load_cache(path: &Path) -> UsageCache {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(path: &Path, cache: &UsageCache) -> Result<(), String> {
+    let content =
+        serde_json::to_string(cache).map_err(|e| tr!("usage-cache-write-failed", error = e))?;
+    std::fs::write(path, content).map_err(|e| tr!("usage-cache-write-failed", error = e))
+}
+
+// ============= Usage Report =============
+
+fn database_usage(database_path: &Path) -> UsageStats {
+    let mut stats = UsageStats::default();
+    let mut candidates = vec![database_path.to_path_buf()];
+    candidates.extend(DATABASE_SIDE_SUFFIXES.iter().map(|suffix| {
+        let mut name = database_path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    }));
+
+    for path in candidates {
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if metadata.is_file() {
+                let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+                stats.add_file(metadata.len(), modified);
+            }
+        }
+    }
+    stats
+}
+
+/// Volumes below `min_free_space_mb`, one entry per configured location
+fn low_space_warnings(locations: &[LocationUsage], min_free_space_mb: u64) -> Vec<LowSpaceWarning> {
+    let threshold_bytes = min_free_space_mb.saturating_mul(1024 * 1024);
+    locations
+        .iter()
+        .filter_map(|location| {
+            let free_bytes = location.free_bytes?;
+            (free_bytes < threshold_bytes).then(|| LowSpaceWarning {
+                field: location.field.clone(),
+                path: location.path.clone(),
+                free_bytes,
+                threshold_bytes,
+                message: tr!(
+                    "usage-low-space",
+                    path = location.path.display(),
+                    available = free_bytes / (1024 * 1024),
+                    required = min_free_space_mb
+                ),
+            })
+        })
+        .collect()
+}
+
+fn collect_usage(
+    storage: &StorageSettings,
+    roots: &[PathBuf],
+    scanner: &mut Scanner,
+) -> Result<StorageUsage, String> {
+    let snapshot_root = path_guard::validate_within(&storage.snapshot_storage_path, roots)?;
+    let screenshot_root = path_guard::validate_within(&storage.screenshot_storage_path, roots)?;
+    let database_path = path_guard::validate_within(&storage.database_path, roots)?;
+
+    // Per-snapshot and per-screenshot-folder totals first; the location totals
+    // below then reuse those directories from the scanner
+    let catalog = snapshots::list_snapshots(&snapshot_root);
+    let snapshot_ids: HashSet<&str> = catalog.iter().map(|s| s.manifest.id.as_str()).collect();
+
+    let mut screenshots_by_snapshot: HashMap<String, UsageStats> = HashMap::new();
+    let mut unassigned_screenshots = UsageStats::default();
+    if let Ok(children) = std::fs::read_dir(&screenshot_root) {
+        for child in children.flatten() {
+            let name = child.file_name().to_string_lossy().into_owned();
+            let metadata = match std::fs::symlink_metadata(child.path()) {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                let stats = scanner.scan(&child.path());
+                if snapshot_ids.contains(name.as_str()) {
+                    screenshots_by_snapshot.insert(name, stats);
+                } else {
+                    unassigned_screenshots.merge(&stats);
+                }
+            } else if metadata.is_file() {
+                let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+                unassigned_screenshots.add_file(metadata.len(), modified);
+            }
+        }
+    }
+
+    let mut projects: BTreeMap<String, ProjectUsage> = BTreeMap::new();
+    for entry in &catalog {
+        let manifest = &entry.manifest;
+        let snapshot = SnapshotUsage {
+            id: manifest.id.clone(),
+            name: manifest.name.clone(),
+            version: manifest.version.to_string(),
+            created_at: manifest.created_at,
+            snapshot: scanner.scan(&entry.dir),
+            screenshots: screenshots_by_snapshot
+                .get(&manifest.id)
+                .copied()
+                .unwrap_or_default(),
+        };
+
+        let project = projects
+            .entry(manifest.project_id.clone())
+            .or_insert_with(|| ProjectUsage {
+                project_id: manifest.project_id.clone(),
+                stats: UsageStats::default(),
+                snapshots: Vec::new(),
+            });
+        project.stats.merge(&snapshot.snapshot);
+        project.stats.merge(&snapshot.screenshots);
+        project.snapshots.push(snapshot);
+    }
+
+    let versions: HashMap<&str, _> = catalog
+        .iter()
+        .map(|s| {
+            (
+                s.manifest.id.as_str(),
+                (s.manifest.version, s.manifest.created_at),
+            )
+        })
+        .collect();
+    let mut projects: Vec<ProjectUsage> = projects.into_values().collect();
+    for project in &mut projects {
+        project
+            .snapshots
+            .sort_by(|a, b| versions[b.id.as_str()].cmp(&versions[a.id.as_str()]));
+    }
+    projects.sort_by_key(|p| std::cmp::Reverse(p.stats.bytes));
+
+    let locations: Vec<LocationUsage> = [
+        (
+            "storage.snapshot_storage_path",
+            snapshot_root.clone(),
+            scanner.scan(&snapshot_root),
+        ),
+        (
+            "storage.screenshot_storage_path",
+            screenshot_root.clone(),
+            scanner.scan(&screenshot_root),
+        ),
+        (
+            "storage.database_path",
+            database_path.clone(),
+            database_usage(&database_path),
+        ),
+    ]
+    .into_iter()
+    .map(|(field, path, stats)| LocationUsage {
+        field: field.to_string(),
+        free_bytes: path_guard::free_space(&path).ok(),
+        path,
+        stats,
+    })
+    .collect();
+
+    Ok(StorageUsage {
+        scanned_at: Utc::now(),
+        warnings: low_space_warnings(&locations, storage.min_free_space_mb),
+        locations,
+        projects,
+        unassigned_screenshots,
+        scanned_dirs: scanner.scanned,
+        cached_dirs: scanner.cached,
+    })
+}
+
+/// Scan the storage paths, reusing cached directories unless `refresh` is set
+pub fn scan(storage: &StorageSettings, refresh: bool) -> Result<StorageUsage, String> {
+    let _guard = SCAN_LOCK.lock().unwrap();
+
+    let cache_path = config::config_dir()?.join(CACHE_FILE_NAME);
+    let cache = if refresh {
+        UsageCache::default()
+    } else {
+        load_cache(&cache_path)
+    };
+
+    let mut scanner = Scanner::new(cache);
+    let usage = collect_usage(storage, &path_guard::storage_roots(), &mut scanner)?;
+    if let Err(e) = save_cache(&cache_path, &scanner.into_cache()) {
+        warn!("Failed to save storage usage cache: {}", e);
+    }
+
+    info!(
+        "Storage usage scanned: {} dirs read, {} from cache",
+        usage.scanned_dirs, usage.cached_dirs
+    );
+    Ok(usage)
+}
+
+// ============= Low Space Monitor =============
+
+/// Emit warnings for volumes that newly dropped below the threshold
+fn notify_low_space<R: Runtime>(app: &AppHandle<R>, warnings: &[LowSpaceWarning]) {
+    let mut warned = WARNED_PATHS.lock().unwrap();
+    // Volumes that recovered may warn again next time they run low
+    warned.retain(|path| warnings.iter().any(|w| &w.path == path));
+
+    for warning in warnings {
+        if warned.contains(&warning.path) {
+            continue;
+        }
+        warn!("{}", warning.message);
+        if let Err(e) = app.emit(LOW_SPACE_EVENT, warning) {
+            warn!("Failed to emit low space warning: {}", e);
+        }
+        warned.push(warning.path.clone());
+    }
+}
+
+fn check_free_space(storage: &StorageSettings) -> Vec<LowSpaceWarning> {
+    let locations: Vec<LocationUsage> = [
+        (
+            "storage.snapshot_storage_path",
+            &storage.snapshot_storage_path,
+        ),
+        (
+            "storage.screenshot_storage_path",
+            &storage.screenshot_storage_path,
+        ),
+        ("storage.database_path", &storage.database_path),
+    ]
+    .into_iter()
+    .map(|(field, path)| LocationUsage {
+        field: field.to_string(),
+        path: path.clone(),
+        stats: UsageStats::default(),
+        free_bytes: path_guard::free_space(path).ok(),
+    })
+    .collect();
+    low_space_warnings(&locations, storage.min_free_space_mb)
+}
+
+/// Periodically check free space on the storage volumes; cheap, no directory walk
+pub fn spawn_low_space_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+        loop {
+            interval.tick().await;
+            let storage = config::load_settings().storage;
+            let warnings = check_free_space(&storage);
+            notify_low_space(&app, &warnings);
+        }
+    });
+}
+
+// ============= Tauri Commands =============
+
+/// Sizes, file counts and age ranges of everything in storage, grouped by project
+#[tauri::command]
+pub async fn get_storage_usage<R: Runtime>(
+    app: AppHandle<R>,
+    refresh: Option<bool>,
+) -> Result<StorageUsage, String> {
+    let storage = config::load_settings().storage;
+    let usage = tokio::task::spawn_blocking(move || scan(&storage, refresh.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())??;
+    notify_low_space(&app, &usage.warnings);
+    Ok(usage)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshots::test_support::write_snapshot;
+    use chrono::Duration as ChronoDuration;
+
+    fn fixture(name: &str) -> (PathBuf, StorageSettings) {
+        let root = std::env::temp_dir().join(format!("autodoc-usage-test-{}", name));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let mut storage = config::AppConfig::default().storage;
+        storage.snapshot_storage_path = root.join("snapshots");
+        storage.screenshot_storage_path = root.join("screenshots");
+        storage.database_path = root.join("autodoc.db");
+        storage.allowed_roots = vec![root.clone()];
+        storage.min_free_space_mb = 0;
+        (root, storage)
+    }
+
+    fn seed(storage: &StorageSettings) {
+        let now = Utc::now();
+        let base = &storage.snapshot_storage_path;
+        write_snapshot(
+            base,
+            "a1",
+            "proj-a",
+            (1, 0, 0),
+            now - ChronoDuration::days(3),
+        );
+        write_snapshot(base, "a2", "proj-a", (1, 1, 0), now);
+        write_snapshot(base, "b1", "proj-b", (1, 0, 0), now);
+
+        let shots = &storage.screenshot_storage_path;
+        std::fs::create_dir_all(shots.join("a1")).unwrap();
+        std::fs::write(shots.join("a1/home.png"), vec![0u8; 4096]).unwrap();
+        std::fs::write(shots.join("stray.png"), vec![0u8; 10]).unwrap();
+        std::fs::write(&storage.database_path, vec![0u8; 100]).unwrap();
+        std::fs::write(
+            storage.database_path.with_extension("db-wal"),
+            vec![0u8; 20],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_usage_grouped_by_project_and_version() {
+        let (root, storage) = fixture("grouping");
+        seed(&storage);
+
+        let mut scanner = Scanner::new(UsageCache::default());
+        let usage = collect_usage(&storage, &storage.allowed_roots, &mut scanner).unwrap();
+
+        // proj-a owns the 4 KB screenshot folder, so it is reported first
+        assert_eq!(usage.projects[0].project_id, "proj-a");
+        let versions: Vec<&str> = usage.projects[0]
+            .snapshots
+            .iter()
+            .map(|s| s.version.as_str())
+            .collect();
+        assert_eq!(versions, vec!["1.1.0", "1.0.0"]);
+        assert_eq!(usage.projects[0].snapshots[1].screenshots.bytes, 4096);
+        assert_eq!(usage.projects[0].snapshots[1].screenshots.files, 1);
+        assert_eq!(usage.unassigned_screenshots.bytes, 10);
+
+        let database = &usage.locations[2];
+        assert_eq!(database.stats.files, 2);
+        assert_eq!(database.stats.bytes, 120);
+        assert!(database.stats.oldest.is_some() && database.stats.newest.is_some());
+
+        let snapshots_total = &usage.locations[0].stats;
+        assert_eq!(snapshots_total.files, 6);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_second_scan_reuses_cache() {
+        let (root, storage) = fixture("cache");
+        seed(&storage);
+
+        let mut first = Scanner::new(UsageCache::default());
+        let before = collect_usage(&storage, &storage.allowed_roots, &mut first).unwrap();
+        assert_eq!(first.cached, 0);
+        let cache_path = root.join(CACHE_FILE_NAME);
+        save_cache(&cache_path, &first.into_cache()).unwrap();
+
+        let mut second = Scanner::new(load_cache(&cache_path));
+        let after = collect_usage(&storage, &storage.allowed_roots, &mut second).unwrap();
+        assert_eq!(second.scanned, 0);
+        assert!(second.cached > 0);
+        assert_eq!(after.locations[0].stats, before.locations[0].stats);
+
+        // A new file changes its directory's mtime and only that directory is re-read
+        std::fs::write(storage.screenshot_storage_path.join("a1/new.png"), b"png").unwrap();
+        let mut third = Scanner::new(second.into_cache());
+        let updated = collect_usage(&storage, &storage.allowed_roots, &mut third).unwrap();
+        assert_eq!(third.scanned, 1);
+        assert_eq!(updated.projects[0].snapshots[1].screenshots.files, 2);
+
+        // Rewriting a file in place leaves the directory mtime alone but is still noticed
+        let bytes_before = updated.projects[0].snapshots[1].screenshots.bytes;
+        std::fs::write(
+            storage.screenshot_storage_path.join("a1/new.png"),
+            b"larger png",
+        )
+        .unwrap();
+        let mut fourth = Scanner::new(third.into_cache());
+        let rewritten = collect_usage(&storage, &storage.allowed_roots, &mut fourth).unwrap();
+        assert_eq!(fourth.scanned, 1);
+        assert_eq!(
+            rewritten.projects[0].snapshots[1].screenshots.bytes,
+            bytes_before + 7
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_low_space_warning_threshold() {
+        let location = LocationUsage {
+            field: "storage.database_path".to_string(),
+            path: PathBuf::from("/data/autodoc.db"),
+            stats: UsageStats::default(),
+            free_bytes: Some(100 * 1024 * 1024),
+        };
+
+        assert!(low_space_warnings(std::slice::from_ref(&location), 50).is_empty());
+        let warnings = low_space_warnings(&[location], 500);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].threshold_bytes, 500 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,395 @@
+use crate::config::{self, AuthSettings};
+use crate::credential_registry::CredentialSource;
+use crate::i18n::tr;
+use crate::secure_storage;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// Keychain key prefix for per-target secrets, e.g. `target_secret:crm`
+const SECRET_KEY_PREFIX: &str = "target_secret:";
+
+/// ID given to the target migrated from the legacy single-target fields
+pub const LEGACY_TARGET_ID: &str = "default";
+/// Keychain key of the legacy single-target password
+const LEGACY_SECRET_KEY: &str = "target_password";
+
+const MAX_TARGET_ID_LEN: usize = 64;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetAuthType {
+    #[default]
+    None,
+    Form,
+    HttpBasic,
+    BearerToken,
+    Cookie,
+}
+
+impl TargetAuthType {
+    /// Map the legacy `auth.target_auth_type` string
+    fn from_legacy(value: &str) -> Self {
+        match value.to_ascii_lowercase().as_str() {
+            "form" | "password" => TargetAuthType::Form,
+            "basic" | "http_basic" => TargetAuthType::HttpBasic,
+            "bearer" | "token" | "bearer_token" => TargetAuthType::BearerToken,
+            "cookie" => TargetAuthType::Cookie,
+            _ => TargetAuthType::None,
+        }
+    }
+
+    fn requires_username(self) -> bool {
+        matches!(self, TargetAuthType::Form | TargetAuthType::HttpBasic)
+    }
+}
+
+/// CSS selectors used to fill in a login form
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct LoginSelectors {
+    pub username_field: Option<String>,
+    pub password_field: Option<String>,
+    pub submit_button: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TargetDefinition {
+    /// Stable identifier referenced by snapshots and saved sessions;
+    /// derived from the name when left empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub entry_url: String,
+    #[serde(default)]
+    pub auth_type: TargetAuthType,
+    pub username: Option<String>,
+    pub login_url: Option<String>,
+    #[serde(default)]
+    pub selectors: LoginSelectors,
+    // Note: password, token or cookie value; stored in OS keychain under `secret_key(id)`
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+}
+
+/// Target as shown in the UI: no secret, only whether one is stored
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TargetSummary {
+    #[serde(flatten)]
+    pub target: TargetDefinition,
+    pub has_secret: bool,
+}
+
+// ============= Keys & IDs =============
+
+/// Keychain key holding the secret of target `id`
+pub fn secret_key(id: &str) -> String {
+    format!("{}{}", SECRET_KEY_PREFIX, id)
+}
+
+/// Target ID encoded in a keychain key, if it is a per-target key
+pub fn target_id_from_key(key: &str) -> Option<&str> {
+    key.strip_prefix(SECRET_KEY_PREFIX)
+        .filter(|id| is_valid_id(id))
+}
+
+pub(crate) fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_TARGET_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug
+        .trim_matches('-')
+        .chars()
+        .take(MAX_TARGET_ID_LEN - 4)
+        .collect();
+    if slug.is_empty() {
+        "target".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Give every target without an ID one derived from its name, unique within the list
+pub fn assign_ids(targets: &mut [TargetDefinition]) {
+    let mut taken: Vec<String> = targets
+        .iter()
+        .filter(|t| !t.id.is_empty())
+        .map(|t| t.id.clone())
+        .collect();
+
+    for target in targets.iter_mut().filter(|t| t.id.is_empty()) {
+        let base = slugify(&target.name);
+        let mut id = base.clone();
+        let mut n = 2;
+        while taken.contains(&id) {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        taken.push(id.clone());
+        target.id = id;
+    }
+}
+
+// ============= Legacy Migration =============
+
+/// Move the legacy single-target fields into `targets` when no targets are defined yet.
+/// Returns true when a target was created; the caller decides whether to persist.
+pub fn migrate_legacy_target(auth: &mut AuthSettings) -> bool {
+    let auth_type = TargetAuthType::from_legacy(&auth.target_auth_type);
+    if !auth.targets.is_empty() || auth_type == TargetAuthType::None {
+        return false;
+    }
+
+    auth.targets.push(TargetDefinition {
+        id: LEGACY_TARGET_ID.to_string(),
+        name: "Default".to_string(),
+        entry_url: String::new(),
+        auth_type,
+        username: auth.target_username.take(),
+        login_url: None,
+        selectors: LoginSelectors::default(),
+        secret: auth.target_password.take(),
+    });
+    auth.target_auth_type = "none".to_string();
+    info!(
+        "Migrated legacy target authentication settings to target '{}'",
+        LEGACY_TARGET_ID
+    );
+    true
+}
+
+/// Move the legacy password in the keychain to the migrated target's key and remove
+/// the old entry. Runs on save, once the migrated target is part of the saved config.
+pub fn migrate_legacy_secret(auth: &AuthSettings) -> Result<(), String> {
+    if find_target(auth, LEGACY_TARGET_ID).is_none()
+        || !secure_storage::has_credential(LEGACY_SECRET_KEY)
+    {
+        return Ok(());
+    }
+
+    // A secret entered for the target since the migration is newer than the old password
+    let key = secret_key(LEGACY_TARGET_ID);
+    if !secure_storage::has_credential(&key) {
+        let password = secure_storage::get_credential(LEGACY_SECRET_KEY)?;
+        secure_storage::store_credential_from(&key, &password, CredentialSource::Migration)?;
+    }
+    secure_storage::delete_credential(LEGACY_SECRET_KEY)?;
+    info!("Moved legacy target password to '{}'", key);
+    Ok(())
+}
+
+// ============= Validation =============
+
+fn validate_url(value: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(value)
+        .map_err(|e| tr!("target-url-invalid", url = value, error = e))?;
+    match url.scheme() {
+        "http" | "https" => Ok(()),
+        scheme => Err(tr!("target-url-unsupported-scheme", scheme = scheme)),
+    }
+}
+
+/// Validate the target list; returns (dotted field, message) pairs
+pub fn validate_targets(targets: &[TargetDefinition]) -> Vec<(String, String)> {
+    let mut problems = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+
+    for (index, target) in targets.iter().enumerate() {
+        let field = |name: &str| format!("auth.targets[{}].{}", index, name);
+
+        if !target.id.is_empty() {
+            if !is_valid_id(&target.id) {
+                problems.push((field("id"), tr!("target-id-invalid", id = target.id)));
+            } else if seen.contains(&target.id.as_str()) {
+                problems.push((field("id"), tr!("target-id-duplicate", id = target.id)));
+            }
+            seen.push(&target.id);
+        }
+
+        if target.name.trim().is_empty() {
+            problems.push((field("name"), tr!("target-name-empty")));
+        }
+
+        // The legacy settings never had an entry URL; don't block saving until one is set
+        let legacy_without_url = target.id == LEGACY_TARGET_ID && target.entry_url.is_empty();
+        if !legacy_without_url {
+            if let Err(e) = validate_url(&target.entry_url) {
+                problems.push((field("entry_url"), e));
+            }
+        }
+
+        if let Some(login_url) = target.login_url.as_deref().filter(|u| !u.is_empty()) {
+            if let Err(e) = validate_url(login_url) {
+                problems.push((field("login_url"), e));
+            }
+        }
+
+        let has_username = target.username.as_deref().is_some_and(|u| !u.is_empty());
+        if target.auth_type.requires_username() && !has_username {
+            problems.push((field("username"), tr!("target-username-required")));
+        }
+    }
+
+    problems
+}
+
+/// Look up a target by ID
+pub fn find_target<'a>(auth: &'a AuthSettings, id: &str) -> Option<&'a TargetDefinition> {
+    auth.targets.iter().find(|t| t.id == id)
+}
+
+// ============= Tauri Commands =============
+
+#[tauri::command]
+pub fn list_targets() -> Vec<TargetSummary> {
+    config::load_settings()
+        .auth
+        .targets
+        .into_iter()
+        .map(|target| {
+            // The migrated legacy target still has its secret under the old key until saved
+            let has_secret = crate::secure_storage::has_credential(&secret_key(&target.id))
+                || (target.id == LEGACY_TARGET_ID
+                    && crate::secure_storage::has_credential(LEGACY_SECRET_KEY));
+            TargetSummary { target, has_secret }
+        })
+        .collect()
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn target(name: &str, url: &str) -> TargetDefinition {
+        TargetDefinition {
+            id: String::new(),
+            name: name.to_string(),
+            entry_url: url.to_string(),
+            auth_type: TargetAuthType::None,
+            username: None,
+            login_url: None,
+            selectors: LoginSelectors::default(),
+            secret: None,
+        }
+    }
+
+    #[test]
+    fn test_assign_ids_from_names() {
+        let mut targets = vec![
+            target("CRM Portal", "https://crm.example.com"),
+            target("CRM portal!", "https://crm2.example.com"),
+            target("", "https://other.example.com"),
+        ];
+        assign_ids(&mut targets);
+
+        assert_eq!(targets[0].id, "crm-portal");
+        assert_eq!(targets[1].id, "crm-portal-2");
+        assert_eq!(targets[2].id, "target");
+    }
+
+    #[test]
+    fn test_assign_ids_keeps_existing() {
+        let mut targets = vec![target("CRM", "https://crm.example.com")];
+        targets[0].id = "custom".to_string();
+        assign_ids(&mut targets);
+        assert_eq!(targets[0].id, "custom");
+    }
+
+    #[test]
+    fn test_secret_key_roundtrip() {
+        assert_eq!(secret_key("crm"), "target_secret:crm");
+        assert_eq!(target_id_from_key("target_secret:crm"), Some("crm"));
+        assert_eq!(target_id_from_key("target_secret:../x"), None);
+        assert_eq!(target_id_from_key("claude_api_key"), None);
+    }
+
+    #[test]
+    fn test_migrate_legacy_target() {
+        let mut config = AppConfig::default();
+        config.auth.target_auth_type = "form".to_string();
+        config.auth.target_username = Some("alice".to_string());
+        config.auth.target_password = Some("hunter2".to_string());
+
+        assert!(migrate_legacy_target(&mut config.auth));
+        assert_eq!(config.auth.targets.len(), 1);
+
+        let migrated = &config.auth.targets[0];
+        assert_eq!(migrated.id, LEGACY_TARGET_ID);
+        assert_eq!(migrated.auth_type, TargetAuthType::Form);
+        assert_eq!(migrated.username.as_deref(), Some("alice"));
+        assert_eq!(migrated.secret.as_deref(), Some("hunter2"));
+        assert_eq!(config.auth.target_auth_type, "none");
+
+        // Only migrates once
+        assert!(!migrate_legacy_target(&mut config.auth));
+    }
+
+    #[test]
+    fn test_no_migration_without_legacy_auth() {
+        let mut config = AppConfig::default();
+        assert!(!migrate_legacy_target(&mut config.auth));
+        assert!(config.auth.targets.is_empty());
+    }
+
+    #[test]
+    fn test_validate_targets() {
+        let mut ok = target("CRM", "https://crm.example.com");
+        ok.id = "crm".to_string();
+        assert!(validate_targets(&[ok.clone()]).is_empty());
+
+        let mut duplicate = ok.clone();
+        duplicate.auth_type = TargetAuthType::HttpBasic;
+        duplicate.entry_url = "ftp://crm.example.com".to_string();
+
+        let problems = validate_targets(&[ok, duplicate]);
+        let fields: Vec<&str> = problems.iter().map(|(f, _)| f.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "auth.targets[1].id",
+                "auth.targets[1].entry_url",
+                "auth.targets[1].username"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migrated_legacy_target_is_valid() {
+        let mut config = AppConfig::default();
+        config.auth.target_auth_type = "cookie".to_string();
+        migrate_legacy_target(&mut config.auth);
+        assert!(validate_targets(&config.auth.targets).is_empty());
+    }
+
+    #[test]
+    fn test_targets_toml_roundtrip_excludes_secret() {
+        let mut config = AppConfig::default();
+        let mut crm = target("CRM", "https://crm.example.com");
+        crm.id = "crm".to_string();
+        crm.auth_type = TargetAuthType::BearerToken;
+        crm.secret = Some("token-should-not-persist".to_string());
+        config.auth.targets.push(crm);
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("bearer_token"));
+        assert!(!text.contains("token-should-not-persist"));
+
+        let parsed: AppConfig = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.auth.targets[0].id, "crm");
+        assert_eq!(parsed.auth.targets[0].secret, None);
+    }
+}
@@ -0,0 +1,386 @@
+use crate::config;
+use crate::i18n::tr;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Local journal; nothing here is ever sent anywhere
+const JOURNAL_FILE_NAME: &str = "telemetry.jsonl";
+/// The journal is rotated once to `telemetry.1.jsonl` past this size
+const MAX_JOURNAL_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_CODE_LEN: usize = 64;
+const MAX_DETAIL_LEN: usize = 200;
+/// Tokens at least this long that mix letters and digits are treated as secrets
+const SECRET_MIN_LEN: usize = 20;
+
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplorationOutcome {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// Everything that can be recorded. Fields are counts, durations and codes;
+/// the only free text is `detail`, which is scrubbed before it is written.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    AppStart {
+        os: String,
+        arch: String,
+        language: String,
+    },
+    BackendCrash {
+        exit_code: Option<i32>,
+        uptime_secs: u64,
+    },
+    Exploration {
+        duration_ms: u64,
+        pages: u32,
+        outcome: ExplorationOutcome,
+        error_code: Option<String>,
+    },
+    Error {
+        code: String,
+        detail: Option<String>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TelemetryRecord {
+    pub at: DateTime<Utc>,
+    pub app_version: String,
+    #[serde(flatten)]
+    pub event: TelemetryEvent,
+}
+
+// ============= Scrubbing =============
+
+/// Keep error codes to a plain identifier alphabet so they cannot carry data
+fn sanitize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .take(MAX_CODE_LEN)
+        .collect()
+}
+
+fn looks_like_secret(token: &str) -> bool {
+    let body = token.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    body.len() >= SECRET_MIN_LEN
+        && body
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+' | '/' | '='))
+        && body.chars().any(|c| c.is_ascii_digit())
+        && body.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+fn scrub_token(token: &str) -> String {
+    if token.contains("://") {
+        return "<url>".to_string();
+    }
+    if let Some((name, value)) = token.split_once('=') {
+        if !value.is_empty() {
+            return format!("{}={}", name, scrub_token(value));
+        }
+    }
+    if token
+        .split_once('@')
+        .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+    {
+        return "<email>".to_string();
+    }
+    if looks_like_secret(token) {
+        return "<redacted>".to_string();
+    }
+    if token.split('.').count() == 4 && token.split('.').all(|p| p.parse::<u8>().is_ok()) {
+        return "<ip>".to_string();
+    }
+    token.to_string()
+}
+
+/// Remove emails, URLs, IPs, key-like tokens and the user's home directory
+pub fn scrub(text: &str, home: Option<&Path>) -> String {
+    let mut text = text.to_string();
+    if let Some(home) = home.map(|h| h.to_string_lossy().into_owned()) {
+        if !home.is_empty() && home != "/" {
+            text = text.replace(&home, "~");
+        }
+    }
+    // Authorization headers carry the secret in the following token
+    text = text.replace("Bearer ", "Bearer=");
+
+    let scrubbed = text
+        .split_whitespace()
+        .map(scrub_token)
+        .collect::<Vec<_>>()
+        .join(" ");
+    scrubbed.chars().take(MAX_DETAIL_LEN).collect()
+}
+
+fn scrub_event(event: TelemetryEvent, home: Option<&Path>) -> TelemetryEvent {
+    match event {
+        TelemetryEvent::Exploration {
+            duration_ms,
+            pages,
+            outcome,
+            error_code,
+        } => TelemetryEvent::Exploration {
+            duration_ms,
+            pages,
+            outcome,
+            error_code: error_code.map(|code| sanitize_code(&code)),
+        },
+        TelemetryEvent::Error { code, detail } => TelemetryEvent::Error {
+            code: sanitize_code(&code),
+            detail: detail.map(|detail| scrub(&detail, home)),
+        },
+        TelemetryEvent::AppStart { os, arch, language } => TelemetryEvent::AppStart {
+            os: sanitize_code(&os),
+            arch: sanitize_code(&arch),
+            language: sanitize_code(&language),
+        },
+        crash @ TelemetryEvent::BackendCrash { .. } => crash,
+    }
+}
+
+// ============= Journal =============
+
+#[cfg(not(test))]
+fn journal_dir() -> Result<PathBuf, String> {
+    config::config_dir()
+}
+
+/// Tests never touch the user's config directory
+#[cfg(test)]
+fn journal_dir() -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join("autodoc-telemetry-test-journal");
+    std::fs::create_dir_all(&dir).map_err(|e| tr!("telemetry-write-failed", error = e))?;
+    Ok(dir)
+}
+
+#[cfg(not(test))]
+fn enabled() -> bool {
+    config::load_settings().advanced.enable_telemetry
+}
+
+/// Tests record as if the user had opted in
+#[cfg(test)]
+fn enabled() -> bool {
+    true
+}
+
+fn rotated_path(journal: &Path) -> PathBuf {
+    journal.with_extension("1.jsonl")
+}
+
+fn append_to(journal: &Path, record: &TelemetryRecord) -> Result<(), String> {
+    let _guard = JOURNAL_LOCK.lock().unwrap();
+
+    let size = std::fs::metadata(journal).map(|m| m.len()).unwrap_or(0);
+    if size > MAX_JOURNAL_BYTES {
+        std::fs::rename(journal, rotated_path(journal))
+            .map_err(|e| tr!("telemetry-write-failed", error = e))?;
+    }
+
+    let mut line =
+        serde_json::to_string(record).map_err(|e| tr!("telemetry-write-failed", error = e))?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| tr!("telemetry-write-failed", error = e))
+}
+
+/// Records from the rotated and current journal, oldest first; bad lines are skipped
+fn read_from(journal: &Path) -> Vec<TelemetryRecord> {
+    [rotated_path(journal), journal.to_path_buf()]
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Append `event` when `enabled`; returns whether anything was written
+fn record_to(journal: &Path, enabled: bool, event: TelemetryEvent) -> Result<bool, String> {
+    if !enabled {
+        return Ok(false);
+    }
+    let record = TelemetryRecord {
+        at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        event: scrub_event(event, dirs::home_dir().as_deref()),
+    };
+    append_to(journal, &record)?;
+    Ok(true)
+}
+
+/// Record an event if `advanced.enable_telemetry` is on. Failures are only logged.
+pub fn record(event: TelemetryEvent) {
+    let result =
+        journal_dir().and_then(|dir| record_to(&dir.join(JOURNAL_FILE_NAME), enabled(), event));
+    if let Err(e) = result {
+        warn!("Failed to record telemetry: {}", e);
+    }
+}
+
+pub fn record_app_start(language: &str) {
+    record(TelemetryEvent::AppStart {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        language: language.to_string(),
+    });
+}
+
+// ============= Tauri Commands =============
+
+/// Recorded events, newest first
+#[tauri::command]
+pub fn get_telemetry_events(limit: Option<usize>) -> Result<Vec<TelemetryRecord>, String> {
+    let mut records = read_from(&journal_dir()?.join(JOURNAL_FILE_NAME));
+    records.reverse();
+    if let Some(limit) = limit {
+        records.truncate(limit);
+    }
+    Ok(records)
+}
+
+/// Write the journal as JSONL to `path`; returns the number of events
+#[tauri::command]
+pub fn export_telemetry(path: PathBuf) -> Result<usize, String> {
+    let output = config::validate_path(&path)?;
+    let records = read_from(&journal_dir()?.join(JOURNAL_FILE_NAME));
+
+    let mut content = String::new();
+    for record in &records {
+        let line =
+            serde_json::to_string(record).map_err(|e| tr!("telemetry-export-failed", error = e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    std::fs::write(&output, content).map_err(|e| tr!("telemetry-export-failed", error = e))?;
+    Ok(records.len())
+}
+
+#[tauri::command]
+pub fn purge_telemetry() -> Result<(), String> {
+    let journal = journal_dir()?.join(JOURNAL_FILE_NAME);
+    let _guard = JOURNAL_LOCK.lock().unwrap();
+    for path in [rotated_path(&journal), journal] {
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(tr!("telemetry-purge-failed", error = e))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Called by the UI, and through the API gateway by the backend, when an exploration finishes
+#[tauri::command]
+pub fn record_exploration(
+    duration_ms: u64,
+    pages: u32,
+    outcome: ExplorationOutcome,
+    error_code: Option<String>,
+) {
+    record(TelemetryEvent::Exploration {
+        duration_ms,
+        pages,
+        outcome,
+        error_code,
+    });
+}
+
+/// Called by the UI, the sidecar and the backend for errors worth counting; `detail` is scrubbed
+#[tauri::command]
+pub fn record_error(code: String, detail: Option<String>) {
+    record(TelemetryEvent::Error { code, detail });
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_journal(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-telemetry-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(JOURNAL_FILE_NAME)
+    }
+
+    #[test]
+    fn test_scrub_removes_pii_and_secrets() {
+        let home = Path::new("/home/alice");
+        let scrubbed = scrub(
+            "open /home/alice/AutoDoc/db failed for bob@example.com at https://app.internal/x?token=1 \
+             key=sk-ant-REDACTED Bearer abcdefghij1234567890klmn from 10.0.0.12",
+            Some(home),
+        );
+
+        assert!(!scrubbed.contains("alice"));
+        assert!(scrubbed.contains("~/AutoDoc/db"));
+        assert!(!scrubbed.contains("bob@"));
+        assert!(!scrubbed.contains("app.internal"));
+        assert!(!scrubbed.contains("sk-ant"));
+        assert!(!scrubbed.contains("abcdefghij1234567890klmn"));
+        assert!(!scrubbed.contains("10.0.0.12"));
+        assert!(scrubbed.contains("failed"));
+
+        assert_eq!(sanitize_code("E_TIMEOUT: user@x.com"), "E_TIMEOUTuserx.com");
+    }
+
+    #[test]
+    fn test_nothing_recorded_when_disabled() {
+        let journal = test_journal("disabled");
+        let event = TelemetryEvent::Error {
+            code: "E1".to_string(),
+            detail: None,
+        };
+
+        assert!(!record_to(&journal, false, event.clone()).unwrap());
+        assert!(!journal.exists());
+
+        assert!(record_to(&journal, true, event.clone()).unwrap());
+        let records = read_from(&journal);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].event, event);
+
+        let _ = std::fs::remove_dir_all(journal.parent().unwrap());
+    }
+
+    #[test]
+    fn test_journal_rotation_keeps_previous_events() {
+        let journal = test_journal("rotation");
+        std::fs::write(&journal, "x".repeat(MAX_JOURNAL_BYTES as usize + 1)).unwrap();
+
+        let crash = TelemetryEvent::BackendCrash {
+            exit_code: Some(1),
+            uptime_secs: 42,
+        };
+        record_to(&journal, true, crash.clone()).unwrap();
+
+        assert!(rotated_path(&journal).exists());
+        // The oversized garbage line is skipped, the new event is kept
+        let records = read_from(&journal);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].event, crash);
+
+        let _ = std::fs::remove_dir_all(journal.parent().unwrap());
+    }
+}
@@ -1,19 +1,25 @@
+use crate::i18n::tr;
+use crate::window_state;
+use log::warn;
 use tauri::{
     menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager, Runtime, Emitter,
+    AppHandle, Runtime, Emitter,
 };
 
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+const TRAY_ID: &str = "main";
+
+/// Tray menu labelled in the current interface language
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
     // Create menu items
-    let show = MenuItem::with_id(app, "show", "顯示主視窗", true, None::<&str>)?;
-    let hide = MenuItem::with_id(app, "hide", "隱藏視窗", true, None::<&str>)?;
-    let settings = MenuItem::with_id(app, "settings", "設定", true, None::<&str>)?;
-    let about = MenuItem::with_id(app, "about", "關於", true, None::<&str>)?;
-    let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", tr!("tray-show"), true, None::<&str>)?;
+    let hide = MenuItem::with_id(app, "hide", tr!("tray-hide"), true, None::<&str>)?;
+    let settings = MenuItem::with_id(app, "settings", tr!("tray-settings"), true, None::<&str>)?;
+    let about = MenuItem::with_id(app, "about", tr!("tray-about"), true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", tr!("tray-quit"), true, None::<&str>)?;
 
     // Build menu
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &show,
@@ -24,24 +30,41 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
             &PredefinedMenuItem::separator(app)?,
             &quit,
         ],
-    )?;
+    )
+}
+
+/// Relabel the tray menu after the interface language changed
+pub fn refresh_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app)?))?;
+    }
+    Ok(())
+}
+
+/// Show a reminder in the tray tooltip, or the plain app name when `None`
+pub fn set_attention<R: Runtime>(app: &AppHandle<R>, reminder: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let tooltip = match reminder {
+        Some(reminder) => format!("AutoDoc Agent\n{}", reminder),
+        None => "AutoDoc Agent".to_string(),
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        warn!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
 
     // Create tray icon
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .icon(app.default_window_icon().unwrap().clone())
         .on_menu_event(|app, event| match event.id().as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            "hide" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.hide();
-                }
-            }
+            "show" => window_state::set_main_visible(app, true),
+            "hide" => window_state::set_main_visible(app, false),
             "settings" => {
                 // 發送事件到前端，開啟設定視窗
                 let _ = app.emit("open-settings", ());
@@ -50,13 +73,11 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                 // 顯示關於對話框 - 使用 Tauri v2 的 dialog plugin
                 use tauri_plugin_dialog::DialogExt;
                 app.dialog()
-                    .message("AutoDoc Agent v2.0\n智能探索式使用手冊生成器\n\n© 2025 AutoDoc Team")
-                    .title("關於 AutoDoc Agent")
+                    .message(tr!("tray-about-message", version = env!("CARGO_PKG_VERSION")))
+                    .title(tr!("tray-about-title"))
                     .blocking_show();
             }
-            "quit" => {
-                std::process::exit(0);
-            }
+            "quit" => window_state::shutdown(app),
             _ => {}
         })
         .on_tray_icon_event(|tray, event| {
@@ -66,15 +87,7 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
                 ..
             } = event
             {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    if window.is_visible().unwrap_or(false) {
-                        let _ = window.hide();
-                    } else {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
-                }
+                window_state::toggle_main(tray.app_handle());
             }
         })
         .build(app)?;
@@ -1,3 +1,4 @@
+use crate::i18n::tr;
 use log::info;
 use tauri::{AppHandle, Wry};
 
@@ -21,7 +22,7 @@ pub async fn check_for_updates(_app: AppHandle<Wry>) -> Result<UpdateInfo, Strin
     // 實際實現需要連接到更新服務器
     let update_available = false; // 從服務器獲取
     let latest_version = "2.0.0".to_string();
-    let release_notes = "暫無更新".to_string();
+    let release_notes = tr!("updater-no-notes");
     let release_date = "2025-11-10".to_string();
 
     if update_available {
@@ -49,7 +50,7 @@ pub async fn install_update(_app: AppHandle<Wry>) -> Result<(), String> {
 
     // 在 Tauri v2 中，更新機制需要通過插件實現
     // 這裡提供一個占位實現
-    Err("更新功能尚未完全實現，請手動下載最新版本".to_string())
+    Err(tr!("updater-install-unsupported"))
 }
 
 #[tauri::command]
@@ -0,0 +1,723 @@
+use crate::config::{self, StorageSettings};
+use crate::i18n::tr;
+use crate::path_guard;
+use chrono::{DateTime, Datelike, NaiveTime, SecondsFormat, Utc};
+use log::warn;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+pub const BUDGET_EVENT: &str = "usage-budget-exceeded";
+
+/// USD per million input/output tokens, matched against the model name
+const PRICES: [(&str, f64, f64); 4] = [
+    ("opus", 15.0, 75.0),
+    ("claude-3-haiku", 0.25, 1.25),
+    ("haiku", 0.8, 4.0),
+    ("sonnet", 3.0, 15.0),
+];
+/// Unknown models are priced like Sonnet so budgets still apply
+const DEFAULT_PRICE: (f64, f64) = (3.0, 15.0);
+
+/// Numbered migrations from `database/migrations`, applied in order on open
+const MIGRATIONS: [(i64, &str); 1] = [(
+    1,
+    include_str!("../../../database/migrations/001_usage_ledger.sql"),
+)];
+/// The backend writes to the same database; wait for its locks instead of failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+type BudgetListener = Box<dyn Fn(&BudgetStatus) + Send + Sync>;
+
+static LISTENER: OnceLock<BudgetListener> = OnceLock::new();
+/// Budgets already reported, keyed by budget id and period start
+static NOTIFIED: Mutex<Option<HashSet<(i64, String)>>> = Mutex::new(None);
+
+/// One Claude request as seen by the API gateway
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub at: DateTime<Utc>,
+    pub project_id: String,
+    pub session_id: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub images: u32,
+    pub cost_usd: f64,
+}
+
+impl UsageRecord {
+    pub fn new(
+        project_id: &str,
+        session_id: &str,
+        model: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+        images: u32,
+    ) -> Self {
+        UsageRecord {
+            at: Utc::now(),
+            project_id: project_id.to_string(),
+            session_id: session_id.to_string(),
+            model: model.to_string(),
+            input_tokens,
+            output_tokens,
+            images,
+            cost_usd: cost_usd(model, input_tokens, output_tokens),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportGrouping {
+    Day,
+    Project,
+    Model,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageReportRow {
+    /// UTC date, project id or model depending on the grouping
+    pub key: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub images: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Daily,
+    Monthly,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Notify once per period
+    Warn,
+    /// Refuse further Claude requests until the period ends
+    Pause,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UsageBudget {
+    /// None when the budget has not been saved yet
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// None applies the budget to the spending of all projects together
+    #[serde(default)]
+    pub project_id: Option<String>,
+    pub period: BudgetPeriod,
+    pub limit_usd: f64,
+    pub action: BudgetAction,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BudgetStatus {
+    pub budget: UsageBudget,
+    pub period_start: DateTime<Utc>,
+    pub spent_usd: f64,
+    pub exceeded: bool,
+}
+
+// ============= Pricing =============
+
+pub fn cost_usd(model: &str, input_tokens: u64, output_tokens: u64) -> f64 {
+    let model = model.to_lowercase();
+    let (input, output) = PRICES
+        .iter()
+        .find(|(family, _, _)| model.contains(family))
+        .map(|(_, input, output)| (*input, *output))
+        .unwrap_or(DEFAULT_PRICE);
+    (input_tokens as f64 * input + output_tokens as f64 * output) / 1_000_000.0
+}
+
+// ============= Database =============
+
+fn timestamp(at: &DateTime<Utc>) -> String {
+    // Fixed width so timestamps compare correctly as text
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn query_error(e: rusqlite::Error) -> String {
+    tr!("ledger-query-failed", error = e)
+}
+
+/// The ledger lives in the application database next to the backend's tables
+pub fn database_path(storage: &StorageSettings) -> Result<PathBuf, String> {
+    if let Some(parent) = storage.database_path.parent() {
+        if !parent.exists() {
+            path_guard::create_dir_secure(parent, &path_guard::storage_roots())?;
+        }
+    }
+    Ok(storage.database_path.clone())
+}
+
+/// Apply the migrations not yet recorded in `schema_migrations`
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        )",
+    )?;
+    let applied: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )?;
+    for (version, sql) in MIGRATIONS.iter().filter(|(version, _)| *version > applied) {
+        tx.execute_batch(sql)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+            params![version, timestamp(&Utc::now())],
+        )?;
+    }
+    tx.commit()
+}
+
+fn open(database: &Path) -> Result<Connection, String> {
+    let mut conn =
+        Connection::open(database).map_err(|e| tr!("ledger-open-failed", error = e))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .and_then(|_| migrate(&mut conn))
+        .map_err(|e| tr!("ledger-open-failed", error = e))?;
+    Ok(conn)
+}
+
+pub fn record(database: &Path, usage: &UsageRecord) -> Result<(), String> {
+    let conn = open(database)?;
+    conn.execute(
+        "INSERT INTO api_usage
+            (at, project_id, session_id, model, input_tokens, output_tokens, images, cost_usd)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            timestamp(&usage.at),
+            usage.project_id,
+            usage.session_id,
+            usage.model,
+            usage.input_tokens as i64,
+            usage.output_tokens as i64,
+            usage.images,
+            usage.cost_usd,
+        ],
+    )
+    .map_err(query_error)?;
+    Ok(())
+}
+
+fn time_range(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> (String, String) {
+    (
+        from.map(|at| timestamp(&at)).unwrap_or_default(),
+        to.map(|at| timestamp(&at))
+            .unwrap_or_else(|| "9999".to_string()),
+    )
+}
+
+pub fn report(
+    database: &Path,
+    grouping: ReportGrouping,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    project_id: Option<&str>,
+) -> Result<Vec<UsageReportRow>, String> {
+    let key = match grouping {
+        ReportGrouping::Day => "substr(at, 1, 10)",
+        ReportGrouping::Project => "project_id",
+        ReportGrouping::Model => "model",
+    };
+    let conn = open(database)?;
+    let mut statement = conn
+        .prepare(&format!(
+            "SELECT {key}, COUNT(*), SUM(input_tokens), SUM(output_tokens), SUM(images), SUM(cost_usd)
+             FROM api_usage
+             WHERE at >= ?1 AND at < ?2 AND (?3 IS NULL OR project_id = ?3)
+             GROUP BY 1 ORDER BY 1"
+        ))
+        .map_err(query_error)?;
+
+    let (from, to) = time_range(from, to);
+    let rows = statement
+        .query_map(params![from, to, project_id], |row| {
+            Ok(UsageReportRow {
+                key: row.get(0)?,
+                requests: row.get::<_, i64>(1)? as u64,
+                input_tokens: row.get::<_, i64>(2)? as u64,
+                output_tokens: row.get::<_, i64>(3)? as u64,
+                images: row.get::<_, i64>(4)? as u64,
+                cost_usd: row.get(5)?,
+            })
+        })
+        .map_err(query_error)?;
+    rows.collect::<Result<_, _>>().map_err(query_error)
+}
+
+/// Quote a CSV field, neutralising values spreadsheets would run as formulas
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Write every request in the range as CSV; returns the number of rows
+pub fn export_csv(
+    database: &Path,
+    output: &Path,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<usize, String> {
+    let conn = open(database)?;
+    let mut statement = conn
+        .prepare(
+            "SELECT at, project_id, session_id, model, input_tokens, output_tokens, images, cost_usd
+             FROM api_usage WHERE at >= ?1 AND at < ?2 ORDER BY at",
+        )
+        .map_err(query_error)?;
+
+    let (from, to) = time_range(from, to);
+    let mut csv = String::from(
+        "timestamp,project_id,session_id,model,input_tokens,output_tokens,images,cost_usd\n",
+    );
+    let mut count = 0;
+    let mut rows = statement.query(params![from, to]).map_err(query_error)?;
+    while let Some(row) = rows.next().map_err(query_error)? {
+        let text = |i: usize| row.get::<_, String>(i).map(|v| csv_field(&v));
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.6}\n",
+            text(0).map_err(query_error)?,
+            text(1).map_err(query_error)?,
+            text(2).map_err(query_error)?,
+            text(3).map_err(query_error)?,
+            row.get::<_, i64>(4).map_err(query_error)?,
+            row.get::<_, i64>(5).map_err(query_error)?,
+            row.get::<_, i64>(6).map_err(query_error)?,
+            row.get::<_, f64>(7).map_err(query_error)?,
+        ));
+        count += 1;
+    }
+
+    std::fs::write(output, csv).map_err(|e| tr!("ledger-export-failed", error = e))?;
+    Ok(count)
+}
+
+// ============= Budgets =============
+
+fn period_start(period: BudgetPeriod, now: DateTime<Utc>) -> DateTime<Utc> {
+    let today = now.date_naive();
+    let day = match period {
+        BudgetPeriod::Daily => today,
+        BudgetPeriod::Monthly => today.with_day(1).unwrap_or(today),
+    };
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn read_budgets(conn: &Connection) -> Result<Vec<UsageBudget>, String> {
+    let mut statement = conn
+        .prepare("SELECT id, project_id, period, limit_usd, action FROM usage_budgets ORDER BY id")
+        .map_err(query_error)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .map_err(query_error)?;
+
+    let mut budgets = Vec::new();
+    for row in rows {
+        let (id, project_id, period, limit_usd, action) = row.map_err(query_error)?;
+        // Enums are stored by their serde names
+        let period = serde_json::from_value(serde_json::Value::String(period));
+        let action = serde_json::from_value(serde_json::Value::String(action));
+        match (period, action) {
+            (Ok(period), Ok(action)) => budgets.push(UsageBudget {
+                id: Some(id),
+                project_id,
+                period,
+                limit_usd,
+                action,
+            }),
+            _ => warn!("Skipping usage budget {} with unknown period or action", id),
+        }
+    }
+    Ok(budgets)
+}
+
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// All budgets with what has been spent in their current period
+pub fn budget_statuses(database: &Path, now: DateTime<Utc>) -> Result<Vec<BudgetStatus>, String> {
+    let conn = open(database)?;
+    read_budgets(&conn)?
+        .into_iter()
+        .map(|budget| {
+            let start = period_start(budget.period, now);
+            let spent_usd: f64 = conn
+                .query_row(
+                    "SELECT COALESCE(SUM(cost_usd), 0) FROM api_usage
+                     WHERE at >= ?1 AND (?2 IS NULL OR project_id = ?2)",
+                    params![timestamp(&start), budget.project_id],
+                    |row| row.get(0),
+                )
+                .map_err(query_error)?;
+            Ok(BudgetStatus {
+                exceeded: spent_usd >= budget.limit_usd,
+                period_start: start,
+                spent_usd,
+                budget,
+            })
+        })
+        .collect()
+}
+
+/// Exceeded budgets that cover `project_id`
+pub fn exceeded_budgets(
+    database: &Path,
+    project_id: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<BudgetStatus>, String> {
+    Ok(budget_statuses(database, now)?
+        .into_iter()
+        .filter(|status| {
+            status.exceeded
+                && !matches!(status.budget.project_id.as_deref(), Some(id) if id != project_id)
+        })
+        .collect())
+}
+
+pub fn save_budget(database: &Path, budget: &UsageBudget) -> Result<UsageBudget, String> {
+    if !budget.limit_usd.is_finite() || budget.limit_usd <= 0.0 {
+        return Err(tr!("ledger-budget-invalid-limit"));
+    }
+    let conn = open(database)?;
+    let period = enum_name(&budget.period);
+    let action = enum_name(&budget.action);
+
+    let id = match budget.id {
+        Some(id) => {
+            let updated = conn
+                .execute(
+                    "UPDATE usage_budgets SET project_id = ?1, period = ?2, limit_usd = ?3, action = ?4
+                     WHERE id = ?5",
+                    params![budget.project_id, period, budget.limit_usd, action, id],
+                )
+                .map_err(query_error)?;
+            if updated == 0 {
+                return Err(tr!("ledger-budget-not-found", id = id));
+            }
+            id
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO usage_budgets (project_id, period, limit_usd, action)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![budget.project_id, period, budget.limit_usd, action],
+            )
+            .map_err(query_error)?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    Ok(UsageBudget {
+        id: Some(id),
+        ..budget.clone()
+    })
+}
+
+pub fn delete_budget(database: &Path, id: i64) -> Result<(), String> {
+    let conn = open(database)?;
+    let exists = conn
+        .query_row("SELECT id FROM usage_budgets WHERE id = ?1", [id], |row| {
+            row.get::<_, i64>(0)
+        })
+        .optional()
+        .map_err(query_error)?;
+    if exists.is_none() {
+        return Err(tr!("ledger-budget-not-found", id = id));
+    }
+    conn.execute("DELETE FROM usage_budgets WHERE id = ?1", [id])
+        .map_err(query_error)?;
+    Ok(())
+}
+
+/// Register the callback told about budgets that were just exceeded
+pub fn on_budget_exceeded(listener: impl Fn(&BudgetStatus) + Send + Sync + 'static) {
+    if LISTENER.set(Box::new(listener)).is_err() {
+        warn!("Budget listener already registered");
+    }
+}
+
+/// Tell the listener about exceeded budgets, once per budget and period
+pub fn notify_exceeded(statuses: &[BudgetStatus]) {
+    let mut notified = NOTIFIED.lock().unwrap();
+    let notified = notified.get_or_insert_with(HashSet::new);
+    for status in statuses {
+        let key = (
+            status.budget.id.unwrap_or_default(),
+            timestamp(&status.period_start),
+        );
+        if notified.insert(key) {
+            warn!(
+                "Usage budget {:?} exceeded: {:.2} of {:.2} USD",
+                status.budget.id, status.spent_usd, status.budget.limit_usd
+            );
+            if let Some(listener) = LISTENER.get() {
+                listener(status);
+            }
+        }
+    }
+}
+
+// ============= Tauri Commands =============
+
+fn current_database() -> Result<PathBuf, String> {
+    database_path(&config::load_settings().storage)
+}
+
+#[tauri::command]
+pub fn get_usage_report(
+    group_by: ReportGrouping,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    project_id: Option<String>,
+) -> Result<Vec<UsageReportRow>, String> {
+    report(
+        &current_database()?,
+        group_by,
+        from,
+        to,
+        project_id.as_deref(),
+    )
+}
+
+#[tauri::command]
+pub fn export_usage_csv(
+    path: PathBuf,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<usize, String> {
+    let output = config::validate_path(&path)?;
+    export_csv(&current_database()?, &output, from, to)
+}
+
+#[tauri::command]
+pub fn get_usage_budgets() -> Result<Vec<BudgetStatus>, String> {
+    budget_statuses(&current_database()?, Utc::now())
+}
+
+#[tauri::command]
+pub fn save_usage_budget(budget: UsageBudget) -> Result<UsageBudget, String> {
+    save_budget(&current_database()?, &budget)
+}
+
+#[tauri::command]
+pub fn delete_usage_budget(id: i64) -> Result<(), String> {
+    delete_budget(&current_database()?, id)
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn test_database(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("autodoc-usage-ledger-test-{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("autodoc.db")
+    }
+
+    fn usage_at(at: DateTime<Utc>, project: &str, model: &str, input: u64) -> UsageRecord {
+        UsageRecord {
+            at,
+            ..UsageRecord::new(project, "s1", model, input, 1_000, 1)
+        }
+    }
+
+    #[test]
+    fn test_migrations_apply_once_and_writes_wait_for_locks() {
+        let database = test_database("migrations");
+        let usage = UsageRecord::new("p1", "s1", "claude-sonnet-4-20250514", 10, 1, 0);
+        record(&database, &usage).unwrap();
+
+        // Another writer, like the backend, holds the database for a moment
+        let backend = Connection::open(&database).unwrap();
+        backend.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            backend.execute_batch("COMMIT").unwrap();
+        });
+        record(&database, &usage).unwrap();
+        writer.join().unwrap();
+
+        let conn = open(&database).unwrap();
+        let versions: Vec<i64> = conn
+            .prepare("SELECT version FROM schema_migrations")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(versions, vec![1]);
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM api_usage", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[test]
+    fn test_report_groups_by_day_project_and_model() {
+        let database = test_database("report");
+        let day1 = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        let day2 = Utc.with_ymd_and_hms(2026, 3, 2, 23, 59, 0).unwrap();
+        record(
+            &database,
+            &usage_at(day1, "p1", "claude-sonnet-4-20250514", 1_000_000),
+        )
+        .unwrap();
+        record(
+            &database,
+            &usage_at(day1, "p2", "claude-opus-4-20250514", 1_000),
+        )
+        .unwrap();
+        record(
+            &database,
+            &usage_at(day2, "p1", "claude-sonnet-4-20250514", 1_000),
+        )
+        .unwrap();
+
+        let by_day = report(&database, ReportGrouping::Day, None, None, None).unwrap();
+        assert_eq!(
+            by_day
+                .iter()
+                .map(|r| (r.key.as_str(), r.requests))
+                .collect::<Vec<_>>(),
+            vec![("2026-03-01", 2), ("2026-03-02", 1)]
+        );
+
+        let by_model = report(&database, ReportGrouping::Model, None, None, Some("p1")).unwrap();
+        assert_eq!(by_model.len(), 1);
+        assert_eq!(by_model[0].input_tokens, 1_001_000);
+        assert_eq!(by_model[0].images, 2);
+        // 1.001M input at $3 plus 2k output at $15 per million
+        assert!((by_model[0].cost_usd - 3.033).abs() < 1e-9);
+
+        let by_project =
+            report(&database, ReportGrouping::Project, Some(day2), None, None).unwrap();
+        assert_eq!(by_project.len(), 1);
+        assert_eq!(by_project[0].key, "p1");
+
+        let _ = std::fs::remove_dir_all(database.parent().unwrap());
+    }
+
+    #[test]
+    fn test_budgets_cover_current_period_and_project() {
+        let database = test_database("budgets");
+        let now = Utc.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap();
+        let last_month = Utc.with_ymd_and_hms(2026, 2, 28, 12, 0, 0).unwrap();
+        // $3 each
+        record(&database, &usage_at(last_month, "p1", "sonnet", 1_000_000)).unwrap();
+        record(&database, &usage_at(now, "p1", "sonnet", 1_000_000)).unwrap();
+
+        let monthly = save_budget(
+            &database,
+            &UsageBudget {
+                id: None,
+                project_id: Some("p1".to_string()),
+                period: BudgetPeriod::Monthly,
+                limit_usd: 3.0,
+                action: BudgetAction::Pause,
+            },
+        )
+        .unwrap();
+        let global = save_budget(
+            &database,
+            &UsageBudget {
+                id: None,
+                project_id: None,
+                period: BudgetPeriod::Daily,
+                limit_usd: 10.0,
+                action: BudgetAction::Warn,
+            },
+        )
+        .unwrap();
+
+        let exceeded = exceeded_budgets(&database, "p1", now).unwrap();
+        assert_eq!(exceeded.len(), 1);
+        assert_eq!(exceeded[0].budget, monthly);
+        assert!((exceeded[0].spent_usd - 3.015).abs() < 1e-9);
+        assert!(exceeded_budgets(&database, "p2", now).unwrap().is_empty());
+
+        save_budget(
+            &database,
+            &UsageBudget {
+                limit_usd: 5.0,
+                ..monthly.clone()
+            },
+        )
+        .unwrap();
+        assert!(exceeded_budgets(&database, "p1", now).unwrap().is_empty());
+
+        delete_budget(&database, global.id.unwrap()).unwrap();
+        assert_eq!(budget_statuses(&database, now).unwrap().len(), 1);
+        assert!(delete_budget(&database, 999).is_err());
+        assert!(save_budget(
+            &database,
+            &UsageBudget {
+                limit_usd: 0.0,
+                ..monthly
+            }
+        )
+        .is_err());
+
+        let _ = std::fs::remove_dir_all(database.parent().unwrap());
+    }
+
+    #[test]
+    fn test_csv_export_quotes_and_neutralises_formulas() {
+        let database = test_database("csv");
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap();
+        record(&database, &usage_at(at, "=HYPERLINK(\"x\")", "sonnet", 10)).unwrap();
+        record(&database, &usage_at(at, "a,b", "haiku", 10)).unwrap();
+
+        let output = database.with_file_name("usage.csv");
+        assert_eq!(export_csv(&database, &output, None, None).unwrap(), 2);
+
+        let csv = std::fs::read_to_string(&output).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,project_id"));
+        assert!(lines[1].starts_with(
+            "2026-03-01T10:00:00.000Z,\"'=HYPERLINK(\"\"x\"\")\",s1,sonnet,10,1000,1,"
+        ));
+        assert!(lines[2].contains(",\"a,b\",s1,haiku,"));
+
+        let _ = std::fs::remove_dir_all(database.parent().unwrap());
+    }
+}
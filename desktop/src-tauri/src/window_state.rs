@@ -0,0 +1,200 @@
+use crate::i18n::tr;
+use crate::{api_gateway, autostart, config, sidecar};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, CloseRequestApi, Manager, Runtime, Window};
+
+const STATE_FILE_NAME: &str = "window_state.json";
+const MAIN_WINDOW: &str = "main";
+
+/// UI state that outlives a session, kept next to the config file
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct WindowState {
+    /// Whether the main window was visible when last shown or hidden
+    pub visible: bool,
+    /// Whether the "still running in the tray" notice has been shown
+    pub tray_notice_shown: bool,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        WindowState {
+            visible: true,
+            tray_notice_shown: false,
+        }
+    }
+}
+
+// ============= State File =============
+
+fn load_from(path: &Path) -> WindowState {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_to(path: &Path, state: &WindowState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| tr!("window-state-serialize-failed", error = e))?;
+    std::fs::write(path, content).map_err(|e| tr!("window-state-write-failed", error = e))
+}
+
+fn load() -> WindowState {
+    match config::config_dir() {
+        Ok(dir) => load_from(&dir.join(STATE_FILE_NAME)),
+        Err(_) => WindowState::default(),
+    }
+}
+
+/// Apply `change` to the stored state; failures only cost the remembered state
+fn update(change: impl FnOnce(&mut WindowState)) {
+    let result = config::config_dir().and_then(|dir| {
+        let path = dir.join(STATE_FILE_NAME);
+        let mut state = load_from(&path);
+        change(&mut state);
+        save_to(&path, &state)
+    });
+    if let Err(e) = result {
+        warn!("Failed to save window state: {}", e);
+    }
+}
+
+// ============= Startup =============
+
+/// Whether the window should stay hidden at launch: on `--minimized` (used by
+/// autostart), or when the last session ended in the tray and tray mode is on
+fn should_start_hidden(args: &[String], minimize_to_tray: bool, state: &WindowState) -> bool {
+    args.iter().any(|arg| arg == autostart::MINIMIZED_FLAG) || (minimize_to_tray && !state.visible)
+}
+
+/// The main window is created hidden (see tauri.conf.json); show it unless
+/// this launch should start in the tray
+pub fn apply_startup_visibility<R: Runtime>(app: &AppHandle<R>) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let minimize_to_tray = config::load_settings().basic.minimize_to_tray;
+
+    if should_start_hidden(&args, minimize_to_tray, &load()) {
+        info!("Starting minimized to tray");
+    } else {
+        set_main_visible(app, true);
+    }
+}
+
+// ============= Visibility =============
+
+pub fn set_main_visible<R: Runtime>(app: &AppHandle<R>, visible: bool) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        if visible {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        } else {
+            let _ = window.hide();
+        }
+    }
+    update(|state| state.visible = visible);
+}
+
+pub fn toggle_main<R: Runtime>(app: &AppHandle<R>) {
+    let visible = app
+        .get_webview_window(MAIN_WINDOW)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or(false);
+    set_main_visible(app, !visible);
+}
+
+// ============= Close & Shutdown =============
+
+/// Quit the app from the tray or the main window
+pub fn shutdown<R: Runtime>(app: &AppHandle<R>) {
+    info!("Shutting down...");
+    app.exit(0);
+}
+
+/// Stop the backend sidecar and the API gateway. Runs on `RunEvent::Exit`, which
+/// every quit path ends in, so the Node process is never orphaned.
+pub fn stop_services<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(backend) = app.try_state::<sidecar::BackendProcess>() {
+        if backend.is_running() {
+            if let Err(e) = backend.stop() {
+                warn!("Failed to stop backend during shutdown: {}", e);
+            }
+        }
+    }
+    api_gateway::stop();
+}
+
+/// Close button on the main window: hide to the tray or quit, per `minimize_to_tray`
+pub fn handle_close_requested<R: Runtime>(window: &Window<R>, api: &CloseRequestApi) {
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+
+    // Either way we decide what happens, not the default close
+    api.prevent_close();
+    let app = window.app_handle();
+
+    if !config::load_settings().basic.minimize_to_tray {
+        shutdown(app);
+        return;
+    }
+
+    set_main_visible(app, false);
+
+    if !load().tray_notice_shown {
+        update(|state| state.tray_notice_shown = true);
+        use tauri_plugin_dialog::DialogExt;
+        app.dialog()
+            .message(tr!("tray-still-running"))
+            .title("AutoDoc Agent")
+            .show(|_| {});
+    }
+}
+
+// ============= Tests =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_roundtrip_and_defaults() {
+        let dir = std::env::temp_dir().join("autodoc-window-state-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(STATE_FILE_NAME);
+
+        // Missing or unreadable file means a visible window and no notice shown yet
+        assert_eq!(load_from(&path), WindowState::default());
+        std::fs::write(&path, "garbage").unwrap();
+        assert_eq!(load_from(&path), WindowState::default());
+
+        let state = WindowState {
+            visible: false,
+            tray_notice_shown: true,
+        };
+        save_to(&path, &state).unwrap();
+        assert_eq!(load_from(&path), state);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_should_start_hidden() {
+        let visible = WindowState::default();
+        let hidden = WindowState {
+            visible: false,
+            ..WindowState::default()
+        };
+        let minimized = vec!["--minimized".to_string()];
+
+        assert!(!should_start_hidden(&[], true, &visible));
+        assert!(should_start_hidden(&minimized, false, &visible));
+        assert!(should_start_hidden(&[], true, &hidden));
+        // Without tray mode a hidden window would be unreachable
+        assert!(!should_start_hidden(&[], false, &hidden));
+    }
+}
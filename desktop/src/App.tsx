@@ -17,9 +17,25 @@ interface AppConfig {
   };
   auth: {
     claude_api_key: string;
+    llm_provider?: LlmProvider;
+    claude_keys?: { id: string; name: string; enabled?: boolean }[];
     claude_model: string;
     chrome_mcp_url: string;
     chrome_mcp_port: number;
+    targets?: {
+      id: string;
+      name: string;
+      entry_url: string;
+      auth_type: "none" | "form" | "http_basic" | "bearer_token" | "cookie";
+      username?: string;
+      login_url?: string;
+      selectors?: {
+        username_field?: string;
+        password_field?: string;
+        submit_button?: string;
+      };
+      secret?: string;
+    }[];
   };
   exploration: {
     strategy: string;
@@ -35,6 +51,9 @@ interface AppConfig {
     enable_compression: boolean;
     auto_cleanup: boolean;
     retention_days: number;
+    allowed_roots?: string[];
+    min_free_space_mb?: number;
+    retention_action?: "delete" | "archive";
   };
   advanced: {
     log_level: string;
@@ -42,9 +61,57 @@ interface AppConfig {
     concurrent_tabs: number;
     api_rate_limit: number;
     proxy_url?: string;
+    no_proxy?: string;
+    proxy_username?: string;
+    proxy_password?: string;
+    ca_bundle_path?: string;
+    credential_rotation_days?: number;
+    credential_expiry_warning_days?: number;
+    endpoints?: {
+      claude_api_base: string;
+      google_auth_url: string;
+      google_token_url: string;
+    };
   };
 }
 
+interface CredentialStatus {
+  key: string;
+  policy: "write_only" | "masked_read" | "internal_only";
+  present: boolean;
+  hint?: string;
+  updated_at?: string;
+}
+
+type LlmProvider = "anthropic" | "openai_compatible" | "ollama";
+
+/** Keychain entry of each provider's key, as in llm_provider.rs */
+const PROVIDER_SECRET_KEYS: Record<LlmProvider, string> = {
+  anthropic: "claude_api_key",
+  openai_compatible: "openai_compatible_api_key",
+  ollama: "ollama_api_key",
+};
+
+/** 所選服務提供者是否已有可用的金鑰；Ollama 不需要金鑰，Claude 也可以只設定金鑰池 */
+async function hasLlmCredential(cfg: AppConfig): Promise<boolean> {
+  const provider = cfg.auth.llm_provider ?? "anthropic";
+  if (provider === "ollama") {
+    return true;
+  }
+  const keys = [PROVIDER_SECRET_KEYS[provider]];
+  if (provider === "anthropic") {
+    for (const key of cfg.auth.claude_keys ?? []) {
+      if (key.enabled !== false) {
+        keys.push(`claude_api_key:${key.id}`);
+      }
+    }
+  }
+  const statuses = await Promise.all(
+    keys.map((key) => invoke<CredentialStatus>("get_credential_status", { key }))
+  );
+  return statuses.some((status) => status.present);
+}
+
 function App() {
   const [config, setConfig] = useState<AppConfig | null>(null);
   const [showSettings, setShowSettings] = useState(false);
@@ -54,24 +121,89 @@ function App() {
   useEffect(() => {
     // 載入配置
     loadConfig();
+    reportLegacySecrets();
 
     // 監聽系統托盤的設定事件
     const unlisten = listen("open-settings", () => {
       setShowSettings(true);
     });
 
+    // 儲存空間不足時提醒使用者
+    const unlistenLowSpace = listen<{ message: string }>(
+      "storage-low-space",
+      (event) => {
+        message.warning(event.payload.message, 10);
+      }
+    );
+
+    // Claude 用量超過預算時提醒使用者
+    const unlistenBudget = listen<{
+      budget: { project_id: string | null; limit_usd: number; action: "warn" | "pause" };
+      spent_usd: number;
+    }>("usage-budget-exceeded", (event) => {
+      const { budget, spent_usd } = event.payload;
+      const scope = budget.project_id ? `專案 ${budget.project_id} ` : "";
+      const paused = budget.action === "pause" ? "，已暫停 Claude 請求" : "";
+      message.warning(
+        `${scope}Claude 用量已超過預算：${spent_usd.toFixed(2)} / ${budget.limit_usd.toFixed(2)} USD${paused}`,
+        10
+      );
+    });
+
+    // 憑證需要輪替或即將到期時提醒使用者
+    const unlistenCredential = listen<{
+      label: string;
+      reminder:
+        | { kind: "rotation_due"; age_days: number }
+        | { kind: "expiring_soon"; expires_at: string }
+        | { kind: "expired"; expires_at: string };
+    }>("credential-reminder", (event) => {
+      const { label, reminder } = event.payload;
+      if (reminder.kind === "rotation_due") {
+        message.warning(`${label} 已 ${reminder.age_days} 天未更換，建議輪替`, 10);
+      } else {
+        const date = new Date(reminder.expires_at).toLocaleDateString();
+        const state = reminder.kind === "expired" ? "已於" : "將於";
+        message.warning(`${label} ${state} ${date} 到期`, 10);
+      }
+    });
+
     return () => {
       unlisten.then((fn) => fn());
+      unlistenLowSpace.then((fn) => fn());
+      unlistenBudget.then((fn) => fn());
+      unlistenCredential.then((fn) => fn());
     };
   }, []);
 
+  // 啟動時若從舊版設定檔搬移了明文憑證，告知使用者
+  const reportLegacySecrets = async () => {
+    try {
+      const report = await invoke<{
+        migrated: string[];
+        discarded: string[];
+        error?: string;
+      } | null>("take_legacy_secret_report");
+      if (report?.error) {
+        message.error("無法搬移設定檔中的明文憑證: " + report.error, 10);
+      } else if (report?.migrated?.length || report?.discarded?.length) {
+        message.info(
+          `已將設定檔中的 ${report.migrated.length} 筆明文憑證移至安全儲存，並從設定檔移除`,
+          10
+        );
+      }
+    } catch (error) {
+      console.error("讀取憑證搬移結果失敗:", error);
+    }
+  };
+
   const loadConfig = async () => {
     try {
       const cfg = await invoke<AppConfig>("load_config");
       setConfig(cfg);
 
-      // 檢查是否首次啟動（API Key 為空）
-      if (!cfg.auth.claude_api_key) {
+      // 檢查是否首次啟動（所選服務提供者尚未儲存 API Key）
+      if (!(await hasLlmCredential(cfg))) {
         setShowWizard(true);
       }
     } catch (error) {
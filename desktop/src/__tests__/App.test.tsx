@@ -34,7 +34,7 @@ describe('App Component', () => {
         check_updates: true,
       },
       auth: {
-        claude_api_key: '', // Empty API key triggers wizard
+        claude_api_key: '', // Secrets never come back from load_config
         claude_model: 'claude-sonnet-4-20250514',
         chrome_mcp_url: 'http://localhost',
         chrome_mcp_port: 3001,
@@ -62,7 +62,13 @@ describe('App Component', () => {
       },
     }
 
-    vi.mocked(invoke).mockResolvedValue(mockConfig)
+    vi.mocked(invoke).mockImplementation((cmd) =>
+      Promise.resolve(
+        cmd === 'get_credential_status'
+          ? { key: 'claude_api_key', policy: 'masked_read', present: false }
+          : mockConfig
+      )
+    )
 
     render(<App />)
 
@@ -81,7 +87,7 @@ describe('App Component', () => {
         check_updates: true,
       },
       auth: {
-        claude_api_key: 'sk-ant-api03-test123', // API key configured
+        claude_api_key: '',
         claude_model: 'claude-sonnet-4-20250514',
         chrome_mcp_url: 'http://localhost',
         chrome_mcp_port: 3001,
@@ -109,7 +115,13 @@ describe('App Component', () => {
       },
     }
 
-    vi.mocked(invoke).mockResolvedValue(mockConfig)
+    vi.mocked(invoke).mockImplementation((cmd) =>
+      Promise.resolve(
+        cmd === 'get_credential_status'
+          ? { key: 'claude_api_key', policy: 'masked_read', present: true, hint: 'sk-…t123' }
+          : mockConfig
+      )
+    )
 
     render(<App />)
 
@@ -117,4 +129,73 @@ describe('App Component', () => {
       expect(screen.getByText('AutoDoc Agent')).toBeInTheDocument()
     })
   })
+
+  it('skips the wizard for providers and pools that need no Claude key', async () => {
+    const mockConfig = {
+      basic: {
+        app_name: 'AutoDoc Agent',
+        language: 'zh-TW',
+        auto_start: false,
+        minimize_to_tray: true,
+        check_updates: true,
+      },
+      auth: {
+        claude_api_key: '',
+        claude_model: 'claude-sonnet-4-20250514',
+        chrome_mcp_url: 'http://localhost',
+        chrome_mcp_port: 3001,
+        llm_provider: 'anthropic',
+        claude_keys: [{ id: 'team-a', name: 'Team A', rate_limit: 50, enabled: true }],
+      },
+      exploration: {
+        strategy: 'importance',
+        max_depth: 5,
+        max_pages: 100,
+        screenshot_quality: 'medium',
+        wait_for_network_idle: true,
+      },
+      storage: {
+        snapshot_storage_path: '~/Documents/AutoDoc/snapshots',
+        screenshot_storage_path: '~/Documents/AutoDoc/screenshots',
+        database_path: '~/Documents/AutoDoc/autodoc.db',
+        enable_compression: true,
+        auto_cleanup: false,
+        retention_days: 0,
+      },
+      advanced: {
+        log_level: 'info',
+        enable_telemetry: false,
+        concurrent_tabs: 3,
+        api_rate_limit: 20,
+      },
+    }
+
+    // Only the pooled key is stored
+    vi.mocked(invoke).mockImplementation((cmd, args) =>
+      Promise.resolve(
+        cmd === 'get_credential_status'
+          ? {
+              key: (args as { key: string }).key,
+              policy: 'masked_read',
+              present: (args as { key: string }).key === 'claude_api_key:team-a',
+            }
+          : mockConfig
+      )
+    )
+
+    const { unmount } = render(<App />)
+    await waitFor(() => {
+      expect(screen.getByText('AutoDoc Agent')).toBeInTheDocument()
+    })
+    expect(screen.queryByText(/歡迎使用 AutoDoc Agent/i)).not.toBeInTheDocument()
+    unmount()
+
+    // Ollama runs without a key
+    mockConfig.auth = { ...mockConfig.auth, llm_provider: 'ollama', claude_keys: [] }
+    render(<App />)
+    await waitFor(() => {
+      expect(screen.getByText('AutoDoc Agent')).toBeInTheDocument()
+    })
+    expect(screen.queryByText(/歡迎使用 AutoDoc Agent/i)).not.toBeInTheDocument()
+  })
 })
@@ -1,6 +1,181 @@
-import { Form, Input, Select, Slider, Switch } from "antd";
+import { useEffect, useState } from "react";
+import {
+  Alert,
+  Button,
+  Form,
+  Input,
+  InputNumber,
+  Modal,
+  Select,
+  Slider,
+  Space,
+  Switch,
+  Table,
+  Tag,
+  message,
+} from "antd";
+import { save } from "@tauri-apps/plugin-dialog";
+import { invoke } from "@tauri-apps/api/core";
+
+type SecretBackend = "keyring" | "file_vault";
+type SecretBackendPreference = "auto" | SecretBackend;
+
+interface SecretStoreDiagnostics {
+  preference: SecretBackendPreference;
+  active: SecretBackend;
+  locked: boolean;
+  keyring_available: boolean;
+  keyring_error?: string;
+  reasons: string[];
+  vault_path?: string;
+  vault_exists: boolean;
+}
+
+type CredentialReminder =
+  | { kind: "rotation_due"; age_days: number }
+  | { kind: "expiring_soon"; expires_at: string }
+  | { kind: "expired"; expires_at: string };
+
+interface CredentialInfo {
+  key: string;
+  label: string;
+  policy: "write_only" | "masked_read" | "internal_only";
+  present: boolean;
+  created_at?: string;
+  updated_at?: string;
+  last_used_at?: string;
+  expires_at?: string;
+  source?: "settings" | "import" | "migration" | "app";
+  reminder?: CredentialReminder;
+}
+
+const SOURCE_NAMES: Record<NonNullable<CredentialInfo["source"]>, string> = {
+  settings: "設定視窗",
+  import: "匯入",
+  migration: "自動搬移",
+  app: "應用程式",
+};
+
+const formatTime = (value?: string) => (value ? new Date(value).toLocaleString() : "—");
+
+function reminderTag(reminder?: CredentialReminder) {
+  switch (reminder?.kind) {
+    case "rotation_due":
+      return <Tag color="orange">建議輪替 ({reminder.age_days} 天)</Tag>;
+    case "expiring_soon":
+      return <Tag color="orange">即將到期</Tag>;
+    case "expired":
+      return <Tag color="red">已到期</Tag>;
+    default:
+      return <Tag color="green">正常</Tag>;
+  }
+}
+
+const BACKEND_NAMES: Record<SecretBackendPreference, string> = {
+  auto: "自動偵測",
+  keyring: "系統鑰匙圈",
+  file_vault: "加密檔案",
+};
 
 function AdvancedSettingsTab() {
+  const form = Form.useFormInstance();
+  const [secretStore, setSecretStore] = useState<SecretStoreDiagnostics | null>(null);
+  const [vaultPassphrase, setVaultPassphrase] = useState("");
+  const [migrationTarget, setMigrationTarget] = useState<SecretBackendPreference>("file_vault");
+  const [migrating, setMigrating] = useState(false);
+  const [credentials, setCredentials] = useState<CredentialInfo[]>([]);
+
+  const refreshSecretStore = () =>
+    invoke<SecretStoreDiagnostics>("get_secret_store_diagnostics")
+      .then(setSecretStore)
+      .catch(() => setSecretStore(null));
+
+  const refreshCredentials = () =>
+    invoke<CredentialInfo[]>("list_credentials")
+      .then(setCredentials)
+      .catch(() => setCredentials([]));
+
+  useEffect(() => {
+    refreshSecretStore();
+    refreshCredentials();
+  }, []);
+
+  const handleSetExpiry = async (key: string, date: string) => {
+    try {
+      await invoke("set_credential_expiry", {
+        key,
+        expiresAt: date ? new Date(date).toISOString() : null,
+      });
+      refreshCredentials();
+    } catch (error) {
+      message.error("設定到期日失敗: " + error);
+    }
+  };
+
+  const handleUnlockVault = async () => {
+    try {
+      await invoke("unlock_secret_store", { passphrase: vaultPassphrase });
+      setVaultPassphrase("");
+      message.success("已解鎖加密檔案");
+      refreshSecretStore();
+    } catch (error) {
+      message.error("解鎖失敗: " + error);
+    }
+  };
+
+  const handleMigrateSecrets = async () => {
+    setMigrating(true);
+    try {
+      const count = await invoke<number>("migrate_secret_store", {
+        target: migrationTarget,
+        passphrase: vaultPassphrase || null,
+      });
+      // Keep the next settings save from restoring the old choice
+      form.setFieldValue(["advanced", "secret_backend"], migrationTarget);
+      setVaultPassphrase("");
+      message.success(`已搬移 ${count} 筆憑證至${BACKEND_NAMES[migrationTarget]}`);
+      refreshSecretStore();
+    } catch (error) {
+      message.error("搬移失敗: " + error);
+    } finally {
+      setMigrating(false);
+    }
+  };
+
+  const handleExportTelemetry = async () => {
+    const path = await save({
+      defaultPath: "autodoc-telemetry.jsonl",
+      filters: [{ name: "JSONL", extensions: ["jsonl"] }],
+    });
+    if (!path) {
+      return;
+    }
+    try {
+      const count = await invoke<number>("export_telemetry", { path });
+      message.success(`已匯出 ${count} 筆紀錄`);
+    } catch (error) {
+      message.error("匯出失敗: " + error);
+    }
+  };
+
+  const handlePurgeTelemetry = () => {
+    Modal.confirm({
+      title: "清除使用統計？",
+      content: "所有本機使用統計紀錄將被刪除，無法復原。",
+      okText: "清除",
+      okButtonProps: { danger: true },
+      cancelText: "取消",
+      onOk: async () => {
+        try {
+          await invoke("purge_telemetry");
+          message.success("已清除使用統計");
+        } catch (error) {
+          message.error("清除失敗: " + error);
+        }
+      },
+    });
+  };
+
   return (
     <div className="space-y-6">
       <div>
@@ -58,15 +233,178 @@ function AdvancedSettingsTab() {
 
         <Form.Item
           name={["advanced", "enable_telemetry"]}
-          label="啟用匿名使用統計"
+          label="記錄本機使用統計"
           valuePropName="checked"
+          extra="僅儲存在本機，不含個人資料或密鑰，也不會上傳"
         >
           <Switch />
         </Form.Item>
 
-        <Form.Item name={["advanced", "proxy_url"]} label="HTTP 代理 (選填)">
-          <Input placeholder="http://proxy.example.com:8080" />
+        <Form.Item>
+          <Button onClick={handleExportTelemetry}>匯出紀錄</Button>
+          <Button className="ml-2" danger onClick={handlePurgeTelemetry}>
+            清除紀錄
+          </Button>
+        </Form.Item>
+
+        <Form.Item name={["advanced", "proxy_url"]} label="代理伺服器 (選填)">
+          <Input placeholder="http://proxy.example.com:8080 或 socks5://proxy.example.com:1080" />
+        </Form.Item>
+
+        <Form.Item name={["advanced", "no_proxy"]} label="不使用代理的主機 (選填)">
+          <Input placeholder="internal.example.com,.corp.example.com" />
         </Form.Item>
+
+        <Form.Item name={["advanced", "proxy_username"]} label="代理帳號 (選填)">
+          <Input />
+        </Form.Item>
+
+        <Form.Item
+          name={["advanced", "proxy_password"]}
+          label="代理密碼 (選填)"
+          extra="密碼將安全儲存於系統鑰匙圈"
+        >
+          <Input.Password />
+        </Form.Item>
+
+        <Form.Item name={["advanced", "ca_bundle_path"]} label="自訂 CA 憑證檔 (選填)">
+          <Input placeholder="/path/to/corporate-ca.pem" />
+        </Form.Item>
+      </div>
+
+      <div>
+        <h3 className="text-lg font-semibold mb-4">憑證儲存</h3>
+
+        {/* Changed only through migration so secrets and setting stay in step */}
+        <Form.Item name={["advanced", "secret_backend"]} hidden>
+          <Input />
+        </Form.Item>
+
+        {secretStore && (
+          <>
+            <p className="mb-2">
+              目前使用: {BACKEND_NAMES[secretStore.active]}
+              {secretStore.preference === "auto" && " (自動偵測)"}
+              {secretStore.active === "file_vault" && secretStore.vault_path && (
+                <span className="text-gray-500"> — {secretStore.vault_path}</span>
+              )}
+            </p>
+
+            {!secretStore.keyring_available && (
+              <Alert
+                type="warning"
+                className="mb-4"
+                message="無法使用系統鑰匙圈"
+                description={
+                  <ul className="list-disc pl-4">
+                    {secretStore.reasons.map((reason) => (
+                      <li key={reason}>{reason}</li>
+                    ))}
+                  </ul>
+                }
+              />
+            )}
+
+            {secretStore.locked && (
+              <Alert
+                type="error"
+                className="mb-4"
+                message="加密檔案已鎖定，輸入密碼後才能讀取 API Key 等憑證"
+              />
+            )}
+          </>
+        )}
+
+        <Form.Item
+          label="加密檔案密碼"
+          extra="解鎖或建立加密檔案時使用；無人值守環境可設定 AUTODOC_VAULT_PASSPHRASE 環境變數"
+        >
+          <Space.Compact className="w-full">
+            <Input.Password
+              value={vaultPassphrase}
+              onChange={(event) => setVaultPassphrase(event.target.value)}
+            />
+            <Button
+              onClick={handleUnlockVault}
+              disabled={!secretStore?.locked || !vaultPassphrase}
+            >
+              解鎖
+            </Button>
+          </Space.Compact>
+        </Form.Item>
+
+        <Form.Item label="搬移憑證至">
+          <Space.Compact>
+            <Select
+              value={migrationTarget}
+              onChange={setMigrationTarget}
+              className="w-40"
+              options={(["auto", "keyring", "file_vault"] as const).map((value) => ({
+                value,
+                label: BACKEND_NAMES[value],
+              }))}
+            />
+            <Button onClick={handleMigrateSecrets} loading={migrating}>
+              搬移
+            </Button>
+          </Space.Compact>
+        </Form.Item>
+
+        <Form.Item
+          name={["advanced", "credential_rotation_days"]}
+          label="輪替提醒 (天)"
+          extra="憑證超過此天數未更換時提醒；設為 0 則關閉"
+        >
+          <InputNumber min={0} max={3650} />
+        </Form.Item>
+
+        <Form.Item
+          name={["advanced", "credential_expiry_warning_days"]}
+          label="到期前提醒 (天)"
+        >
+          <InputNumber min={0} max={365} />
+        </Form.Item>
+
+        <Table
+          size="small"
+          rowKey="key"
+          pagination={false}
+          dataSource={credentials.filter((credential) => credential.present)}
+          locale={{ emptyText: "尚未儲存任何憑證" }}
+          columns={[
+            { title: "憑證", dataIndex: "label" },
+            {
+              title: "狀態",
+              render: (_, credential: CredentialInfo) => reminderTag(credential.reminder),
+            },
+            {
+              title: "最後更新",
+              render: (_, credential: CredentialInfo) => (
+                <span title={credential.source && `來源: ${SOURCE_NAMES[credential.source]}`}>
+                  {formatTime(credential.updated_at)}
+                </span>
+              ),
+            },
+            {
+              title: "最後使用",
+              render: (_, credential: CredentialInfo) => formatTime(credential.last_used_at),
+            },
+            {
+              title: "到期日",
+              render: (_, credential: CredentialInfo) =>
+                credential.policy === "internal_only" ? (
+                  "—"
+                ) : (
+                  <Input
+                    type="date"
+                    size="small"
+                    value={credential.expires_at?.slice(0, 10) ?? ""}
+                    onChange={(event) => handleSetExpiry(credential.key, event.target.value)}
+                  />
+                ),
+            },
+          ]}
+        />
       </div>
     </div>
   );
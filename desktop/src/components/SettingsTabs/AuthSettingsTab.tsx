@@ -1,7 +1,96 @@
-import { useState } from "react";
-import { Form, Input, Select, Button, Space, message, FormInstance } from "antd";
-import { EyeOutlined, EyeInvisibleOutlined, CheckCircleOutlined } from "@ant-design/icons";
-import { open } from "@tauri-apps/plugin-dialog";
+import { useState, useEffect } from "react";
+import {
+  Form,
+  Input,
+  InputNumber,
+  Select,
+  Switch,
+  Button,
+  Space,
+  Table,
+  Tag,
+  message,
+  FormInstance,
+} from "antd";
+import {
+  EyeOutlined,
+  EyeInvisibleOutlined,
+  CheckCircleOutlined,
+  PlusOutlined,
+  DeleteOutlined,
+} from "@ant-design/icons";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { invoke } from "@tauri-apps/api/core";
+
+interface ConnectionCheck {
+  id: string;
+  name: string;
+  status: "ok" | "warning" | "failed" | "skipped";
+  latency_ms: number;
+  message: string;
+  hint?: string;
+}
+
+interface GoogleAuthStatus {
+  authorized: boolean;
+  expires_at?: string;
+  scope?: string;
+}
+
+interface KeyStatus {
+  id: string;
+  name: string;
+  priority: number;
+  rate_limit: number;
+  health:
+    | { state: "healthy" }
+    | { state: "cooling_down"; until: string }
+    | { state: "invalid"; until: string };
+  requests: number;
+  rate_limited: number;
+  auth_failures: number;
+  input_tokens: number;
+  output_tokens: number;
+  last_used?: string;
+  last_error?: string;
+}
+
+interface CredentialStatus {
+  key: string;
+  present: boolean;
+  hint?: string;
+  updated_at?: string;
+}
+
+interface SessionSummary {
+  target_id: string;
+  captured_at: string;
+  expires_at?: string;
+  expired: boolean;
+}
+
+type LlmProvider = "anthropic" | "openai_compatible" | "ollama";
+
+const PROVIDER_NAMES: Record<LlmProvider, string> = {
+  anthropic: "Claude API",
+  openai_compatible: "OpenAI 相容 API",
+  ollama: "Ollama",
+};
+
+const PROVIDER_SECRET_KEYS: Record<LlmProvider, string> = {
+  anthropic: "claude_api_key",
+  openai_compatible: "openai_compatible_api_key",
+  ollama: "ollama_api_key",
+};
+
+/** Describes a stored key without revealing it; the field may stay empty to keep it */
+function storedKeyHint(status: CredentialStatus | null): string | undefined {
+  if (!status?.present) return undefined;
+  const updated = status.updated_at
+    ? `，更新於 ${new Date(status.updated_at).toLocaleString()}`
+    : "";
+  return `已儲存 ${status.hint ?? "API Key"}${updated}；留空則保留現有值`;
+}
 
 interface AuthSettingsTabProps {
   form: FormInstance;
@@ -10,21 +99,158 @@ interface AuthSettingsTabProps {
 function AuthSettingsTab({ form }: AuthSettingsTabProps) {
   const [testingConnection, setTestingConnection] = useState(false);
   const [connectionStatus, setConnectionStatus] = useState<"idle" | "success" | "error">("idle");
+  const [googleStatus, setGoogleStatus] = useState<GoogleAuthStatus | null>(null);
+  const [authorizingGoogle, setAuthorizingGoogle] = useState(false);
+  const [keyStatuses, setKeyStatuses] = useState<KeyStatus[]>([]);
+  const [storedKey, setStoredKey] = useState<CredentialStatus | null>(null);
+  const [sessions, setSessions] = useState<SessionSummary[]>([]);
+  const [sessionPassphrase, setSessionPassphrase] = useState("");
+  const provider: LlmProvider =
+    Form.useWatch(["auth", "llm_provider"], form) ?? "anthropic";
 
-  const handleTestClaudeApi = async () => {
+  useEffect(() => {
+    invoke<CredentialStatus>("get_credential_status", { key: PROVIDER_SECRET_KEYS[provider] })
+      .then(setStoredKey)
+      .catch(() => setStoredKey(null));
+  }, [provider]);
+
+  useEffect(() => {
+    invoke<GoogleAuthStatus>("get_google_auth_status")
+      .then(setGoogleStatus)
+      .catch(() => setGoogleStatus(null));
+  }, []);
+
+  useEffect(() => {
+    if (provider !== "anthropic") return;
+    const refresh = () =>
+      invoke<KeyStatus[]>("get_claude_key_pool_status")
+        .then(setKeyStatuses)
+        .catch(() => setKeyStatuses([]));
+    refresh();
+    const timer = setInterval(refresh, 5000);
+    return () => clearInterval(timer);
+  }, [provider]);
+
+  const refreshSessions = () =>
+    invoke<SessionSummary[]>("list_sessions")
+      .then(setSessions)
+      .catch(() => setSessions([]));
+
+  useEffect(() => {
+    refreshSessions();
+  }, []);
+
+  const targetName = (targetId: string) =>
+    (form.getFieldValue(["auth", "targets"]) ?? []).find(
+      (target: { id: string; name: string }) => target.id === targetId
+    )?.name ?? targetId;
+
+  const handleInvalidateSession = async (targetId: string) => {
+    try {
+      await invoke("invalidate_session", { targetId });
+      message.success("已清除登入工作階段");
+      refreshSessions();
+    } catch (error) {
+      message.error("清除工作階段失敗: " + error);
+    }
+  };
+
+  const handleExportSession = async (targetId: string) => {
+    if (!sessionPassphrase) {
+      message.error("請先輸入匯出密碼");
+      return;
+    }
+    const path = await save({
+      defaultPath: `${targetId}.autodoc-session`,
+      filters: [{ name: "AutoDoc Session", extensions: ["autodoc-session"] }],
+    });
+    if (!path) return;
+    try {
+      await invoke("export_session", { targetId, path, passphrase: sessionPassphrase });
+      message.success("工作階段已匯出");
+    } catch (error) {
+      message.error("匯出工作階段失敗: " + error);
+    }
+  };
+
+  const handleImportSession = async () => {
+    if (!sessionPassphrase) {
+      message.error("請先輸入匯出時設定的密碼");
+      return;
+    }
+    const path = await open({
+      filters: [{ name: "AutoDoc Session", extensions: ["autodoc-session"] }],
+    });
+    if (!path || typeof path !== "string") return;
+    try {
+      const targetId = await invoke<string>("import_session", {
+        path,
+        passphrase: sessionPassphrase,
+        targetId: null,
+      });
+      message.success(`已匯入「${targetName(targetId)}」的工作階段`);
+      refreshSessions();
+    } catch (error) {
+      message.error("匯入工作階段失敗: " + error);
+    }
+  };
+
+  const handleAuthorizeGoogle = async () => {
+    setAuthorizingGoogle(true);
+    try {
+      const status = await invoke<GoogleAuthStatus>("authorize_google", {
+        config: form.getFieldsValue(true),
+      });
+      setGoogleStatus(status);
+      message.success("Google 帳戶授權成功");
+    } catch (error) {
+      message.error("Google 授權失敗: " + error);
+    } finally {
+      setAuthorizingGoogle(false);
+    }
+  };
+
+  const handleRevokeGoogle = async () => {
+    try {
+      await invoke("revoke_google_authorization");
+      setGoogleStatus({ authorized: false });
+      message.success("已移除 Google 授權");
+    } catch (error) {
+      message.error("移除授權失敗: " + error);
+    }
+  };
+
+  const handleTestLlm = async () => {
+    const name = PROVIDER_NAMES[provider];
     setTestingConnection(true);
     try {
-      const apiKey = form.getFieldValue(["auth", "claude_api_key"]);
-      if (!apiKey) {
+      const apiKey =
+        provider === "anthropic"
+          ? form.getFieldValue(["auth", "claude_api_key"])
+          : form.getFieldValue(["auth", provider, "api_key"]);
+      if (!apiKey && !storedKey?.present && provider !== "ollama") {
         message.error("請先輸入 API Key");
         return;
       }
-      // 這裡可以調用後端 API 測試連線
+      const checks = await invoke<ConnectionCheck[]>("test_connections", {
+        config: form.getFieldsValue(true),
+      });
+      const checkId = provider === "anthropic" ? "claude_api" : `llm:${provider}`;
+      const llm = checks.find((check) => check.id === checkId);
+      if (!llm || llm.status === "failed") {
+        throw new Error(
+          [llm?.message, llm?.hint].filter(Boolean).join(" - ") || "未知錯誤"
+        );
+      }
       setConnectionStatus("success");
-      message.success("Claude API 連線成功");
+      if (llm.status === "warning") {
+        message.warning(`${name}: ${[llm.message, llm.hint].filter(Boolean).join(" - ")}`);
+      } else {
+        message.success(`${name} 連線成功 (${llm.latency_ms} ms)`);
+      }
     } catch (error) {
       setConnectionStatus("error");
-      message.error("Claude API 連線失敗: " + error);
+      message.error(`${name} 連線失敗: ` + error);
     } finally {
       setTestingConnection(false);
     }
@@ -51,14 +277,88 @@ function AuthSettingsTab({ form }: AuthSettingsTabProps) {
 
   return (
     <div className="space-y-8">
-      {/* Claude API */}
+      {/* LLM 服務 */}
       <div>
+        <h3 className="text-lg font-semibold mb-4">LLM 服務設定</h3>
+
+        <Form.Item name={["auth", "llm_provider"]} label="服務提供者" initialValue="anthropic">
+          <Select onChange={() => setConnectionStatus("idle")}>
+            <Select.Option value="anthropic">Claude API (Anthropic)</Select.Option>
+            <Select.Option value="openai_compatible">OpenAI 相容 API (含地端部署)</Select.Option>
+            <Select.Option value="ollama">Ollama (本機模型)</Select.Option>
+          </Select>
+        </Form.Item>
+
+        {provider !== "anthropic" && (
+          <>
+            <Form.Item
+              name={["auth", provider, "base_url"]}
+              label="API 基礎網址"
+              rules={[{ required: true, message: "請輸入 API 基礎網址" }]}
+            >
+              <Input
+                placeholder={
+                  provider === "ollama" ? "http://localhost:11434" : "https://llm.example.com/v1"
+                }
+              />
+            </Form.Item>
+
+            <Form.Item
+              name={["auth", provider, "model"]}
+              label="模型"
+              rules={[{ required: true, message: "請輸入模型名稱" }]}
+            >
+              <Input placeholder={provider === "ollama" ? "llama3.2-vision" : "gpt-4o"} />
+            </Form.Item>
+
+            <Form.Item
+              name={["auth", provider, "api_key"]}
+              label="API Key"
+              extra={
+                storedKeyHint(storedKey) ??
+                (provider === "ollama" ? "僅在伺服器需要驗證時填寫" : undefined)
+              }
+              rules={
+                provider === "ollama" || storedKey?.present
+                  ? []
+                  : [{ required: true, message: `請輸入 ${PROVIDER_NAMES[provider]} 的 API Key` }]
+              }
+            >
+              <Input.Password
+                iconRender={(visible) =>
+                  visible ? <EyeOutlined /> : <EyeInvisibleOutlined />
+                }
+                addonAfter={
+                  <Button size="small" onClick={handleTestLlm} loading={testingConnection}>
+                    測試
+                  </Button>
+                }
+              />
+            </Form.Item>
+
+            {connectionStatus === "success" && (
+              <div className="text-green-600 flex items-center gap-2 mb-4">
+                <CheckCircleOutlined /> 連線成功
+              </div>
+            )}
+          </>
+        )}
+      </div>
+
+      {/* Claude API */}
+      <div hidden={provider !== "anthropic"}>
         <h3 className="text-lg font-semibold mb-4">Claude API 設定</h3>
 
         <Form.Item
           name={["auth", "claude_api_key"]}
           label="API Key"
-          rules={[{ required: true, message: "請輸入 Claude API Key" }]}
+          extra={storedKeyHint(storedKey)}
+          rules={[
+            {
+              required: provider === "anthropic" && !storedKey?.present,
+              message: "請輸入 Claude API Key",
+            },
+          ]}
         >
           <Input.Password
             placeholder="sk-ant-api03-..."
@@ -68,7 +368,7 @@ function AuthSettingsTab({ form }: AuthSettingsTabProps) {
             addonAfter={
               <Button
                 size="small"
-                onClick={handleTestClaudeApi}
+                onClick={handleTestLlm}
                 loading={testingConnection}
               >
                 測試
@@ -93,6 +393,135 @@ function AuthSettingsTab({ form }: AuthSettingsTabProps) {
             </Select.Option>
           </Select>
         </Form.Item>
+
+        <h4 className="font-semibold mb-2">API Key 集區</h4>
+        <p className="text-gray-500 mb-4">
+          設定多把 API Key 時，請求會依優先順序分散到各金鑰，遇到速率限制或驗證失敗時自動切換；未設定時使用上方的 API Key。
+        </p>
+
+        <Form.List name={["auth", "claude_keys"]}>
+          {(fields, { add, remove }) => (
+            <>
+              {fields.map((field) => (
+                <div key={field.key} className="border rounded p-4 mb-4">
+                  <Form.Item name={[field.name, "id"]} hidden>
+                    <Input />
+                  </Form.Item>
+
+                  <Space.Compact className="w-full">
+                    <Form.Item
+                      name={[field.name, "name"]}
+                      label="名稱"
+                      className="flex-1 mb-0"
+                      rules={[{ required: true, message: "請輸入金鑰名稱" }]}
+                    >
+                      <Input placeholder="團隊 A" />
+                    </Form.Item>
+                    <Form.Item
+                      name={[field.name, "rate_limit"]}
+                      label="每分鐘請求上限"
+                      className="w-40 mb-0"
+                      rules={[{ required: true, message: "請輸入上限" }]}
+                    >
+                      <InputNumber min={1} className="w-full" />
+                    </Form.Item>
+                    <Form.Item
+                      name={[field.name, "priority"]}
+                      label="優先順序"
+                      tooltip="數字越大越優先使用，相同優先順序平均分配"
+                      className="w-32 mb-0"
+                    >
+                      <InputNumber min={0} className="w-full" />
+                    </Form.Item>
+                    <Form.Item
+                      name={[field.name, "enabled"]}
+                      label="啟用"
+                      valuePropName="checked"
+                      className="w-20 mb-0"
+                    >
+                      <Switch />
+                    </Form.Item>
+                  </Space.Compact>
+
+                  <Form.Item
+                    name={[field.name, "secret"]}
+                    label="API Key"
+                    extra="儲存於系統鑰匙圈，留空則保留現有值"
+                  >
+                    <Input.Password placeholder="sk-ant-api03-..." />
+                  </Form.Item>
+
+                  <div className="flex justify-end">
+                    <Button
+                      size="small"
+                      danger
+                      icon={<DeleteOutlined />}
+                      onClick={() => remove(field.name)}
+                    >
+                      移除
+                    </Button>
+                  </div>
+                </div>
+              ))}
+
+              <Button
+                type="dashed"
+                icon={<PlusOutlined />}
+                className="mb-4"
+                onClick={() =>
+                  add({ id: "", name: "", rate_limit: 50, priority: 0, enabled: true })
+                }
+              >
+                新增 API Key
+              </Button>
+            </>
+          )}
+        </Form.List>
+
+        {keyStatuses.length > 0 && (
+          <Table<KeyStatus>
+            size="small"
+            rowKey="id"
+            pagination={false}
+            dataSource={keyStatuses}
+            columns={[
+              { title: "金鑰", dataIndex: "name" },
+              {
+                title: "狀態",
+                render: (_, status) => {
+                  switch (status.health.state) {
+                    case "healthy":
+                      return <Tag color="green">正常</Tag>;
+                    case "cooling_down":
+                      return (
+                        <Tag color="orange">
+                          限速中至 {new Date(status.health.until).toLocaleTimeString()}
+                        </Tag>
+                      );
+                    case "invalid":
+                      return (
+                        <Tag color="red" title={status.last_error}>
+                          驗證失敗，暫停至 {new Date(status.health.until).toLocaleTimeString()}
+                        </Tag>
+                      );
+                  }
+                },
+              },
+              { title: "請求數", dataIndex: "requests" },
+              { title: "429 次數", dataIndex: "rate_limited" },
+              {
+                title: "Tokens (輸入 / 輸出)",
+                render: (_, status) =>
+                  `${status.input_tokens.toLocaleString()} / ${status.output_tokens.toLocaleString()}`,
+              },
+              {
+                title: "最後使用",
+                render: (_, status) =>
+                  status.last_used ? new Date(status.last_used).toLocaleString() : "-",
+              },
+            ]}
+          />
+        )}
       </div>
 
       {/* Google Docs API */}
@@ -113,6 +542,29 @@ function AuthSettingsTab({ form }: AuthSettingsTabProps) {
             }
           />
         </Form.Item>
+
+        <Form.Item label="Google 帳戶">
+          <Space>
+            {googleStatus?.authorized ? (
+              <>
+                <span className="text-green-600 flex items-center gap-2">
+                  <CheckCircleOutlined /> 已授權
+                </span>
+                <Button size="small" onClick={handleRevokeGoogle}>
+                  移除授權
+                </Button>
+              </>
+            ) : (
+              <Button
+                size="small"
+                onClick={handleAuthorizeGoogle}
+                loading={authorizingGoogle}
+              >
+                在瀏覽器中授權
+              </Button>
+            )}
+          </Space>
+        </Form.Item>
       </div>
 
       {/* Chrome MCP */}
@@ -137,6 +589,178 @@ function AuthSettingsTab({ form }: AuthSettingsTabProps) {
           </Form.Item>
         </Space.Compact>
       </div>
+
+      {/* Target applications */}
+      <div>
+        <h3 className="text-lg font-semibold mb-4">目標應用程式</h3>
+
+        <Form.List name={["auth", "targets"]}>
+          {(fields, { add, remove }) => (
+            <>
+              {fields.map((field) => (
+                <div key={field.key} className="border rounded p-4 mb-4">
+                  <div className="flex justify-end">
+                    <Button
+                      size="small"
+                      danger
+                      icon={<DeleteOutlined />}
+                      onClick={() => remove(field.name)}
+                    >
+                      移除
+                    </Button>
+                  </div>
+
+                  <Form.Item name={[field.name, "id"]} hidden>
+                    <Input />
+                  </Form.Item>
+
+                  <Form.Item
+                    name={[field.name, "name"]}
+                    label="名稱"
+                    rules={[{ required: true, message: "請輸入目標名稱" }]}
+                  >
+                    <Input placeholder="CRM 系統" />
+                  </Form.Item>
+
+                  <Form.Item
+                    name={[field.name, "entry_url"]}
+                    label="入口網址"
+                    rules={[{ required: true, message: "請輸入入口網址" }]}
+                  >
+                    <Input placeholder="https://app.example.com" />
+                  </Form.Item>
+
+                  <Form.Item name={[field.name, "auth_type"]} label="認證方式">
+                    <Select>
+                      <Select.Option value="none">無</Select.Option>
+                      <Select.Option value="form">登入表單</Select.Option>
+                      <Select.Option value="http_basic">HTTP Basic</Select.Option>
+                      <Select.Option value="bearer_token">Bearer Token</Select.Option>
+                      <Select.Option value="cookie">Cookie</Select.Option>
+                    </Select>
+                  </Form.Item>
+
+                  <Form.Item name={[field.name, "username"]} label="使用者名稱">
+                    <Input />
+                  </Form.Item>
+
+                  <Form.Item
+                    name={[field.name, "secret"]}
+                    label="密碼 / Token / Cookie"
+                    extra="儲存於系統鑰匙圈，留空則保留現有值"
+                  >
+                    <Input.Password />
+                  </Form.Item>
+
+                  <Form.Item name={[field.name, "login_url"]} label="登入頁網址">
+                    <Input placeholder="https://app.example.com/login" />
+                  </Form.Item>
+
+                  <Space.Compact className="w-full">
+                    <Form.Item
+                      name={[field.name, "selectors", "username_field"]}
+                      label="帳號欄位選擇器"
+                      className="flex-1 mb-0"
+                    >
+                      <Input placeholder="#username" />
+                    </Form.Item>
+                    <Form.Item
+                      name={[field.name, "selectors", "password_field"]}
+                      label="密碼欄位選擇器"
+                      className="flex-1 mb-0"
+                    >
+                      <Input placeholder="#password" />
+                    </Form.Item>
+                    <Form.Item
+                      name={[field.name, "selectors", "submit_button"]}
+                      label="送出按鈕選擇器"
+                      className="flex-1 mb-0"
+                    >
+                      <Input placeholder="button[type=submit]" />
+                    </Form.Item>
+                  </Space.Compact>
+                </div>
+              ))}
+
+              <Button
+                type="dashed"
+                icon={<PlusOutlined />}
+                onClick={() => add({ id: "", name: "", entry_url: "", auth_type: "none" })}
+              >
+                新增目標
+              </Button>
+            </>
+          )}
+        </Form.List>
+      </div>
+
+      {/* Saved browser sessions */}
+      <div>
+        <h3 className="text-lg font-semibold mb-4">已儲存的登入工作階段</h3>
+        <p className="text-gray-500 mb-4">
+          成功登入後，Cookie 與 localStorage 會加密保存在本機，下次探索時直接沿用而不必重新登入。
+        </p>
+
+        <Form.Item
+          name={["auth", "session_max_age_hours"]}
+          label="工作階段保存時數"
+          tooltip="超過此時數的工作階段會被捨棄並重新登入"
+        >
+          <InputNumber min={1} max={720} addonAfter="小時" />
+        </Form.Item>
+
+        <Table<SessionSummary>
+          size="small"
+          rowKey="target_id"
+          pagination={false}
+          dataSource={sessions}
+          locale={{ emptyText: "尚無已儲存的工作階段" }}
+          columns={[
+            { title: "目標", render: (_, session) => targetName(session.target_id) },
+            {
+              title: "擷取時間",
+              render: (_, session) => new Date(session.captured_at).toLocaleString(),
+            },
+            {
+              title: "到期",
+              render: (_, session) =>
+                session.expired ? (
+                  <Tag color="red">已過期</Tag>
+                ) : session.expires_at ? (
+                  new Date(session.expires_at).toLocaleString()
+                ) : (
+                  "-"
+                ),
+            },
+            {
+              title: "",
+              render: (_, session) => (
+                <Space>
+                  <Button size="small" onClick={() => handleExportSession(session.target_id)}>
+                    匯出
+                  </Button>
+                  <Button
+                    size="small"
+                    danger
+                    onClick={() => handleInvalidateSession(session.target_id)}
+                  >
+                    清除
+                  </Button>
+                </Space>
+              ),
+            },
+          ]}
+        />
+
+        <Space className="mt-4">
+          <Input.Password
+            placeholder="匯出 / 匯入密碼"
+            value={sessionPassphrase}
+            onChange={(e) => setSessionPassphrase(e.target.value)}
+          />
+          <Button onClick={handleImportSession}>匯入工作階段</Button>
+        </Space>
+      </div>
     </div>
   );
 }
@@ -1,6 +1,31 @@
+import { useEffect, useState } from "react";
 import { Form, Input, Select, Switch } from "antd";
+import { invoke } from "@tauri-apps/api/core";
+
+interface AutostartStatus {
+  supported: boolean;
+  enabled_in_config: boolean;
+  registered: boolean;
+  points_to_current_binary: boolean;
+}
+
+function autostartNote(status: AutostartStatus | null): string | undefined {
+  if (!status) return undefined;
+  if (!status.supported) return "此平台尚不支援開機自動啟動";
+  if (status.enabled_in_config && !status.registered) return "尚未向系統註冊，請重新儲存設定";
+  if (status.registered && !status.points_to_current_binary) return "已註冊的啟動項目指向其他安裝位置";
+  return status.registered ? "已向系統註冊" : undefined;
+}
 
 function BasicSettingsTab() {
+  const [autostart, setAutostart] = useState<AutostartStatus | null>(null);
+
+  useEffect(() => {
+    invoke<AutostartStatus>("get_autostart_status")
+      .then(setAutostart)
+      .catch(() => setAutostart(null));
+  }, []);
+
   return (
     <div className="space-y-6">
       <div>
@@ -30,6 +55,7 @@ function BasicSettingsTab() {
           name={["basic", "auto_start"]}
           label="開機自動啟動"
           valuePropName="checked"
+          extra={autostartNote(autostart)}
         >
           <Switch />
         </Form.Item>
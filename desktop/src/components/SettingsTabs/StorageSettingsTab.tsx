@@ -1,19 +1,218 @@
-import { Form, Input, Switch, Slider, Button, FormInstance } from "antd";
+import { useEffect, useState } from "react";
+import {
+  Form,
+  Input,
+  InputNumber,
+  Switch,
+  Slider,
+  Select,
+  Button,
+  Table,
+  Alert,
+  Modal,
+  message,
+  FormInstance,
+} from "antd";
 import { open } from "@tauri-apps/plugin-dialog";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface RetentionReport {
+  items: { kind: "snapshot" | "screenshot"; size_bytes: number }[];
+  kept_latest: string[];
+}
+
+interface UsageStats {
+  bytes: number;
+  files: number;
+  oldest: string | null;
+  newest: string | null;
+}
+
+interface StorageUsage {
+  locations: { field: string; stats: UsageStats; free_bytes: number | null }[];
+  projects: {
+    project_id: string;
+    stats: UsageStats;
+    snapshots: { version: string }[];
+  }[];
+  unassigned_screenshots: UsageStats;
+}
+
+interface RecompressProgress {
+  processed: number;
+  total: number;
+  saved_bytes: number;
+}
+
+interface RecompressReport {
+  compress: boolean;
+  files: number;
+  saved_bytes: number;
+  errors: string[];
+}
+
+type StorageLocation = "snapshots" | "screenshots";
+
+interface MoveProgress {
+  location: StorageLocation;
+  target: string;
+  files_done: number;
+  files_total: number;
+}
+
+const MOVABLE_FIELDS: Record<string, StorageLocation> = {
+  snapshot_storage_path: "snapshots",
+  screenshot_storage_path: "screenshots",
+};
+
+const formatMegabytes = (bytes: number) => `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
 
 interface StorageSettingsTabProps {
   form: FormInstance;
 }
 
 function StorageSettingsTab({ form }: StorageSettingsTabProps) {
+  const [previewing, setPreviewing] = useState(false);
+  const [preview, setPreview] = useState<RetentionReport | null>(null);
+  const [scanning, setScanning] = useState(false);
+  const [usage, setUsage] = useState<StorageUsage | null>(null);
+  const [recompressProgress, setRecompressProgress] =
+    useState<RecompressProgress | null>(null);
+  const [pendingMove, setPendingMove] = useState<MoveProgress | null>(null);
+  const [moveProgress, setMoveProgress] = useState<MoveProgress | null>(null);
+
+  useEffect(() => {
+    // 上次中斷的搬移作業
+    invoke<MoveProgress | null>("get_pending_storage_move")
+      .then(setPendingMove)
+      .catch(() => setPendingMove(null));
+  }, []);
+
+  const moveStorage = async (location: StorageLocation, target: string) => {
+    setMoveProgress({ location, target, files_done: 0, files_total: 0 });
+    const unlisten = await listen<MoveProgress>("storage-move-progress", (event) =>
+      setMoveProgress(event.payload)
+    );
+    try {
+      const report = await invoke<{ files: number; cleanup_errors: string[] }>(
+        "move_storage",
+        { location, target }
+      );
+      const field =
+        location === "snapshots" ? "snapshot_storage_path" : "screenshot_storage_path";
+      form.setFieldValue(["storage", field], target);
+      setPendingMove(null);
+      if (report.cleanup_errors.length > 0) {
+        message.warning(
+          `已搬移 ${report.files} 個檔案，但原位置有 ${report.cleanup_errors.length} 個項目無法刪除`
+        );
+      } else {
+        message.success(`已搬移 ${report.files} 個檔案`);
+      }
+    } catch (error) {
+      message.error("搬移失敗: " + error);
+      setPendingMove(await invoke<MoveProgress | null>("get_pending_storage_move"));
+    } finally {
+      unlisten();
+      setMoveProgress(null);
+    }
+  };
+
+  const handleCancelMove = async () => {
+    try {
+      await invoke("cancel_storage_move");
+      if (!moveProgress) {
+        setPendingMove(null);
+      }
+    } catch (error) {
+      message.error("取消搬移失敗: " + error);
+    }
+  };
+
+  const handlePreviewRetention = async () => {
+    setPreviewing(true);
+    try {
+      const report = await invoke<RetentionReport>("preview_retention", {
+        storage: form.getFieldValue("storage"),
+      });
+      setPreview(report);
+    } catch (error) {
+      message.error("無法預覽清理結果: " + error);
+    } finally {
+      setPreviewing(false);
+    }
+  };
+
+  const handleScanUsage = async (refresh: boolean) => {
+    setScanning(true);
+    try {
+      setUsage(await invoke<StorageUsage>("get_storage_usage", { refresh }));
+    } catch (error) {
+      message.error("無法分析磁碟用量: " + error);
+    } finally {
+      setScanning(false);
+    }
+  };
+
+  // 依已儲存的壓縮設定重新壓縮（或解壓縮）現有快照
+  const handleRecompress = async () => {
+    setRecompressProgress({ processed: 0, total: 0, saved_bytes: 0 });
+    const unlisten = await listen<RecompressProgress>(
+      "snapshot-recompress-progress",
+      (event) => setRecompressProgress(event.payload)
+    );
+    try {
+      const report = await invoke<RecompressReport>("recompress_snapshots");
+      const summary = `已處理 ${report.files} 個檔案，節省 ${formatMegabytes(report.saved_bytes)}`;
+      if (report.errors.length > 0) {
+        message.warning(`${summary}，${report.errors.length} 個檔案失敗`);
+      } else {
+        message.success(summary);
+      }
+    } catch (error) {
+      message.error("重新壓縮失敗: " + error);
+    } finally {
+      unlisten();
+      setRecompressProgress(null);
+    }
+  };
+
+  const previewSummary = (report: RetentionReport) => {
+    const snapshots = report.items.filter((item) => item.kind === "snapshot").length;
+    const screenshots = report.items.length - snapshots;
+    const megabytes =
+      report.items.reduce((sum, item) => sum + item.size_bytes, 0) / (1024 * 1024);
+    return `將清理 ${snapshots} 個快照、${screenshots} 張截圖（約 ${megabytes.toFixed(1)} MB），保留 ${report.kept_latest.length} 個專案最新版本`;
+  };
+
   const handleBrowseDirectory = async (fieldName: string[]) => {
     const selected = await open({
       directory: true,
     });
 
-    if (selected && typeof selected === "string") {
+    if (!selected || typeof selected !== "string") {
+      return;
+    }
+
+    const location = MOVABLE_FIELDS[fieldName[1]];
+    const current = form.getFieldValue(fieldName);
+    if (!location || !current || current === selected) {
       form.setFieldValue(fieldName, selected);
+      return;
     }
+
+    // 路徑變更時詢問是否一併搬移現有資料
+    Modal.confirm({
+      title: "搬移現有資料？",
+      content: `要將 ${current} 的資料搬移到 ${selected} 嗎？搬移完成並驗證後才會切換路徑並刪除原資料。`,
+      okText: "搬移資料",
+      cancelText: "只變更路徑",
+      onOk: () => {
+        moveStorage(location, selected);
+      },
+      onCancel: () => form.setFieldValue(fieldName, selected),
+    });
   };
 
   return (
@@ -21,6 +220,41 @@ function StorageSettingsTab({ form }: StorageSettingsTabProps) {
       <div>
         <h3 className="text-lg font-semibold mb-4">儲存路徑</h3>
 
+        {moveProgress && (
+          <Alert
+            className="mb-4"
+            type="info"
+            message={`正在搬移資料到 ${moveProgress.target}（${moveProgress.files_done}/${moveProgress.files_total}）`}
+            action={
+              <Button size="small" onClick={handleCancelMove}>
+                暫停
+              </Button>
+            }
+          />
+        )}
+
+        {!moveProgress && pendingMove && (
+          <Alert
+            className="mb-4"
+            type="warning"
+            message={`有未完成的資料搬移：${pendingMove.target}（${pendingMove.files_done}/${pendingMove.files_total}）`}
+            action={
+              <>
+                <Button
+                  size="small"
+                  type="primary"
+                  onClick={() => moveStorage(pendingMove.location, pendingMove.target)}
+                >
+                  繼續
+                </Button>
+                <Button size="small" className="ml-2" onClick={handleCancelMove}>
+                  取消
+                </Button>
+              </>
+            }
+          />
+        )}
+
         <Form.Item
           name={["storage", "snapshot_storage_path"]}
           label="快照儲存路徑"
@@ -86,10 +320,19 @@ function StorageSettingsTab({ form }: StorageSettingsTabProps) {
           name={["storage", "enable_compression"]}
           label="啟用壓縮（節省空間）"
           valuePropName="checked"
+          extra="變更後請先儲存設定，再套用到現有快照"
         >
           <Switch />
         </Form.Item>
 
+        <Form.Item>
+          <Button onClick={handleRecompress} loading={recompressProgress !== null}>
+            {recompressProgress && recompressProgress.total > 0
+              ? `處理中 ${recompressProgress.processed}/${recompressProgress.total}`
+              : "套用到現有快照"}
+          </Button>
+        </Form.Item>
+
         <Form.Item
           name={["storage", "auto_cleanup"]}
           label="自動清理舊資料"
@@ -117,6 +360,97 @@ function StorageSettingsTab({ form }: StorageSettingsTabProps) {
             }}
           />
         </Form.Item>
+
+        <Form.Item
+          name={["storage", "retention_action"]}
+          label="過期資料處理方式"
+          extra={preview ? previewSummary(preview) : undefined}
+        >
+          <Select style={{ width: 200 }}>
+            <Select.Option value="delete">刪除</Select.Option>
+            <Select.Option value="archive">移至封存資料夾</Select.Option>
+          </Select>
+        </Form.Item>
+
+        <Form.Item>
+          <Button onClick={handlePreviewRetention} loading={previewing}>
+            預覽清理結果
+          </Button>
+        </Form.Item>
+
+        <Form.Item
+          name={["storage", "min_free_space_mb"]}
+          label="最低可用空間 (MB)"
+        >
+          <InputNumber min={0} step={100} style={{ width: 200 }} />
+        </Form.Item>
+      </div>
+
+      <div>
+        <h3 className="text-lg font-semibold mb-4">磁碟用量</h3>
+
+        <Form.Item>
+          <Button onClick={() => handleScanUsage(false)} loading={scanning}>
+            分析用量
+          </Button>
+          <Button
+            className="ml-2"
+            onClick={() => handleScanUsage(true)}
+            loading={scanning}
+          >
+            完整重新掃描
+          </Button>
+        </Form.Item>
+
+        {usage && (
+          <>
+            <p className="mb-2">
+              {usage.locations
+                .map(
+                  (location) =>
+                    `${location.field.replace("storage.", "")}: ${formatMegabytes(location.stats.bytes)}` +
+                    (location.free_bytes !== null
+                      ? `（剩餘 ${formatMegabytes(location.free_bytes)}）`
+                      : "")
+                )
+                .join("、")}
+            </p>
+            <Table
+              size="small"
+              rowKey="project_id"
+              pagination={false}
+              dataSource={usage.projects}
+              columns={[
+                { title: "專案", dataIndex: "project_id" },
+                {
+                  title: "版本數",
+                  render: (_, project) => project.snapshots.length,
+                },
+                {
+                  title: "檔案數",
+                  render: (_, project) => project.stats.files,
+                },
+                {
+                  title: "大小",
+                  render: (_, project) => formatMegabytes(project.stats.bytes),
+                },
+                {
+                  title: "最舊 / 最新",
+                  render: (_, project) =>
+                    [project.stats.oldest, project.stats.newest]
+                      .map((time) => (time ? new Date(time).toLocaleDateString() : "-"))
+                      .join(" / "),
+                },
+              ]}
+            />
+            {usage.unassigned_screenshots.files > 0 && (
+              <p className="mt-2 text-gray-500">
+                未歸屬的截圖: {usage.unassigned_screenshots.files} 個檔案，
+                {formatMegabytes(usage.unassigned_screenshots.bytes)}
+              </p>
+            )}
+          </>
+        )}
       </div>
     </div>
   );